@@ -0,0 +1,10 @@
+DROP INDEX IF EXISTS idx_books_format;
+
+ALTER TABLE reading_status
+    DROP COLUMN IF EXISTS current_location,
+    DROP COLUMN IF EXISTS listened_minutes;
+
+ALTER TABLE books
+    DROP COLUMN IF EXISTS total_locations,
+    DROP COLUMN IF EXISTS duration_minutes,
+    DROP COLUMN IF EXISTS format;
@@ -0,0 +1,13 @@
+-- Format of the owned edition and format-specific totals
+ALTER TABLE books
+    ADD COLUMN format VARCHAR(20) NOT NULL DEFAULT 'paperback'
+        CHECK (format IN ('hardcover', 'paperback', 'ebook', 'audiobook')),
+    ADD COLUMN duration_minutes INTEGER CHECK (duration_minutes > 0),
+    ADD COLUMN total_locations INTEGER CHECK (total_locations > 0);
+
+-- Format-specific progress markers
+ALTER TABLE reading_status
+    ADD COLUMN listened_minutes INTEGER CHECK (listened_minutes >= 0),
+    ADD COLUMN current_location INTEGER CHECK (current_location >= 0);
+
+CREATE INDEX idx_books_format ON books(format) WHERE deleted_at IS NULL;
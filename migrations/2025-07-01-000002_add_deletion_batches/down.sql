@@ -0,0 +1,13 @@
+DROP INDEX IF EXISTS idx_note_tags_deletion_batch;
+DROP INDEX IF EXISTS idx_book_tags_deletion_batch;
+DROP INDEX IF EXISTS idx_book_categories_deletion_batch;
+DROP INDEX IF EXISTS idx_notes_deletion_batch;
+DROP INDEX IF EXISTS idx_reading_status_deletion_batch;
+DROP INDEX IF EXISTS idx_books_deletion_batch;
+
+ALTER TABLE note_tags DROP COLUMN IF EXISTS deletion_batch_id;
+ALTER TABLE book_tags DROP COLUMN IF EXISTS deletion_batch_id;
+ALTER TABLE book_categories DROP COLUMN IF EXISTS deletion_batch_id;
+ALTER TABLE reading_notes DROP COLUMN IF EXISTS deletion_batch_id;
+ALTER TABLE reading_status DROP COLUMN IF EXISTS deletion_batch_id;
+ALTER TABLE books DROP COLUMN IF EXISTS deletion_batch_id;
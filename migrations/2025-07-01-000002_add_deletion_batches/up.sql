@@ -0,0 +1,43 @@
+-- Records which soft delete operation removed a row, so that restoring a
+-- book restores exactly the notes, status and associations deleted with it
+ALTER TABLE books ADD COLUMN deletion_batch_id UUID;
+ALTER TABLE reading_status ADD COLUMN deletion_batch_id UUID;
+ALTER TABLE reading_notes ADD COLUMN deletion_batch_id UUID;
+ALTER TABLE book_categories ADD COLUMN deletion_batch_id UUID;
+ALTER TABLE book_tags ADD COLUMN deletion_batch_id UUID;
+ALTER TABLE note_tags ADD COLUMN deletion_batch_id UUID;
+
+CREATE INDEX idx_books_deletion_batch ON books(deletion_batch_id) WHERE deletion_batch_id IS NOT NULL;
+CREATE INDEX idx_reading_status_deletion_batch ON reading_status(deletion_batch_id) WHERE deletion_batch_id IS NOT NULL;
+CREATE INDEX idx_notes_deletion_batch ON reading_notes(deletion_batch_id) WHERE deletion_batch_id IS NOT NULL;
+CREATE INDEX idx_book_categories_deletion_batch ON book_categories(deletion_batch_id) WHERE deletion_batch_id IS NOT NULL;
+CREATE INDEX idx_book_tags_deletion_batch ON book_tags(deletion_batch_id) WHERE deletion_batch_id IS NOT NULL;
+CREATE INDEX idx_note_tags_deletion_batch ON note_tags(deletion_batch_id) WHERE deletion_batch_id IS NOT NULL;
+
+-- Backfill: cascade existing book deletions to rows that were left active
+UPDATE books SET deletion_batch_id = uuid_generate_v4() WHERE deleted_at IS NOT NULL;
+
+UPDATE reading_notes n
+SET deleted_at = b.deleted_at, deletion_batch_id = b.deletion_batch_id
+FROM books b
+WHERE n.book_id = b.id AND b.deleted_at IS NOT NULL AND n.deleted_at IS NULL;
+
+UPDATE reading_status s
+SET deleted_at = b.deleted_at, deletion_batch_id = b.deletion_batch_id
+FROM books b
+WHERE s.book_id = b.id AND b.deleted_at IS NOT NULL AND s.deleted_at IS NULL;
+
+UPDATE book_tags bt
+SET deleted_at = b.deleted_at, deletion_batch_id = b.deletion_batch_id
+FROM books b
+WHERE bt.book_id = b.id AND b.deleted_at IS NOT NULL AND bt.deleted_at IS NULL;
+
+UPDATE book_categories bc
+SET deleted_at = b.deleted_at, deletion_batch_id = b.deletion_batch_id
+FROM books b
+WHERE bc.book_id = b.id AND b.deleted_at IS NOT NULL AND bc.deleted_at IS NULL;
+
+UPDATE note_tags nt
+SET deleted_at = n.deleted_at, deletion_batch_id = n.deletion_batch_id
+FROM reading_notes n
+WHERE nt.note_id = n.id AND n.deletion_batch_id IS NOT NULL AND nt.deleted_at IS NULL;
@@ -0,0 +1,2 @@
+DROP TRIGGER IF EXISTS update_loans_updated_at ON loans;
+DROP TABLE IF EXISTS loans;
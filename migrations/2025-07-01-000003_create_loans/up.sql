@@ -0,0 +1,27 @@
+-- Book loans to colleagues
+CREATE TABLE loans (
+    id BIGSERIAL PRIMARY KEY,
+    book_id BIGINT NOT NULL REFERENCES books(id) ON DELETE CASCADE,
+    borrower VARCHAR(100) NOT NULL,
+    lent_date DATE NOT NULL DEFAULT CURRENT_DATE,
+    due_date DATE,
+    returned_date DATE,
+    notes TEXT,
+    deleted_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    deletion_batch_id UUID,
+    CHECK (due_date IS NULL OR due_date >= lent_date),
+    CHECK (returned_date IS NULL OR returned_date >= lent_date)
+);
+
+-- A book can only be lent out once at a time
+CREATE UNIQUE INDEX idx_loans_book_active_unique ON loans(book_id)
+    WHERE returned_date IS NULL AND deleted_at IS NULL;
+
+CREATE INDEX idx_loans_book_id ON loans(book_id) WHERE deleted_at IS NULL;
+CREATE INDEX idx_loans_due_date ON loans(due_date) WHERE returned_date IS NULL AND deleted_at IS NULL;
+CREATE INDEX idx_loans_deletion_batch ON loans(deletion_batch_id) WHERE deletion_batch_id IS NOT NULL;
+
+CREATE TRIGGER update_loans_updated_at BEFORE UPDATE ON loans
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
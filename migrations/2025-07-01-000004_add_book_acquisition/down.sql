@@ -0,0 +1,9 @@
+DROP INDEX IF EXISTS idx_books_purchase_date;
+DROP INDEX IF EXISTS idx_books_acquisition_status;
+
+ALTER TABLE books
+    DROP COLUMN IF EXISTS purchase_place,
+    DROP COLUMN IF EXISTS currency,
+    DROP COLUMN IF EXISTS purchase_price,
+    DROP COLUMN IF EXISTS purchase_date,
+    DROP COLUMN IF EXISTS acquisition_status;
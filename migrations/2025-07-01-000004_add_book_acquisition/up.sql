@@ -0,0 +1,11 @@
+-- How a book was acquired and what it cost
+ALTER TABLE books
+    ADD COLUMN acquisition_status VARCHAR(20) NOT NULL DEFAULT 'owned'
+        CHECK (acquisition_status IN ('owned', 'wishlist', 'library')),
+    ADD COLUMN purchase_date DATE,
+    ADD COLUMN purchase_price DECIMAL(10,2) CHECK (purchase_price >= 0),
+    ADD COLUMN currency VARCHAR(3),
+    ADD COLUMN purchase_place VARCHAR(100);
+
+CREATE INDEX idx_books_acquisition_status ON books(acquisition_status) WHERE deleted_at IS NULL;
+CREATE INDEX idx_books_purchase_date ON books(purchase_date) WHERE deleted_at IS NULL AND purchase_date IS NOT NULL;
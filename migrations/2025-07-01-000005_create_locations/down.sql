@@ -0,0 +1,7 @@
+DROP INDEX IF EXISTS idx_books_location_id;
+
+ALTER TABLE books
+    DROP COLUMN IF EXISTS location_id;
+
+DROP TRIGGER IF EXISTS update_locations_updated_at ON locations;
+DROP TABLE IF EXISTS locations;
@@ -0,0 +1,22 @@
+-- Physical locations of the home library: room > bookcase > shelf
+CREATE TABLE locations (
+    id BIGSERIAL PRIMARY KEY,
+    name VARCHAR(100) NOT NULL,
+    kind VARCHAR(20) NOT NULL CHECK (kind IN ('room', 'bookcase', 'shelf')),
+    parent_id BIGINT REFERENCES locations(id) ON DELETE RESTRICT,
+    description TEXT,
+    deleted_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    CHECK ((kind = 'room') = (parent_id IS NULL))
+);
+
+CREATE INDEX idx_locations_parent_id ON locations(parent_id) WHERE deleted_at IS NULL;
+
+CREATE TRIGGER update_locations_updated_at BEFORE UPDATE ON locations
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+ALTER TABLE books
+    ADD COLUMN location_id BIGINT REFERENCES locations(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_books_location_id ON books(location_id) WHERE deleted_at IS NULL AND location_id IS NOT NULL;
@@ -0,0 +1,7 @@
+DROP INDEX IF EXISTS idx_books_custom_fields;
+
+ALTER TABLE books
+    DROP COLUMN IF EXISTS custom_fields;
+
+DROP TRIGGER IF EXISTS update_custom_field_definitions_updated_at ON custom_field_definitions;
+DROP TABLE IF EXISTS custom_field_definitions;
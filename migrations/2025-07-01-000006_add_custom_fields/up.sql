@@ -0,0 +1,20 @@
+-- User-defined fields on books; values live in books.custom_fields keyed by field key
+CREATE TABLE custom_field_definitions (
+    id BIGSERIAL PRIMARY KEY,
+    key VARCHAR(50) NOT NULL UNIQUE,
+    name VARCHAR(100) NOT NULL,
+    field_type VARCHAR(20) NOT NULL CHECK (field_type IN ('text', 'number', 'date', 'bool', 'enum')),
+    enum_values JSONB,
+    description TEXT,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    CHECK ((field_type = 'enum') = (enum_values IS NOT NULL))
+);
+
+CREATE TRIGGER update_custom_field_definitions_updated_at BEFORE UPDATE ON custom_field_definitions
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+ALTER TABLE books
+    ADD COLUMN custom_fields JSONB NOT NULL DEFAULT '{}'::jsonb;
+
+CREATE INDEX idx_books_custom_fields ON books USING GIN (custom_fields);
@@ -0,0 +1,12 @@
+-- Prior versions of notes, written before each update that changes them
+CREATE TABLE note_revisions (
+    id BIGSERIAL PRIMARY KEY,
+    note_id BIGINT NOT NULL REFERENCES reading_notes(id) ON DELETE CASCADE,
+    revision INTEGER NOT NULL,
+    title VARCHAR(200),
+    content TEXT NOT NULL,
+    note_type VARCHAR(20),
+    page_reference VARCHAR(50),
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (note_id, revision)
+);
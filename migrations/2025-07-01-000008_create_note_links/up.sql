@@ -0,0 +1,14 @@
+-- Wiki-style links from note content to other notes ([[note:123]]) and books ([[Book Title]])
+CREATE TABLE note_links (
+    id BIGSERIAL PRIMARY KEY,
+    source_note_id BIGINT NOT NULL REFERENCES reading_notes(id) ON DELETE CASCADE,
+    link_text VARCHAR(200) NOT NULL,
+    target_note_id BIGINT REFERENCES reading_notes(id) ON DELETE SET NULL,
+    target_book_id BIGINT REFERENCES books(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (source_note_id, link_text),
+    CHECK (target_note_id IS NULL OR target_book_id IS NULL)
+);
+
+CREATE INDEX idx_note_links_target_note_id ON note_links(target_note_id);
+CREATE INDEX idx_note_links_target_book_id ON note_links(target_book_id);
@@ -0,0 +1,8 @@
+DROP INDEX IF EXISTS idx_reading_notes_position;
+
+ALTER TABLE reading_notes
+    DROP COLUMN IF EXISTS percentage,
+    DROP COLUMN IF EXISTS kindle_location,
+    DROP COLUMN IF EXISTS chapter,
+    DROP COLUMN IF EXISTS page_end,
+    DROP COLUMN IF EXISTS page_start;
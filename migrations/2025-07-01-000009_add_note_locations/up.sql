@@ -0,0 +1,37 @@
+-- Structured note positions parsed from page_reference, used to sort notes in reading order
+ALTER TABLE reading_notes
+    ADD COLUMN page_start INTEGER CHECK (page_start >= 1),
+    ADD COLUMN page_end INTEGER CHECK (page_end >= page_start),
+    ADD COLUMN chapter INTEGER,
+    ADD COLUMN kindle_location INTEGER,
+    ADD COLUMN percentage INTEGER CHECK (percentage BETWEEN 0 AND 100);
+
+-- Backfill the common "p. 5", "Pages 10-20", "Loc 1234" and "45%" forms
+UPDATE reading_notes n
+SET page_start = m.parts[1]::INTEGER, page_end = m.parts[2]::INTEGER
+FROM (
+    SELECT id, regexp_match(page_reference, '^\s*(?:pp?|pg|pages?)?\.?\s*(\d{1,9})(?:\s*[-–—]\s*(\d{1,9}))?\s*$', 'i') AS parts
+    FROM reading_notes
+) m
+WHERE n.id = m.id
+    AND m.parts IS NOT NULL
+    AND m.parts[1]::INTEGER >= 1
+    AND (m.parts[2] IS NULL OR m.parts[2]::INTEGER >= m.parts[1]::INTEGER);
+
+UPDATE reading_notes n
+SET kindle_location = m.parts[1]::INTEGER
+FROM (
+    SELECT id, regexp_match(page_reference, '^\s*(?:loc|location)\.?\s*(\d{1,9})', 'i') AS parts
+    FROM reading_notes
+) m
+WHERE n.id = m.id AND m.parts IS NOT NULL;
+
+UPDATE reading_notes n
+SET percentage = m.parts[1]::INTEGER
+FROM (
+    SELECT id, regexp_match(page_reference, '^\s*(\d{1,3})\s*%\s*$') AS parts
+    FROM reading_notes
+) m
+WHERE n.id = m.id AND m.parts IS NOT NULL AND m.parts[1]::INTEGER <= 100;
+
+CREATE INDEX idx_reading_notes_position ON reading_notes(book_id, page_start, kindle_location);
@@ -0,0 +1,5 @@
+ALTER TABLE reading_notes
+    DROP CONSTRAINT IF EXISTS fk_reading_notes_note_type;
+
+DROP TRIGGER IF EXISTS update_note_types_updated_at ON note_types;
+DROP TABLE IF EXISTS note_types;
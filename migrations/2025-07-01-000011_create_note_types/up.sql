@@ -0,0 +1,29 @@
+-- User-definable note types; reading_notes.note_type references the key
+CREATE TABLE note_types (
+    id BIGSERIAL PRIMARY KEY,
+    key VARCHAR(20) NOT NULL UNIQUE,
+    name VARCHAR(50) NOT NULL,
+    icon VARCHAR(50),
+    color VARCHAR(7) CHECK (color ~ '^#[0-9a-fA-F]{6}$'),
+    is_builtin BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TRIGGER update_note_types_updated_at BEFORE UPDATE ON note_types
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+INSERT INTO note_types (key, name, icon, color, is_builtin) VALUES
+    ('quote', 'Quote', 'quote', '#6366f1', TRUE),
+    ('summary', 'Summary', 'list', '#10b981', TRUE),
+    ('thought', 'Thought', 'lightbulb', '#f59e0b', TRUE),
+    ('general', 'General', 'note', '#6b7280', TRUE);
+
+-- Unknown values used to be read back as 'general'
+UPDATE reading_notes
+SET note_type = 'general'
+WHERE note_type NOT IN (SELECT key FROM note_types);
+
+ALTER TABLE reading_notes
+    ADD CONSTRAINT fk_reading_notes_note_type
+    FOREIGN KEY (note_type) REFERENCES note_types(key) ON DELETE RESTRICT;
@@ -0,0 +1,2 @@
+DROP TRIGGER IF EXISTS update_note_reviews_updated_at ON note_reviews;
+DROP TABLE IF EXISTS note_reviews;
@@ -0,0 +1,17 @@
+-- Spaced-repetition (SM-2) schedule per note; notes without a row are new and due
+CREATE TABLE note_reviews (
+    note_id BIGINT PRIMARY KEY REFERENCES reading_notes(id) ON DELETE CASCADE,
+    ease_factor DOUBLE PRECISION NOT NULL DEFAULT 2.5 CHECK (ease_factor >= 1.3),
+    interval_days INTEGER NOT NULL DEFAULT 0 CHECK (interval_days >= 0),
+    repetitions INTEGER NOT NULL DEFAULT 0 CHECK (repetitions >= 0),
+    due_date DATE NOT NULL,
+    last_grade VARCHAR(10) CHECK (last_grade IN ('again', 'hard', 'good', 'easy')),
+    last_reviewed_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_note_reviews_due_date ON note_reviews(due_date);
+
+CREATE TRIGGER update_note_reviews_updated_at BEFORE UPDATE ON note_reviews
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS digest_notes;
+DROP TABLE IF EXISTS digests;
@@ -0,0 +1,14 @@
+-- Daily highlight digests; the picks are stored so a day's digest never changes
+CREATE TABLE digests (
+    digest_date DATE PRIMARY KEY,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE digest_notes (
+    digest_date DATE NOT NULL REFERENCES digests(digest_date) ON DELETE CASCADE,
+    note_id BIGINT NOT NULL REFERENCES reading_notes(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    PRIMARY KEY (digest_date, note_id)
+);
+
+CREATE INDEX idx_digest_notes_note_id ON digest_notes(note_id, digest_date);
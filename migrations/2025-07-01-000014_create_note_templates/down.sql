@@ -0,0 +1,2 @@
+DROP TRIGGER IF EXISTS update_note_templates_updated_at ON note_templates;
+DROP TABLE IF EXISTS note_templates;
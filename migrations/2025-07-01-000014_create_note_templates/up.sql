@@ -0,0 +1,14 @@
+-- Reusable note templates with {{placeholders}} filled in from the book
+CREATE TABLE note_templates (
+    id BIGSERIAL PRIMARY KEY,
+    name VARCHAR(100) NOT NULL UNIQUE,
+    title VARCHAR(200),
+    content TEXT NOT NULL,
+    note_type VARCHAR(20) REFERENCES note_types(key) ON DELETE RESTRICT,
+    tags JSONB NOT NULL DEFAULT '[]'::jsonb,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TRIGGER update_note_templates_updated_at BEFORE UPDATE ON note_templates
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
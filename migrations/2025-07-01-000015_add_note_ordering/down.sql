@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS idx_reading_notes_manual_order;
+
+ALTER TABLE reading_notes
+    DROP COLUMN IF EXISTS is_pinned,
+    DROP COLUMN IF EXISTS sort_order;
@@ -0,0 +1,6 @@
+-- Manual arrangement of a book's notes into an outline; pinned notes come first
+ALTER TABLE reading_notes
+    ADD COLUMN sort_order INTEGER CHECK (sort_order >= 0),
+    ADD COLUMN is_pinned BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE INDEX idx_reading_notes_manual_order ON reading_notes(book_id, is_pinned DESC, sort_order);
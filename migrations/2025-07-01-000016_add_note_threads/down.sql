@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS idx_reading_notes_parent_id;
+
+ALTER TABLE reading_notes
+    DROP CONSTRAINT IF EXISTS chk_reading_notes_parent,
+    DROP COLUMN IF EXISTS parent_id;
@@ -0,0 +1,6 @@
+-- Replies: a note can respond to another note of the same book
+ALTER TABLE reading_notes
+    ADD COLUMN parent_id BIGINT REFERENCES reading_notes(id) ON DELETE SET NULL,
+    ADD CONSTRAINT chk_reading_notes_parent CHECK (parent_id <> id);
+
+CREATE INDEX idx_reading_notes_parent_id ON reading_notes(parent_id);
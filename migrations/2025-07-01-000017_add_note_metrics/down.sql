@@ -0,0 +1,4 @@
+ALTER TABLE reading_notes
+    DROP COLUMN IF EXISTS reading_time_seconds,
+    DROP COLUMN IF EXISTS word_count,
+    DROP COLUMN IF EXISTS char_count;
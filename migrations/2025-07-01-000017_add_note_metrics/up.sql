@@ -0,0 +1,23 @@
+-- Length and estimated reading time of each note, computed by the application on save
+ALTER TABLE reading_notes
+    ADD COLUMN char_count INTEGER NOT NULL DEFAULT 0 CHECK (char_count >= 0),
+    ADD COLUMN word_count INTEGER NOT NULL DEFAULT 0 CHECK (word_count >= 0),
+    ADD COLUMN reading_time_seconds INTEGER NOT NULL DEFAULT 0 CHECK (reading_time_seconds >= 0);
+
+-- Backfill from the raw Markdown; notes get exact counts the next time they are saved.
+-- Chinese and Japanese characters count as one word each, at 400 characters per minute;
+-- other words are read at 230 words per minute.
+UPDATE reading_notes n
+SET char_count = m.char_count,
+    word_count = m.words + m.cjk_chars,
+    reading_time_seconds = CEIL(m.words * 60 / 230.0) + CEIL(m.cjk_chars * 60 / 400.0)
+FROM (
+    SELECT id,
+        char_length(regexp_replace(content, '\s', '', 'g')) AS char_count,
+        (SELECT COUNT(*) FROM regexp_matches(content, '[぀-ヿ㐀-䶿一-鿿豈-﫿ｦ-ﾟ]', 'g')) AS cjk_chars,
+        (SELECT COUNT(*) FROM regexp_matches(
+            regexp_replace(content, '[぀-ヿ㐀-䶿一-鿿豈-﫿ｦ-ﾟ]', ' ', 'g'),
+            '[[:alnum:]]+([''’][[:alnum:]]+)*', 'g')) AS words
+    FROM reading_notes
+) m
+WHERE n.id = m.id;
@@ -0,0 +1,13 @@
+-- Read-only links to a note or to all notes of a book, served at /s/{token}
+CREATE TABLE share_links (
+    id BIGSERIAL PRIMARY KEY,
+    token VARCHAR(64) NOT NULL UNIQUE,
+    note_id BIGINT REFERENCES reading_notes(id) ON DELETE CASCADE,
+    book_id BIGINT REFERENCES books(id) ON DELETE CASCADE,
+    expires_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    CHECK ((note_id IS NULL) <> (book_id IS NULL))
+);
+
+CREATE INDEX idx_share_links_note_id ON share_links(note_id) WHERE note_id IS NOT NULL;
+CREATE INDEX idx_share_links_book_id ON share_links(book_id) WHERE book_id IS NOT NULL;
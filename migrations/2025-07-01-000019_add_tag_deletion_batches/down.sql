@@ -0,0 +1,14 @@
+-- Associations trashed with a tag were left active before
+UPDATE note_tags nt
+SET deleted_at = NULL, deletion_batch_id = NULL
+FROM tags t
+WHERE nt.deletion_batch_id = t.deletion_batch_id;
+
+UPDATE book_tags bt
+SET deleted_at = NULL, deletion_batch_id = NULL
+FROM tags t
+WHERE bt.deletion_batch_id = t.deletion_batch_id;
+
+DROP INDEX IF EXISTS idx_tags_deletion_batch;
+
+ALTER TABLE tags DROP COLUMN IF EXISTS deletion_batch_id;
@@ -0,0 +1,18 @@
+-- Deleting a tag trashes its note and book associations in the same batch,
+-- so that restoring the tag brings back exactly those associations
+ALTER TABLE tags ADD COLUMN deletion_batch_id UUID;
+
+CREATE INDEX idx_tags_deletion_batch ON tags(deletion_batch_id) WHERE deletion_batch_id IS NOT NULL;
+
+-- Backfill: cascade existing tag deletions to associations that were left active
+UPDATE tags SET deletion_batch_id = uuid_generate_v4() WHERE deleted_at IS NOT NULL;
+
+UPDATE note_tags nt
+SET deleted_at = t.deleted_at, deletion_batch_id = t.deletion_batch_id
+FROM tags t
+WHERE nt.tag_id = t.id AND t.deleted_at IS NOT NULL AND nt.deleted_at IS NULL;
+
+UPDATE book_tags bt
+SET deleted_at = t.deleted_at, deletion_batch_id = t.deletion_batch_id
+FROM tags t
+WHERE bt.tag_id = t.id AND t.deleted_at IS NOT NULL AND bt.deleted_at IS NULL;
@@ -9,6 +9,7 @@ diesel::table! {
         category_id -> Int8,
         deleted_at -> Nullable<Timestamptz>,
         created_at -> Nullable<Timestamptz>,
+        deletion_batch_id -> Nullable<Uuid>,
     }
 }
 
@@ -21,6 +22,7 @@ diesel::table! {
         tag_id -> Int8,
         deleted_at -> Nullable<Timestamptz>,
         created_at -> Nullable<Timestamptz>,
+        deletion_batch_id -> Nullable<Uuid>,
     }
 }
 
@@ -46,6 +48,21 @@ diesel::table! {
         deleted_at -> Nullable<Timestamptz>,
         created_at -> Nullable<Timestamptz>,
         updated_at -> Nullable<Timestamptz>,
+        #[max_length = 20]
+        format -> Varchar,
+        duration_minutes -> Nullable<Int4>,
+        total_locations -> Nullable<Int4>,
+        deletion_batch_id -> Nullable<Uuid>,
+        #[max_length = 20]
+        acquisition_status -> Varchar,
+        purchase_date -> Nullable<Date>,
+        purchase_price -> Nullable<Numeric>,
+        #[max_length = 3]
+        currency -> Nullable<Varchar>,
+        #[max_length = 100]
+        purchase_place -> Nullable<Varchar>,
+        location_id -> Nullable<Int8>,
+        custom_fields -> Jsonb,
     }
 }
 
@@ -70,6 +87,156 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    use diesel::sql_types::*;
+    use diesel::pg::sql_types::*;
+
+    custom_field_definitions (id) {
+        id -> Int8,
+        #[max_length = 50]
+        key -> Varchar,
+        #[max_length = 100]
+        name -> Varchar,
+        #[max_length = 20]
+        field_type -> Varchar,
+        enum_values -> Nullable<Jsonb>,
+        description -> Nullable<Text>,
+        created_at -> Nullable<Timestamptz>,
+        updated_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use diesel::pg::sql_types::*;
+
+    digest_notes (digest_date, note_id) {
+        digest_date -> Date,
+        note_id -> Int8,
+        position -> Int4,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use diesel::pg::sql_types::*;
+
+    digests (digest_date) {
+        digest_date -> Date,
+        created_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use diesel::pg::sql_types::*;
+
+    loans (id) {
+        id -> Int8,
+        book_id -> Int8,
+        #[max_length = 100]
+        borrower -> Varchar,
+        lent_date -> Date,
+        due_date -> Nullable<Date>,
+        returned_date -> Nullable<Date>,
+        notes -> Nullable<Text>,
+        deleted_at -> Nullable<Timestamptz>,
+        created_at -> Nullable<Timestamptz>,
+        updated_at -> Nullable<Timestamptz>,
+        deletion_batch_id -> Nullable<Uuid>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use diesel::pg::sql_types::*;
+
+    locations (id) {
+        id -> Int8,
+        #[max_length = 100]
+        name -> Varchar,
+        #[max_length = 20]
+        kind -> Varchar,
+        parent_id -> Nullable<Int8>,
+        description -> Nullable<Text>,
+        deleted_at -> Nullable<Timestamptz>,
+        created_at -> Nullable<Timestamptz>,
+        updated_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use diesel::pg::sql_types::*;
+
+    note_attachments (id) {
+        id -> Int8,
+        note_id -> Int8,
+        #[max_length = 255]
+        filename -> Varchar,
+        #[max_length = 50]
+        content_type -> Varchar,
+        size_bytes -> Int8,
+        #[max_length = 64]
+        checksum -> Bpchar,
+        #[max_length = 100]
+        storage_key -> Varchar,
+        created_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use diesel::pg::sql_types::*;
+
+    note_links (id) {
+        id -> Int8,
+        source_note_id -> Int8,
+        #[max_length = 200]
+        link_text -> Varchar,
+        target_note_id -> Nullable<Int8>,
+        target_book_id -> Nullable<Int8>,
+        created_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use diesel::pg::sql_types::*;
+
+    note_reviews (note_id) {
+        note_id -> Int8,
+        ease_factor -> Float8,
+        interval_days -> Int4,
+        repetitions -> Int4,
+        due_date -> Date,
+        #[max_length = 10]
+        last_grade -> Nullable<Varchar>,
+        last_reviewed_at -> Nullable<Timestamptz>,
+        created_at -> Nullable<Timestamptz>,
+        updated_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use diesel::pg::sql_types::*;
+
+    note_revisions (id) {
+        id -> Int8,
+        note_id -> Int8,
+        revision -> Int4,
+        #[max_length = 200]
+        title -> Nullable<Varchar>,
+        content -> Text,
+        #[max_length = 20]
+        note_type -> Nullable<Varchar>,
+        #[max_length = 50]
+        page_reference -> Nullable<Varchar>,
+        created_at -> Nullable<Timestamptz>,
+    }
+}
+
 diesel::table! {
     use diesel::sql_types::*;
     use diesel::pg::sql_types::*;
@@ -79,6 +246,46 @@ diesel::table! {
         tag_id -> Int8,
         deleted_at -> Nullable<Timestamptz>,
         created_at -> Nullable<Timestamptz>,
+        deletion_batch_id -> Nullable<Uuid>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use diesel::pg::sql_types::*;
+
+    note_templates (id) {
+        id -> Int8,
+        #[max_length = 100]
+        name -> Varchar,
+        #[max_length = 200]
+        title -> Nullable<Varchar>,
+        content -> Text,
+        #[max_length = 20]
+        note_type -> Nullable<Varchar>,
+        tags -> Jsonb,
+        created_at -> Nullable<Timestamptz>,
+        updated_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use diesel::pg::sql_types::*;
+
+    note_types (id) {
+        id -> Int8,
+        #[max_length = 20]
+        key -> Varchar,
+        #[max_length = 50]
+        name -> Varchar,
+        #[max_length = 50]
+        icon -> Nullable<Varchar>,
+        #[max_length = 7]
+        color -> Nullable<Varchar>,
+        is_builtin -> Bool,
+        created_at -> Nullable<Timestamptz>,
+        updated_at -> Nullable<Timestamptz>,
     }
 }
 
@@ -100,6 +307,18 @@ diesel::table! {
         deleted_at -> Nullable<Timestamptz>,
         created_at -> Nullable<Timestamptz>,
         updated_at -> Nullable<Timestamptz>,
+        deletion_batch_id -> Nullable<Uuid>,
+        page_start -> Nullable<Int4>,
+        page_end -> Nullable<Int4>,
+        chapter -> Nullable<Int4>,
+        kindle_location -> Nullable<Int4>,
+        percentage -> Nullable<Int4>,
+        sort_order -> Nullable<Int4>,
+        is_pinned -> Bool,
+        parent_id -> Nullable<Int8>,
+        char_count -> Int4,
+        word_count -> Int4,
+        reading_time_seconds -> Int4,
     }
 }
 
@@ -120,6 +339,24 @@ diesel::table! {
         deleted_at -> Nullable<Timestamptz>,
         created_at -> Nullable<Timestamptz>,
         updated_at -> Nullable<Timestamptz>,
+        listened_minutes -> Nullable<Int4>,
+        current_location -> Nullable<Int4>,
+        deletion_batch_id -> Nullable<Uuid>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use diesel::pg::sql_types::*;
+
+    share_links (id) {
+        id -> Int8,
+        #[max_length = 64]
+        token -> Varchar,
+        note_id -> Nullable<Int8>,
+        book_id -> Nullable<Int8>,
+        expires_at -> Nullable<Timestamptz>,
+        created_at -> Nullable<Timestamptz>,
     }
 }
 
@@ -136,6 +373,7 @@ diesel::table! {
         usage_count -> Nullable<Int4>,
         deleted_at -> Nullable<Timestamptz>,
         created_at -> Nullable<Timestamptz>,
+        deletion_batch_id -> Nullable<Uuid>,
     }
 }
 
@@ -143,18 +381,40 @@ diesel::joinable!(book_categories -> books (book_id));
 diesel::joinable!(book_categories -> categories (category_id));
 diesel::joinable!(book_tags -> books (book_id));
 diesel::joinable!(book_tags -> tags (tag_id));
+diesel::joinable!(books -> locations (location_id));
+diesel::joinable!(digest_notes -> digests (digest_date));
+diesel::joinable!(digest_notes -> reading_notes (note_id));
+diesel::joinable!(loans -> books (book_id));
+diesel::joinable!(note_attachments -> reading_notes (note_id));
+diesel::joinable!(note_links -> books (target_book_id));
+diesel::joinable!(note_reviews -> reading_notes (note_id));
+diesel::joinable!(note_revisions -> reading_notes (note_id));
 diesel::joinable!(note_tags -> reading_notes (note_id));
 diesel::joinable!(note_tags -> tags (tag_id));
 diesel::joinable!(reading_notes -> books (book_id));
 diesel::joinable!(reading_status -> books (book_id));
+diesel::joinable!(share_links -> books (book_id));
+diesel::joinable!(share_links -> reading_notes (note_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
     book_categories,
     book_tags,
     books,
     categories,
+    custom_field_definitions,
+    digest_notes,
+    digests,
+    loans,
+    locations,
+    note_attachments,
+    note_links,
+    note_reviews,
+    note_revisions,
     note_tags,
+    note_templates,
+    note_types,
     reading_notes,
     reading_status,
+    share_links,
     tags,
 );
@@ -29,6 +29,10 @@ pub enum AppError {
     #[error("Validation error: {0}")]
     ValidationError(String),
     
+    /// Request conflicts with the current state of a resource
+    #[error("Conflict: {0}")]
+    Conflict(String),
+    
     /// Database operation error
     #[error("Database error: {0}")]
     DatabaseError(#[from] diesel::result::Error),
@@ -43,7 +47,7 @@ pub enum AppError {
 }
 
 /// Error response structure sent to clients
-#[derive(Serialize, ToSchema)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ErrorResponse {
     /// Error type/code for client handling
     #[schema(example = "VALIDATION_ERROR")]
@@ -53,16 +57,19 @@ pub struct ErrorResponse {
     pub message: String,
 }
 
+impl From<&AppError> for ErrorResponse {
+    fn from(error: &AppError) -> Self {
+        ErrorResponse {
+            error: error.error_type(),
+            message: error.to_string(),
+        }
+    }
+}
+
 impl ResponseError for AppError {
     /// Converts the error into an HTTP response
     fn error_response(&self) -> HttpResponse {
-        let status_code = self.status_code();
-        let error_response = ErrorResponse {
-            error: self.error_type(),
-            message: self.to_string(),
-        };
-        
-        HttpResponse::build(status_code).json(error_response)
+        HttpResponse::build(self.status_code()).json(ErrorResponse::from(self))
     }
     
     /// Maps error variants to HTTP status codes
@@ -72,6 +79,7 @@ impl ResponseError for AppError {
             AppError::BadRequest(_) => StatusCode::BAD_REQUEST,
             AppError::NotFound(_) => StatusCode::NOT_FOUND,
             AppError::ValidationError(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            AppError::Conflict(_) => StatusCode::CONFLICT,
             AppError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
             AppError::EnvVarError(_) => StatusCode::INTERNAL_SERVER_ERROR,
             AppError::JsonError(_) => StatusCode::BAD_REQUEST,
@@ -87,6 +95,7 @@ impl AppError {
             AppError::BadRequest(_) => "BAD_REQUEST",
             AppError::NotFound(_) => "NOT_FOUND",
             AppError::ValidationError(_) => "VALIDATION_ERROR",
+            AppError::Conflict(_) => "CONFLICT",
             AppError::DatabaseError(_) => "DATABASE_ERROR",
             AppError::EnvVarError(_) => "CONFIGURATION_ERROR",
             AppError::JsonError(_) => "JSON_ERROR",
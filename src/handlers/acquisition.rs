@@ -0,0 +1,126 @@
+//! Book acquisition HTTP handlers
+//!
+//! Provides endpoints for the wishlist, moving wishlist books to owned
+//! and reporting purchase spending
+
+use actix_web::{web, HttpResponse, Result};
+use serde::Deserialize;
+use utoipa::IntoParams;
+use crate::db::DbPool;
+use crate::errors::AppError;
+use crate::models::acquisition::{self, AcquireBookRequest, SpendingPeriod};
+use crate::models::book::{AcquisitionStatus, Book, BookResponse, BookListResponse};
+
+/// Query parameters for the wishlist
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct WishlistQuery {
+    /// Page number (1-based, default: 1)
+    #[param(example = 1)]
+    pub page: Option<u32>,
+    /// Items per page (default: 20, max: 100)
+    #[param(example = 20)]
+    pub per_page: Option<u32>,
+}
+
+/// Query parameters for the spending report
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct SpendingQuery {
+    /// Grouping period: month or year (default: month)
+    #[param(example = "month")]
+    pub period: Option<SpendingPeriod>,
+    /// Only include purchases made in this year
+    #[param(example = 2024)]
+    pub year: Option<i32>,
+}
+
+/// Path parameters for acquisition operations
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct AcquirePath {
+    /// Book ID
+    #[param(example = 1)]
+    pub id: i64,
+}
+
+/// Lists books on the wishlist
+#[utoipa::path(
+    get,
+    path = "/api/books/wishlist",
+    params(WishlistQuery),
+    responses(
+        (status = 200, description = "Wishlist retrieved successfully", body = BookListResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Acquisition"
+)]
+pub async fn list_wishlist(
+    pool: web::Data<DbPool>,
+    query: web::Query<WishlistQuery>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+
+    let page = query.page.unwrap_or(1).max(1);
+    let per_page = query.per_page.unwrap_or(20).clamp(1, 100);
+
+    let (books, total) = Book::list_by_acquisition_status(&mut conn, AcquisitionStatus::Wishlist, page, per_page)?;
+    let total_pages = ((total as f64) / (per_page as f64)).ceil() as u32;
+
+    let response = BookListResponse {
+        books: books.into_iter().map(BookResponse::from).collect(),
+        total,
+        page,
+        per_page,
+        total_pages,
+    };
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Moves a wishlist book to owned and records the purchase
+#[utoipa::path(
+    post,
+    path = "/api/books/{id}/acquire",
+    params(AcquirePath),
+    request_body = AcquireBookRequest,
+    responses(
+        (status = 200, description = "Book acquired successfully", body = BookResponse),
+        (status = 404, description = "Book not found", body = ErrorResponse),
+        (status = 409, description = "Book is not on the wishlist", body = ErrorResponse),
+        (status = 422, description = "Validation error", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Acquisition"
+)]
+pub async fn acquire_book(
+    pool: web::Data<DbPool>,
+    path: web::Path<AcquirePath>,
+    body: Option<web::Json<AcquireBookRequest>>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+
+    let request = body.map(|b| b.into_inner()).unwrap_or_default();
+    let book = Book::acquire(&mut conn, path.id, request)?;
+
+    Ok(HttpResponse::Ok().json(BookResponse::from(book)))
+}
+
+/// Reports purchase spending per month or year and currency
+#[utoipa::path(
+    get,
+    path = "/api/books/spending",
+    params(SpendingQuery),
+    responses(
+        (status = 200, description = "Spending report", body = [SpendingEntry]),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Acquisition"
+)]
+pub async fn get_spending(
+    pool: web::Data<DbPool>,
+    query: web::Query<SpendingQuery>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+
+    let report = acquisition::spending_report(&mut conn, query.period.unwrap_or_default(), query.year)?;
+
+    Ok(HttpResponse::Ok().json(report))
+}
@@ -0,0 +1,160 @@
+//! Note attachment HTTP handlers
+//!
+//! Provides endpoints for uploading images to notes as multipart form
+//! data, downloading them and removing them
+
+use actix_multipart::Multipart;
+use actix_web::{http::header, web, HttpResponse, Result};
+use futures_util::TryStreamExt;
+use serde::Deserialize;
+use utoipa::IntoParams;
+use crate::db::DbPool;
+use crate::errors::AppError;
+use crate::models::attachment::{
+    AttachmentResponse, AttachmentUpload, NoteAttachment, MAX_ATTACHMENTS_PER_UPLOAD, MAX_ATTACHMENT_SIZE,
+    MAX_UPLOAD_SIZE,
+};
+use crate::models::note::ReadingNote;
+
+/// Path parameters for a note's attachments
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct NoteAttachmentsPath {
+    /// Note ID
+    #[param(example = 1)]
+    pub id: i64,
+}
+
+/// Path parameters for attachment operations
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct AttachmentPath {
+    /// Attachment ID
+    #[param(example = 1)]
+    pub id: i64,
+}
+
+/// Uploads image attachments to a note
+#[utoipa::path(
+    post,
+    path = "/api/notes/{id}/attachments",
+    params(NoteAttachmentsPath),
+    request_body(content = AttachmentUploadForm, content_type = "multipart/form-data"),
+    responses(
+        (status = 201, description = "Attachments stored successfully", body = [AttachmentResponse]),
+        (status = 400, description = "Malformed multipart body or no file", body = ErrorResponse),
+        (status = 404, description = "Note not found", body = ErrorResponse),
+        (status = 422, description = "Not a supported image, too large or too many files", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Notes"
+)]
+pub async fn upload_attachments(
+    pool: web::Data<DbPool>,
+    path: web::Path<NoteAttachmentsPath>,
+    mut payload: Multipart,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+
+    // Fail fast before reading the body
+    ReadingNote::find_by_id(&mut conn, path.id)?;
+
+    let mut uploads = Vec::new();
+    let mut total_size = 0;
+    while let Some(mut field) = payload.try_next().await.map_err(|e| AppError::BadRequest(e.to_string()))? {
+        // Only file parts are attachments
+        let Some(filename) = field
+            .content_disposition()
+            .and_then(|cd| cd.get_filename())
+            .map(|name| name.to_string())
+        else {
+            continue;
+        };
+
+        if uploads.len() == MAX_ATTACHMENTS_PER_UPLOAD {
+            return Err(AppError::ValidationError(format!(
+                "Cannot upload more than {} files at once",
+                MAX_ATTACHMENTS_PER_UPLOAD
+            )));
+        }
+
+        let mut bytes = Vec::new();
+        while let Some(chunk) = field.try_next().await.map_err(|e| AppError::BadRequest(e.to_string()))? {
+            if bytes.len() + chunk.len() > MAX_ATTACHMENT_SIZE {
+                return Err(AppError::ValidationError(format!(
+                    "File '{}' exceeds the {} MB limit",
+                    filename,
+                    MAX_ATTACHMENT_SIZE / (1024 * 1024)
+                )));
+            }
+            total_size += chunk.len();
+            if total_size > MAX_UPLOAD_SIZE {
+                return Err(AppError::ValidationError(format!(
+                    "Upload exceeds the {} MB total limit",
+                    MAX_UPLOAD_SIZE / (1024 * 1024)
+                )));
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+        uploads.push(AttachmentUpload { filename, bytes });
+    }
+
+    if uploads.is_empty() {
+        return Err(AppError::BadRequest("No file found in multipart body".to_string()));
+    }
+
+    let responses: Vec<AttachmentResponse> = NoteAttachment::create_all(&mut conn, path.id, uploads)?
+        .into_iter()
+        .map(AttachmentResponse::from)
+        .collect();
+
+    Ok(HttpResponse::Created().json(responses))
+}
+
+/// Downloads an attachment
+#[utoipa::path(
+    get,
+    path = "/api/attachments/{id}",
+    params(AttachmentPath),
+    responses(
+        (status = 200, description = "Image contents", content_type = "image/*"),
+        (status = 404, description = "Attachment not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Notes"
+)]
+pub async fn get_attachment(
+    pool: web::Data<DbPool>,
+    path: web::Path<AttachmentPath>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+
+    let attachment = NoteAttachment::find_by_id(&mut conn, path.id)?;
+    let bytes = attachment.read()?;
+
+    Ok(HttpResponse::Ok()
+        .content_type(attachment.content_type.as_str())
+        .insert_header((header::ETAG, format!("\"{}\"", attachment.checksum.trim_end())))
+        .body(bytes))
+}
+
+/// Deletes an attachment and its stored file
+#[utoipa::path(
+    delete,
+    path = "/api/attachments/{id}",
+    params(AttachmentPath),
+    responses(
+        (status = 204, description = "Attachment deleted successfully"),
+        (status = 404, description = "Attachment not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Notes"
+)]
+pub async fn delete_attachment(
+    pool: web::Data<DbPool>,
+    path: web::Path<AttachmentPath>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+
+    NoteAttachment::delete(&mut conn, path.id)?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
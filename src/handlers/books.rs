@@ -2,12 +2,19 @@
 //! 
 //! Provides RESTful API endpoints for book CRUD operations
 
-use actix_web::{web, HttpResponse, Result};
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use bigdecimal::Signed;
+use diesel::PgConnection;
 use serde::Deserialize;
 use utoipa::IntoParams;
 use crate::db::DbPool;
 use crate::errors::AppError;
-use crate::models::book::{Book, CreateBookRequest, UpdateBook, BookResponse, BookListResponse};
+use crate::models::acquisition::{validate_purchase, MAX_PURCHASE_PLACE_LENGTH};
+use crate::models::loan::Loan;
+use crate::models::location::{attach_location_paths, validate_book_location};
+use crate::models::book::{Book, BookFormat, AcquisitionStatus, BookChangeset, CreateBookRequest, UpdateBook, PatchBookRequest, BookResponse, BookListResponse};
+use crate::utils::patch::parse_merge_patch;
+use crate::utils::validation::optional_text;
 
 /// Query parameters for book listing
 #[derive(Debug, Deserialize, IntoParams)]
@@ -21,6 +28,15 @@ pub struct BookListQuery {
     /// Search query for title/author
     #[param(example = "rust")]
     pub search: Option<String>,
+    /// Filter by a custom field value, as `key:value`
+    #[param(example = "condition:good")]
+    pub custom_field: Option<String>,
+    /// Sort by a custom field key (books without a value come last)
+    #[param(example = "edition")]
+    pub sort_custom_field: Option<String>,
+    /// Sort direction for `sort_custom_field`: asc or desc (default: asc)
+    #[param(example = "desc")]
+    pub sort_order: Option<String>,
 }
 
 /// Path parameters for book operations
@@ -56,10 +72,16 @@ pub async fn create_book(
     if book_data.author.trim().is_empty() {
         return Err(AppError::ValidationError("Author is required".to_string()));
     }
+    validate_format_totals(book_data.duration_minutes, book_data.total_locations)?;
+    validate_purchase(book_data.purchase_price.as_ref(), book_data.currency.as_deref())?;
+    validate_book_location(&mut conn, book_data.location_id, book_data.acquisition_status.unwrap_or_default())?;
 
-    let new_book = book_data.into_inner().into();
+    let mut book_data = book_data.into_inner();
+    book_data.purchase_place = optional_text("Purchase place", book_data.purchase_place, MAX_PURCHASE_PLACE_LENGTH)?;
+    let new_book = book_data.into();
     let book = Book::create(&mut conn, new_book)?;
-    let response = BookResponse::from(book);
+    let mut response = BookResponse::from(book);
+    attach_location_paths(&mut conn, std::slice::from_mut(&mut response))?;
 
     Ok(HttpResponse::Created().json(response))
 }
@@ -83,7 +105,13 @@ pub async fn get_book(
     let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
     
     let book = Book::find_by_id(&mut conn, path.id)?;
-    let response = BookResponse::from(book);
+    let loans = Loan::find_by_book_id(&mut conn, book.id)?
+        .iter()
+        .map(|loan| loan.to_response(&book.title))
+        .collect();
+    let mut response = BookResponse::from(book);
+    attach_location_paths(&mut conn, std::slice::from_mut(&mut response))?;
+    response.loans = Some(loans);
 
     Ok(HttpResponse::Ok().json(response))
 }
@@ -109,7 +137,28 @@ pub async fn list_books(
     let page = query.page.unwrap_or(1).max(1);
     let per_page = query.per_page.unwrap_or(20).clamp(1, 100);
 
-    let (books, total) = if let Some(ref search_query) = query.search {
+    let search = query.search.as_deref().map(str::trim).filter(|q| !q.is_empty());
+    let custom_filter = match query.custom_field {
+        Some(ref filter) => Some(filter.split_once(':').ok_or_else(|| {
+            AppError::ValidationError("Custom field filter must be in the form key:value".to_string())
+        })?),
+        None => None,
+    };
+    let descending = match query.sort_order.as_deref() {
+        None | Some("asc") => false,
+        Some("desc") => true,
+        Some(other) => {
+            return Err(AppError::ValidationError(format!(
+                "Invalid sort order '{}', expected asc or desc",
+                other
+            )))
+        }
+    };
+    let custom_sort = query.sort_custom_field.as_deref().map(|key| (key, descending));
+
+    let (books, total) = if custom_filter.is_some() || custom_sort.is_some() {
+        Book::list_by_custom_field(&mut conn, search, custom_filter, custom_sort, page, per_page)?
+    } else if let Some(ref search_query) = query.search {
         if search_query.trim().is_empty() {
             Book::list_paginated(&mut conn, page, per_page)?
         } else {
@@ -120,7 +169,8 @@ pub async fn list_books(
     };
 
     let total_pages = ((total as f64) / (per_page as f64)).ceil() as u32;
-    let book_responses: Vec<BookResponse> = books.into_iter().map(BookResponse::from).collect();
+    let mut book_responses: Vec<BookResponse> = books.into_iter().map(BookResponse::from).collect();
+    attach_location_paths(&mut conn, &mut book_responses)?;
 
     let response = BookListResponse {
         books: book_responses,
@@ -154,20 +204,49 @@ pub async fn update_book(
 ) -> Result<HttpResponse, AppError> {
     let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
     
-    // Validate update data
-    if let Some(ref title) = update_data.title {
-        if title.trim().is_empty() {
-            return Err(AppError::ValidationError("Title cannot be empty".to_string()));
-        }
-    }
-    if let Some(ref author) = update_data.author {
-        if author.trim().is_empty() {
-            return Err(AppError::ValidationError("Author cannot be empty".to_string()));
-        }
-    }
+    let mut changes = BookChangeset::from(update_data.into_inner());
+    validate_book_changes(&mut conn, path.id, &mut changes)?;
+
+    let book = Book::patch(&mut conn, path.id, changes)?;
+    let mut response = BookResponse::from(book);
+    attach_location_paths(&mut conn, std::slice::from_mut(&mut response))?;
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Partially updates a book
+/// 
+/// Accepts a JSON Merge Patch document: absent fields are left unchanged
+/// and `null` clears optional fields such as `isbn` or `cover_image`.
+#[utoipa::path(
+    patch,
+    path = "/api/books/{id}",
+    params(BookPath),
+    request_body(content = PatchBookRequest, content_type = "application/merge-patch+json"),
+    responses(
+        (status = 200, description = "Book updated successfully", body = BookResponse),
+        (status = 400, description = "Malformed patch document", body = ErrorResponse),
+        (status = 404, description = "Book not found", body = ErrorResponse),
+        (status = 422, description = "Validation error", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Books"
+)]
+pub async fn patch_book(
+    pool: web::Data<DbPool>,
+    path: web::Path<BookPath>,
+    req: HttpRequest,
+    body: web::Bytes,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+    
+    let patch: PatchBookRequest = parse_merge_patch(&req, &body)?;
+    let mut changes = BookChangeset::try_from(patch)?;
+    validate_book_changes(&mut conn, path.id, &mut changes)?;
 
-    let book = Book::update(&mut conn, path.id, update_data.into_inner())?;
-    let response = BookResponse::from(book);
+    let book = Book::patch(&mut conn, path.id, changes)?;
+    let mut response = BookResponse::from(book);
+    attach_location_paths(&mut conn, std::slice::from_mut(&mut response))?;
 
     Ok(HttpResponse::Ok().json(response))
 }
@@ -195,6 +274,75 @@ pub async fn delete_book(
     Ok(HttpResponse::NoContent().finish())
 }
 
+/// Validates changes to an existing book, trimming the purchase place
+fn validate_book_changes(conn: &mut PgConnection, book_id: i64, changes: &mut BookChangeset) -> Result<(), AppError> {
+    if changes.title.as_ref().is_some_and(|t| t.trim().is_empty()) {
+        return Err(AppError::ValidationError("Title cannot be empty".to_string()));
+    }
+    if changes.author.as_ref().is_some_and(|a| a.trim().is_empty()) {
+        return Err(AppError::ValidationError("Author cannot be empty".to_string()));
+    }
+    if let Some(ref format) = changes.format {
+        format.parse::<BookFormat>()?;
+    }
+    if let Some(ref status) = changes.acquisition_status {
+        status.parse::<AcquisitionStatus>()?;
+    }
+    validate_format_totals(changes.duration_minutes.flatten(), changes.total_locations.flatten())?;
+    // The currency may already be stored on the book, so only its format is checked here
+    validate_purchase(None, changes.currency.as_ref().and_then(|c| c.as_deref()))?;
+    if changes.purchase_price.as_ref().and_then(|p| p.as_ref()).is_some_and(Signed::is_negative) {
+        return Err(AppError::ValidationError("Purchase price cannot be negative".to_string()));
+    }
+    if let Some(place) = changes.purchase_place.take() {
+        changes.purchase_place = Some(optional_text("Purchase place", place, MAX_PURCHASE_PLACE_LENGTH)?);
+    }
+    if let Some(location_id) = changes.location_id.flatten() {
+        let status = match changes.acquisition_status {
+            Some(ref status) => status.parse()?,
+            None => Book::find_by_id(conn, book_id)?.acquisition_status(),
+        };
+        validate_book_location(conn, Some(location_id), status)?;
+    }
+    Ok(())
+}
+
+/// Validates that audiobook duration and ebook location totals are positive
+fn validate_format_totals(duration_minutes: Option<i32>, total_locations: Option<i32>) -> Result<(), AppError> {
+    if duration_minutes.is_some_and(|d| d <= 0) {
+        return Err(AppError::ValidationError("Duration must be greater than 0".to_string()));
+    }
+    if total_locations.is_some_and(|l| l <= 0) {
+        return Err(AppError::ValidationError("Total locations must be greater than 0".to_string()));
+    }
+    Ok(())
+}
+
+/// Restores a soft deleted book from the trash
+#[utoipa::path(
+    post,
+    path = "/api/books/{id}/restore",
+    params(BookPath),
+    responses(
+        (status = 200, description = "Book restored successfully", body = BookResponse),
+        (status = 404, description = "Book not found in trash", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Books"
+)]
+pub async fn restore_book(
+    pool: web::Data<DbPool>,
+    path: web::Path<BookPath>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+    
+    let book = Book::restore(&mut conn, path.id)?;
+    let mut response = BookResponse::from(book);
+    attach_location_paths(&mut conn, std::slice::from_mut(&mut response))?;
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
 #[cfg(test)]
 mod tests {
     // Additional unit tests can be added here
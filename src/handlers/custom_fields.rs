@@ -0,0 +1,129 @@
+//! Custom field HTTP handlers
+//!
+//! Provides endpoints for managing user-defined fields whose values
+//! are stored on books
+
+use actix_web::{web, HttpResponse, Result};
+use serde::Deserialize;
+use utoipa::IntoParams;
+use crate::db::DbPool;
+use crate::errors::AppError;
+use crate::models::custom_field::{
+    CustomFieldDefinition, CustomFieldResponse, CreateCustomFieldRequest, UpdateCustomFieldRequest,
+};
+
+/// Path parameters for custom field operations
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct CustomFieldPath {
+    /// Custom field ID
+    #[param(example = 1)]
+    pub id: i64,
+}
+
+/// Defines a new custom field
+#[utoipa::path(
+    post,
+    path = "/api/custom-fields",
+    request_body = CreateCustomFieldRequest,
+    responses(
+        (status = 201, description = "Custom field created successfully", body = CustomFieldResponse),
+        (status = 409, description = "Key already in use", body = ErrorResponse),
+        (status = 422, description = "Validation error", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Custom Fields"
+)]
+pub async fn create_custom_field(
+    pool: web::Data<DbPool>,
+    field_data: web::Json<CreateCustomFieldRequest>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+
+    // Validate required fields
+    if field_data.name.trim().is_empty() {
+        return Err(AppError::ValidationError("Name is required".to_string()));
+    }
+
+    let definition = CustomFieldDefinition::create(&mut conn, field_data.into_inner())?;
+
+    Ok(HttpResponse::Created().json(CustomFieldResponse::from(definition)))
+}
+
+/// Lists all custom field definitions
+#[utoipa::path(
+    get,
+    path = "/api/custom-fields",
+    responses(
+        (status = 200, description = "Custom fields retrieved successfully", body = [CustomFieldResponse]),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Custom Fields"
+)]
+pub async fn list_custom_fields(
+    pool: web::Data<DbPool>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+
+    let responses: Vec<CustomFieldResponse> = CustomFieldDefinition::list_all(&mut conn)?
+        .into_iter()
+        .map(CustomFieldResponse::from)
+        .collect();
+
+    Ok(HttpResponse::Ok().json(responses))
+}
+
+/// Updates a custom field definition
+#[utoipa::path(
+    put,
+    path = "/api/custom-fields/{id}",
+    params(CustomFieldPath),
+    request_body = UpdateCustomFieldRequest,
+    responses(
+        (status = 200, description = "Custom field updated successfully", body = CustomFieldResponse),
+        (status = 404, description = "Custom field not found", body = ErrorResponse),
+        (status = 409, description = "Removed enum value is still in use", body = ErrorResponse),
+        (status = 422, description = "Validation error", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Custom Fields"
+)]
+pub async fn update_custom_field(
+    pool: web::Data<DbPool>,
+    path: web::Path<CustomFieldPath>,
+    update_data: web::Json<UpdateCustomFieldRequest>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+
+    if let Some(ref name) = update_data.name {
+        if name.trim().is_empty() {
+            return Err(AppError::ValidationError("Name cannot be empty".to_string()));
+        }
+    }
+
+    let definition = CustomFieldDefinition::update(&mut conn, path.id, update_data.into_inner())?;
+
+    Ok(HttpResponse::Ok().json(CustomFieldResponse::from(definition)))
+}
+
+/// Deletes a custom field definition and its values on all books
+#[utoipa::path(
+    delete,
+    path = "/api/custom-fields/{id}",
+    params(CustomFieldPath),
+    responses(
+        (status = 204, description = "Custom field deleted successfully"),
+        (status = 404, description = "Custom field not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Custom Fields"
+)]
+pub async fn delete_custom_field(
+    pool: web::Data<DbPool>,
+    path: web::Path<CustomFieldPath>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+
+    CustomFieldDefinition::delete(&mut conn, path.id)?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
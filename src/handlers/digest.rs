@@ -0,0 +1,46 @@
+//! Daily digest HTTP handlers
+//!
+//! Provides the daily highlights digest with notes written on the same
+//! date in earlier years
+
+use actix_web::{web, HttpResponse, Result};
+use chrono::{NaiveDate, Utc};
+use serde::Deserialize;
+use utoipa::IntoParams;
+use crate::db::DbPool;
+use crate::errors::AppError;
+use crate::models::digest;
+
+/// Query parameters for the daily digest
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct DigestQuery {
+    /// Day of the digest (default: today, UTC)
+    #[param(value_type = Option<String>, format = Date, example = "2024-01-01")]
+    pub date: Option<NaiveDate>,
+}
+
+/// Gets the highlights digest of the day
+///
+/// Today's highlights are stored, so they stay the same all day. Other
+/// dates return their stored highlights or an unsaved preview.
+#[utoipa::path(
+    get,
+    path = "/api/digest/today",
+    params(DigestQuery),
+    responses(
+        (status = 200, description = "Digest retrieved successfully", body = DigestResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Digest"
+)]
+pub async fn get_daily_digest(
+    pool: web::Data<DbPool>,
+    query: web::Query<DigestQuery>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+
+    let date = query.date.unwrap_or_else(|| Utc::now().date_naive());
+    let response = digest::for_day(&mut conn, date)?;
+
+    Ok(HttpResponse::Ok().json(response))
+}
@@ -0,0 +1,161 @@
+//! Book lending HTTP handlers
+//! 
+//! Provides endpoints for lending books, returning them and
+//! tracking what is currently out or overdue
+
+use actix_web::{web, HttpResponse, Result};
+use serde::Deserialize;
+use utoipa::IntoParams;
+use crate::db::DbPool;
+use crate::errors::AppError;
+use crate::models::book::Book;
+use crate::models::loan::{Loan, LoanState, CreateLoanRequest, ReturnLoanRequest, LoanListResponse};
+
+/// Query parameters for loan listing
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct LoanListQuery {
+    /// Page number (1-based, default: 1)
+    #[param(example = 1)]
+    pub page: Option<u32>,
+    /// Items per page (default: 20, max: 100)
+    #[param(example = 20)]
+    pub per_page: Option<u32>,
+    /// Filter by loan state (active, overdue, returned)
+    #[param(example = "overdue")]
+    pub state: Option<LoanState>,
+}
+
+/// Path parameters for loan operations
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct LoanPath {
+    /// Loan ID
+    #[param(example = 1)]
+    pub id: i64,
+}
+
+/// Path parameters for a book's loans
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct BookLoansPath {
+    /// Book ID
+    #[param(example = 1)]
+    pub book_id: i64,
+}
+
+/// Lends a book to a borrower
+#[utoipa::path(
+    post,
+    path = "/api/books/{book_id}/loans",
+    params(BookLoansPath),
+    request_body = CreateLoanRequest,
+    responses(
+        (status = 201, description = "Book lent successfully", body = LoanResponse),
+        (status = 404, description = "Book not found", body = ErrorResponse),
+        (status = 409, description = "Book is already lent out", body = ErrorResponse),
+        (status = 422, description = "Validation error", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Loans"
+)]
+pub async fn create_loan(
+    pool: web::Data<DbPool>,
+    path: web::Path<BookLoansPath>,
+    loan_data: web::Json<CreateLoanRequest>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+
+    let book = Book::find_by_id(&mut conn, path.book_id)?;
+    let loan = Loan::create(&mut conn, &book, loan_data.into_inner())?;
+
+    Ok(HttpResponse::Created().json(loan.to_response(&book.title)))
+}
+
+/// Gets the loan history of a book
+#[utoipa::path(
+    get,
+    path = "/api/books/{book_id}/loans",
+    params(BookLoansPath),
+    responses(
+        (status = 200, description = "Loan history retrieved successfully", body = Vec<LoanResponse>),
+        (status = 404, description = "Book not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Loans"
+)]
+pub async fn get_book_loans(
+    pool: web::Data<DbPool>,
+    path: web::Path<BookLoansPath>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+    
+    let book = Book::find_by_id(&mut conn, path.book_id)?;
+    let responses: Vec<_> = Loan::find_by_book_id(&mut conn, book.id)?
+        .iter()
+        .map(|loan| loan.to_response(&book.title))
+        .collect();
+
+    Ok(HttpResponse::Ok().json(responses))
+}
+
+/// Lists loans, optionally only active (lent out), overdue or returned ones
+#[utoipa::path(
+    get,
+    path = "/api/loans",
+    params(LoanListQuery),
+    responses(
+        (status = 200, description = "Loans retrieved successfully", body = LoanListResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Loans"
+)]
+pub async fn list_loans(
+    pool: web::Data<DbPool>,
+    query: web::Query<LoanListQuery>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+    
+    // Validate and set defaults for pagination
+    let page = query.page.unwrap_or(1).max(1);
+    let per_page = query.per_page.unwrap_or(20).clamp(1, 100);
+
+    let (loans, total) = Loan::list_with_filters(&mut conn, query.state, page, per_page)?;
+    let total_pages = ((total as f64) / (per_page as f64)).ceil() as u32;
+
+    let response = LoanListResponse {
+        loans: Loan::to_responses(&mut conn, loans)?,
+        total,
+        page,
+        per_page,
+        total_pages,
+    };
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Marks a loan as returned
+#[utoipa::path(
+    post,
+    path = "/api/loans/{id}/return",
+    params(LoanPath),
+    request_body = ReturnLoanRequest,
+    responses(
+        (status = 200, description = "Loan marked returned", body = LoanResponse),
+        (status = 404, description = "Loan not found", body = ErrorResponse),
+        (status = 409, description = "Loan already returned", body = ErrorResponse),
+        (status = 422, description = "Validation error", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Loans"
+)]
+pub async fn return_loan(
+    pool: web::Data<DbPool>,
+    path: web::Path<LoanPath>,
+    return_data: Option<web::Json<ReturnLoanRequest>>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+    
+    let request = return_data.map(|data| data.into_inner()).unwrap_or_default();
+    let loan = Loan::mark_returned(&mut conn, path.id, request)?;
+    let response = Loan::to_responses(&mut conn, vec![loan])?.remove(0);
+
+    Ok(HttpResponse::Ok().json(response))
+}
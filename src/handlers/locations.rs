@@ -0,0 +1,204 @@
+//! Physical location HTTP handlers
+//!
+//! Provides endpoints for managing the room > bookcase > shelf hierarchy
+//! of the home library and finding the books stored in each location
+
+use actix_web::{web, HttpResponse, Result};
+use serde::Deserialize;
+use utoipa::IntoParams;
+use crate::db::DbPool;
+use crate::errors::AppError;
+use crate::models::book::{BookResponse, BookListResponse};
+use crate::models::location::{attach_location_paths, Location, CreateLocationRequest, UpdateLocation};
+
+/// Query parameters for books in a location
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct LocationBooksQuery {
+    /// Page number (1-based, default: 1)
+    #[param(example = 1)]
+    pub page: Option<u32>,
+    /// Items per page (default: 20, max: 100)
+    #[param(example = 20)]
+    pub per_page: Option<u32>,
+}
+
+/// Path parameters for location operations
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct LocationPath {
+    /// Location ID
+    #[param(example = 1)]
+    pub id: i64,
+}
+
+/// Creates a new location
+#[utoipa::path(
+    post,
+    path = "/api/locations",
+    request_body = CreateLocationRequest,
+    responses(
+        (status = 201, description = "Location created successfully", body = LocationResponse),
+        (status = 404, description = "Parent location not found", body = ErrorResponse),
+        (status = 422, description = "Validation error", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Locations"
+)]
+pub async fn create_location(
+    pool: web::Data<DbPool>,
+    location_data: web::Json<CreateLocationRequest>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+
+    // Validate required fields
+    if location_data.name.trim().is_empty() {
+        return Err(AppError::ValidationError("Name is required".to_string()));
+    }
+
+    let location = Location::create(&mut conn, location_data.into_inner())?;
+    let paths = Location::paths(&mut conn, &[location.id])?;
+    let response = location.to_response(paths.get(&location.id).cloned().unwrap_or_default());
+
+    Ok(HttpResponse::Created().json(response))
+}
+
+/// Lists all locations ordered by path
+#[utoipa::path(
+    get,
+    path = "/api/locations",
+    responses(
+        (status = 200, description = "Locations retrieved successfully", body = [LocationResponse]),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Locations"
+)]
+pub async fn list_locations(
+    pool: web::Data<DbPool>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+
+    let locations = Location::list_all(&mut conn)?;
+    let responses = Location::to_responses(&mut conn, &locations)?;
+
+    Ok(HttpResponse::Ok().json(responses))
+}
+
+/// Gets a location by ID
+#[utoipa::path(
+    get,
+    path = "/api/locations/{id}",
+    params(LocationPath),
+    responses(
+        (status = 200, description = "Location found", body = LocationResponse),
+        (status = 404, description = "Location not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Locations"
+)]
+pub async fn get_location(
+    pool: web::Data<DbPool>,
+    path: web::Path<LocationPath>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+
+    let location = Location::find_by_id(&mut conn, path.id)?;
+    let paths = Location::paths(&mut conn, &[location.id])?;
+    let response = location.to_response(paths.get(&location.id).cloned().unwrap_or_default());
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Updates a location
+#[utoipa::path(
+    put,
+    path = "/api/locations/{id}",
+    params(LocationPath),
+    request_body = UpdateLocation,
+    responses(
+        (status = 200, description = "Location updated successfully", body = LocationResponse),
+        (status = 404, description = "Location not found", body = ErrorResponse),
+        (status = 422, description = "Validation error", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Locations"
+)]
+pub async fn update_location(
+    pool: web::Data<DbPool>,
+    path: web::Path<LocationPath>,
+    update_data: web::Json<UpdateLocation>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+
+    if let Some(ref name) = update_data.name {
+        if name.trim().is_empty() {
+            return Err(AppError::ValidationError("Name cannot be empty".to_string()));
+        }
+    }
+
+    let location = Location::update(&mut conn, path.id, update_data.into_inner())?;
+    let paths = Location::paths(&mut conn, &[location.id])?;
+    let response = location.to_response(paths.get(&location.id).cloned().unwrap_or_default());
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Soft deletes an empty location
+#[utoipa::path(
+    delete,
+    path = "/api/locations/{id}",
+    params(LocationPath),
+    responses(
+        (status = 204, description = "Location deleted successfully"),
+        (status = 404, description = "Location not found", body = ErrorResponse),
+        (status = 409, description = "Location still contains locations or books", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Locations"
+)]
+pub async fn delete_location(
+    pool: web::Data<DbPool>,
+    path: web::Path<LocationPath>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+
+    Location::soft_delete(&mut conn, path.id)?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Lists books stored in a location, including nested bookcases and shelves
+#[utoipa::path(
+    get,
+    path = "/api/locations/{id}/books",
+    params(LocationPath, LocationBooksQuery),
+    responses(
+        (status = 200, description = "Books retrieved successfully", body = BookListResponse),
+        (status = 404, description = "Location not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Locations"
+)]
+pub async fn get_location_books(
+    pool: web::Data<DbPool>,
+    path: web::Path<LocationPath>,
+    query: web::Query<LocationBooksQuery>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+
+    let page = query.page.unwrap_or(1).max(1);
+    let per_page = query.per_page.unwrap_or(20).clamp(1, 100);
+
+    let (books, total) = Location::list_books(&mut conn, path.id, page, per_page)?;
+    let total_pages = ((total as f64) / (per_page as f64)).ceil() as u32;
+    let mut book_responses: Vec<BookResponse> = books.into_iter().map(BookResponse::from).collect();
+    attach_location_paths(&mut conn, &mut book_responses)?;
+
+    let response = BookListResponse {
+        books: book_responses,
+        total,
+        page,
+        per_page,
+        total_pages,
+    };
+
+    Ok(HttpResponse::Ok().json(response))
+}
@@ -1,10 +1,25 @@
 use actix_web::HttpResponse;
 use serde::Serialize;
 
+pub mod acquisition;
+pub mod attachments;
 pub mod books;
 pub mod categories;
+pub mod custom_fields;
+pub mod digest;
+pub mod loans;
+pub mod locations;
+pub mod note_revisions;
+pub mod note_templates;
+pub mod note_types;
 pub mod notes;
+pub mod reading_status;
+pub mod recommendations;
+pub mod review;
+pub mod shares;
+pub mod statistics;
 pub mod tags;
+pub mod trash;
 
 #[derive(Serialize)]
 struct HealthResponse {
@@ -0,0 +1,115 @@
+//! Note revision HTTP handlers
+//!
+//! Provides endpoints for browsing, diffing and restoring prior
+//! versions of a note
+
+use actix_web::{web, HttpResponse, Result};
+use serde::Deserialize;
+use utoipa::IntoParams;
+use crate::db::DbPool;
+use crate::errors::AppError;
+use crate::models::note_revision::{NoteRevision, NoteRevisionResponse};
+
+/// Path parameters for note revision listing
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct NoteRevisionsPath {
+    /// Note ID
+    #[param(example = 1)]
+    pub id: i64,
+}
+
+/// Path parameters for a single note revision
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct NoteRevisionPath {
+    /// Note ID
+    #[param(example = 1)]
+    pub id: i64,
+    /// Revision number
+    #[param(example = 1)]
+    pub rev: i32,
+}
+
+/// Query parameters for diffing revisions
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct NoteDiffQuery {
+    /// Base revision number
+    #[param(example = 1)]
+    pub from: i32,
+    /// Target revision number (default: current content)
+    #[param(example = 2)]
+    pub to: Option<i32>,
+}
+
+/// Lists prior versions of a note
+#[utoipa::path(
+    get,
+    path = "/api/notes/{id}/revisions",
+    params(NoteRevisionsPath),
+    responses(
+        (status = 200, description = "Revisions retrieved successfully", body = [NoteRevisionResponse]),
+        (status = 404, description = "Note not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Notes"
+)]
+pub async fn list_note_revisions(
+    pool: web::Data<DbPool>,
+    path: web::Path<NoteRevisionsPath>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+
+    let responses: Vec<NoteRevisionResponse> = NoteRevision::list_for_note(&mut conn, path.id)?
+        .into_iter()
+        .map(NoteRevisionResponse::from)
+        .collect();
+
+    Ok(HttpResponse::Ok().json(responses))
+}
+
+/// Shows a word-level diff between two versions of a note
+#[utoipa::path(
+    get,
+    path = "/api/notes/{id}/revisions/diff",
+    params(NoteRevisionsPath, NoteDiffQuery),
+    responses(
+        (status = 200, description = "Diff computed successfully", body = NoteDiffResponse),
+        (status = 404, description = "Note or revision not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Notes"
+)]
+pub async fn diff_note_revisions(
+    pool: web::Data<DbPool>,
+    path: web::Path<NoteRevisionsPath>,
+    query: web::Query<NoteDiffQuery>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+
+    let diff = NoteRevision::diff(&mut conn, path.id, query.from, query.to)?;
+
+    Ok(HttpResponse::Ok().json(diff))
+}
+
+/// Restores a note to a prior revision
+#[utoipa::path(
+    post,
+    path = "/api/notes/{id}/revisions/{rev}/restore",
+    params(NoteRevisionPath),
+    responses(
+        (status = 200, description = "Note restored to the revision", body = NoteResponse),
+        (status = 404, description = "Note or revision not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Notes"
+)]
+pub async fn restore_note_revision(
+    pool: web::Data<DbPool>,
+    path: web::Path<NoteRevisionPath>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+
+    let note = NoteRevision::restore(&mut conn, path.id, path.rev)?;
+    let response = note.to_response(&mut conn)?;
+
+    Ok(HttpResponse::Ok().json(response))
+}
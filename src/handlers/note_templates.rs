@@ -0,0 +1,192 @@
+//! Note template HTTP handlers
+//!
+//! Provides endpoints for managing reusable note templates and for
+//! creating notes from them
+
+use actix_web::{web, HttpResponse, Result};
+use chrono::Utc;
+use serde::Deserialize;
+use utoipa::IntoParams;
+use crate::db::DbPool;
+use crate::errors::AppError;
+use crate::models::note_template::{
+    NoteTemplate, NoteTemplateResponse, CreateNoteTemplateRequest, UpdateNoteTemplateRequest,
+    CreateNoteFromTemplateRequest,
+};
+
+/// Path parameters for note template operations
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct NoteTemplatePath {
+    /// Note template ID
+    #[param(example = 1)]
+    pub id: i64,
+}
+
+/// Creates a new note template
+#[utoipa::path(
+    post,
+    path = "/api/note-templates",
+    request_body = CreateNoteTemplateRequest,
+    responses(
+        (status = 201, description = "Note template created successfully", body = NoteTemplateResponse),
+        (status = 409, description = "Name already in use", body = ErrorResponse),
+        (status = 422, description = "Validation error", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Note Templates"
+)]
+pub async fn create_note_template(
+    pool: web::Data<DbPool>,
+    template_data: web::Json<CreateNoteTemplateRequest>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+
+    // Validate required fields
+    if template_data.name.trim().is_empty() {
+        return Err(AppError::ValidationError("Name is required".to_string()));
+    }
+    if template_data.content.trim().is_empty() {
+        return Err(AppError::ValidationError("Content is required".to_string()));
+    }
+
+    let template = NoteTemplate::create(&mut conn, template_data.into_inner())?;
+
+    Ok(HttpResponse::Created().json(NoteTemplateResponse::from(template)))
+}
+
+/// Lists all note templates
+#[utoipa::path(
+    get,
+    path = "/api/note-templates",
+    responses(
+        (status = 200, description = "Note templates retrieved successfully", body = [NoteTemplateResponse]),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Note Templates"
+)]
+pub async fn list_note_templates(
+    pool: web::Data<DbPool>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+
+    let responses: Vec<NoteTemplateResponse> = NoteTemplate::list_all(&mut conn)?
+        .into_iter()
+        .map(NoteTemplateResponse::from)
+        .collect();
+
+    Ok(HttpResponse::Ok().json(responses))
+}
+
+/// Gets a note template by ID
+#[utoipa::path(
+    get,
+    path = "/api/note-templates/{id}",
+    params(NoteTemplatePath),
+    responses(
+        (status = 200, description = "Note template found", body = NoteTemplateResponse),
+        (status = 404, description = "Note template not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Note Templates"
+)]
+pub async fn get_note_template(
+    pool: web::Data<DbPool>,
+    path: web::Path<NoteTemplatePath>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+
+    let template = NoteTemplate::find_by_id(&mut conn, path.id)?;
+
+    Ok(HttpResponse::Ok().json(NoteTemplateResponse::from(template)))
+}
+
+/// Updates a note template
+#[utoipa::path(
+    put,
+    path = "/api/note-templates/{id}",
+    params(NoteTemplatePath),
+    request_body = UpdateNoteTemplateRequest,
+    responses(
+        (status = 200, description = "Note template updated successfully", body = NoteTemplateResponse),
+        (status = 404, description = "Note template not found", body = ErrorResponse),
+        (status = 409, description = "Name already in use", body = ErrorResponse),
+        (status = 422, description = "Validation error", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Note Templates"
+)]
+pub async fn update_note_template(
+    pool: web::Data<DbPool>,
+    path: web::Path<NoteTemplatePath>,
+    update_data: web::Json<UpdateNoteTemplateRequest>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+
+    if let Some(ref name) = update_data.name {
+        if name.trim().is_empty() {
+            return Err(AppError::ValidationError("Name cannot be empty".to_string()));
+        }
+    }
+    if let Some(ref content) = update_data.content {
+        if content.trim().is_empty() {
+            return Err(AppError::ValidationError("Content cannot be empty".to_string()));
+        }
+    }
+
+    let template = NoteTemplate::update(&mut conn, path.id, update_data.into_inner())?;
+
+    Ok(HttpResponse::Ok().json(NoteTemplateResponse::from(template)))
+}
+
+/// Deletes a note template
+///
+/// Notes created from the template are kept.
+#[utoipa::path(
+    delete,
+    path = "/api/note-templates/{id}",
+    params(NoteTemplatePath),
+    responses(
+        (status = 204, description = "Note template deleted successfully"),
+        (status = 404, description = "Note template not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Note Templates"
+)]
+pub async fn delete_note_template(
+    pool: web::Data<DbPool>,
+    path: web::Path<NoteTemplatePath>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+
+    NoteTemplate::delete(&mut conn, path.id)?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Creates a note for a book from a template
+///
+/// Placeholders in the template's title and content are filled in with
+/// the book's details, and the note gets the template's note type and tags.
+#[utoipa::path(
+    post,
+    path = "/api/notes/from-template",
+    request_body = CreateNoteFromTemplateRequest,
+    responses(
+        (status = 201, description = "Note created successfully", body = NoteResponse),
+        (status = 404, description = "Template or book not found", body = ErrorResponse),
+        (status = 422, description = "Validation error", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Notes"
+)]
+pub async fn create_note_from_template(
+    pool: web::Data<DbPool>,
+    request: web::Json<CreateNoteFromTemplateRequest>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+
+    let note = NoteTemplate::create_note(&mut conn, request.into_inner(), Utc::now().date_naive())?;
+    let response = note.to_response(&mut conn)?;
+
+    Ok(HttpResponse::Created().json(response))
+}
@@ -0,0 +1,150 @@
+//! Note type HTTP handlers
+//!
+//! Provides endpoints for managing the types a note can have, such as
+//! quotes, summaries or user-defined types like vocabulary
+
+use actix_web::{web, HttpResponse, Result};
+use serde::Deserialize;
+use utoipa::IntoParams;
+use crate::db::DbPool;
+use crate::errors::AppError;
+use crate::models::note_type::{NoteType, NoteTypeResponse, CreateNoteTypeRequest, UpdateNoteTypeRequest};
+
+/// Path parameters for note type operations
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct NoteTypePath {
+    /// Note type ID
+    #[param(example = 1)]
+    pub id: i64,
+}
+
+/// Creates a new note type
+#[utoipa::path(
+    post,
+    path = "/api/note-types",
+    request_body = CreateNoteTypeRequest,
+    responses(
+        (status = 201, description = "Note type created successfully", body = NoteTypeResponse),
+        (status = 409, description = "Key already in use", body = ErrorResponse),
+        (status = 422, description = "Validation error", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Note Types"
+)]
+pub async fn create_note_type(
+    pool: web::Data<DbPool>,
+    type_data: web::Json<CreateNoteTypeRequest>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+
+    // Validate required fields
+    if type_data.name.trim().is_empty() {
+        return Err(AppError::ValidationError("Name is required".to_string()));
+    }
+
+    let note_type = NoteType::create(&mut conn, type_data.into_inner())?;
+
+    Ok(HttpResponse::Created().json(NoteTypeResponse::from(note_type)))
+}
+
+/// Lists all note types
+#[utoipa::path(
+    get,
+    path = "/api/note-types",
+    responses(
+        (status = 200, description = "Note types retrieved successfully", body = [NoteTypeResponse]),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Note Types"
+)]
+pub async fn list_note_types(
+    pool: web::Data<DbPool>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+
+    let responses: Vec<NoteTypeResponse> = NoteType::list_all(&mut conn)?
+        .into_iter()
+        .map(NoteTypeResponse::from)
+        .collect();
+
+    Ok(HttpResponse::Ok().json(responses))
+}
+
+/// Gets a note type by ID
+#[utoipa::path(
+    get,
+    path = "/api/note-types/{id}",
+    params(NoteTypePath),
+    responses(
+        (status = 200, description = "Note type found", body = NoteTypeResponse),
+        (status = 404, description = "Note type not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Note Types"
+)]
+pub async fn get_note_type(
+    pool: web::Data<DbPool>,
+    path: web::Path<NoteTypePath>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+
+    let note_type = NoteType::find_by_id(&mut conn, path.id)?;
+
+    Ok(HttpResponse::Ok().json(NoteTypeResponse::from(note_type)))
+}
+
+/// Updates a note type's name, icon or color
+#[utoipa::path(
+    put,
+    path = "/api/note-types/{id}",
+    params(NoteTypePath),
+    request_body = UpdateNoteTypeRequest,
+    responses(
+        (status = 200, description = "Note type updated successfully", body = NoteTypeResponse),
+        (status = 404, description = "Note type not found", body = ErrorResponse),
+        (status = 422, description = "Validation error", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Note Types"
+)]
+pub async fn update_note_type(
+    pool: web::Data<DbPool>,
+    path: web::Path<NoteTypePath>,
+    update_data: web::Json<UpdateNoteTypeRequest>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+
+    if let Some(ref name) = update_data.name {
+        if name.trim().is_empty() {
+            return Err(AppError::ValidationError("Name cannot be empty".to_string()));
+        }
+    }
+
+    let note_type = NoteType::update(&mut conn, path.id, update_data.into_inner())?;
+
+    Ok(HttpResponse::Ok().json(NoteTypeResponse::from(note_type)))
+}
+
+/// Deletes an unused, user-defined note type
+#[utoipa::path(
+    delete,
+    path = "/api/note-types/{id}",
+    params(NoteTypePath),
+    responses(
+        (status = 204, description = "Note type deleted successfully"),
+        (status = 404, description = "Note type not found", body = ErrorResponse),
+        (status = 409, description = "Note type is built in or still used by notes", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Note Types"
+)]
+pub async fn delete_note_type(
+    pool: web::Data<DbPool>,
+    path: web::Path<NoteTypePath>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+
+    NoteType::delete(&mut conn, path.id)?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
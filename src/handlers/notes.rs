@@ -2,12 +2,16 @@
 //! 
 //! Provides RESTful API endpoints for note CRUD operations
 
-use actix_web::{web, HttpResponse, Result};
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use chrono::NaiveDate;
 use serde::Deserialize;
 use utoipa::IntoParams;
 use crate::db::DbPool;
 use crate::errors::AppError;
-use crate::models::note::{ReadingNote, CreateNoteRequest, UpdateReadingNote, NoteListResponse};
+use crate::models::note::{ReadingNote, NoteFilter, NoteSort, NoteChangeset, CreateNoteRequest, UpdateReadingNote, PatchNoteRequest, NoteListResponse, ReorderNotesRequest};
+use crate::models::note_bulk::BulkNoteRequest;
+use crate::models::note_link::NoteLink;
+use crate::utils::patch::parse_merge_patch;
 
 /// Query parameters for note listing
 #[derive(Debug, Deserialize, IntoParams)]
@@ -21,9 +25,77 @@ pub struct NoteListQuery {
     /// Search query for title/content
     #[param(example = "important")]
     pub search: Option<String>,
-    /// Filter by note type (quote, summary, thought, general)
+    /// Filter by note type key
     #[param(example = "quote")]
     pub note_type: Option<String>,
+    /// Filter by book
+    #[param(example = 1)]
+    pub book_id: Option<i64>,
+    /// Comma separated tag names; notes must have all of them
+    #[param(example = "important,chapter1")]
+    pub tags: Option<String>,
+    /// Filter by favorite flag
+    #[param(example = true)]
+    pub is_favorite: Option<bool>,
+    /// Created on or after this date
+    #[param(value_type = Option<String>, format = Date, example = "2024-01-01")]
+    pub created_from: Option<NaiveDate>,
+    /// Created on or before this date
+    #[param(value_type = Option<String>, format = Date, example = "2024-12-31")]
+    pub created_to: Option<NaiveDate>,
+    /// Last updated on or after this date
+    #[param(value_type = Option<String>, format = Date, example = "2024-01-01")]
+    pub updated_from: Option<NaiveDate>,
+    /// Last updated on or before this date
+    #[param(value_type = Option<String>, format = Date, example = "2024-12-31")]
+    pub updated_to: Option<NaiveDate>,
+    /// Sort order: created (newest first, default), updated, position, title, book or manual
+    #[param(example = "updated")]
+    pub sort: Option<String>,
+    /// Hide replies, listing only notes that do not reply to an active note
+    #[param(example = true)]
+    pub collapse_replies: Option<bool>,
+    /// Set to `html` to include sanitized HTML of each note's content
+    #[param(example = "html")]
+    pub render: Option<String>,
+}
+
+/// Query parameters for a book's notes
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct BookNotesQuery {
+    /// Page number (1-based, default: 1)
+    #[param(example = 1)]
+    pub page: Option<u32>,
+    /// Items per page (default: 20, max: 100)
+    #[param(example = 20)]
+    pub per_page: Option<u32>,
+    /// Sort order: manual (arranged order, default), created (newest first), updated,
+    /// position (reading order) or title; pinned notes always come first
+    #[param(example = "position")]
+    pub sort: Option<String>,
+    /// Hide replies, listing only notes that do not reply to an active note
+    #[param(example = true)]
+    pub collapse_replies: Option<bool>,
+    /// Set to `html` to include sanitized HTML of each note's content
+    #[param(example = "html")]
+    pub render: Option<String>,
+}
+
+/// Query parameters for single note retrieval
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct NoteRenderQuery {
+    /// Set to `html` to include sanitized HTML of the content
+    #[param(example = "html")]
+    pub render: Option<String>,
+}
+
+/// Parses the `render` query parameter, returning whether HTML is requested
+fn wants_html(render: Option<&str>) -> Result<bool, AppError> {
+    match render {
+        None => Ok(false),
+        Some("html") => Ok(true),
+        Some(other) => Err(AppError::ValidationError(format!("Unsupported render format: {}", other))),
+    }
 }
 
 /// Path parameters for note operations
@@ -84,10 +156,11 @@ pub async fn create_note(
 #[utoipa::path(
     get,
     path = "/api/notes/{id}",
-    params(NotePath),
+    params(NotePath, NoteRenderQuery),
     responses(
         (status = 200, description = "Note found", body = NoteResponse),
         (status = 404, description = "Note not found", body = ErrorResponse),
+        (status = 422, description = "Unsupported render format", body = ErrorResponse),
         (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
     tag = "Notes"
@@ -95,22 +168,25 @@ pub async fn create_note(
 pub async fn get_note(
     pool: web::Data<DbPool>,
     path: web::Path<NotePath>,
+    query: web::Query<NoteRenderQuery>,
 ) -> Result<HttpResponse, AppError> {
     let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+    let render_html = wants_html(query.render.as_deref())?;
     
     let note = ReadingNote::find_by_id(&mut conn, path.id)?;
-    let response = note.to_response(&mut conn)?;
+    let response = note.to_rendered_response(&mut conn, render_html)?;
 
     Ok(HttpResponse::Ok().json(response))
 }
 
-/// Lists notes with pagination and optional search
+/// Lists notes with pagination, filters and sorting
 #[utoipa::path(
     get,
     path = "/api/notes",
     params(NoteListQuery),
     responses(
         (status = 200, description = "Notes retrieved successfully", body = NoteListResponse),
+        (status = 422, description = "Invalid sort, date range or render format", body = ErrorResponse),
         (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
     tag = "Notes"
@@ -120,24 +196,41 @@ pub async fn list_notes(
     query: web::Query<NoteListQuery>,
 ) -> Result<HttpResponse, AppError> {
     let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+    let render_html = wants_html(query.render.as_deref())?;
     
     // Validate and set defaults for pagination
     let page = query.page.unwrap_or(1).max(1);
     let per_page = query.per_page.unwrap_or(20).clamp(1, 100);
 
-    let (notes, total) = ReadingNote::list_with_filters(
-        &mut conn,
-        query.search.as_deref(),
-        query.note_type.as_deref(),
-        page,
-        per_page,
-    )?;
+    let sort = match query.sort {
+        Some(ref sort) => sort.parse()?,
+        None => NoteSort::default(),
+    };
+    let query = query.into_inner();
+    let filter = NoteFilter {
+        search: query.search,
+        note_type: query.note_type,
+        book_id: query.book_id,
+        tags: query
+            .tags
+            .as_deref()
+            .map(|tags| tags.split(',').map(str::trim).filter(|t| !t.is_empty()).map(String::from).collect())
+            .unwrap_or_default(),
+        is_favorite: query.is_favorite,
+        created_from: query.created_from,
+        created_to: query.created_to,
+        updated_from: query.updated_from,
+        updated_to: query.updated_to,
+        collapse_replies: query.collapse_replies.unwrap_or(false),
+    };
+
+    let (notes, total) = ReadingNote::list_with_filters(&mut conn, &filter, sort, page, per_page)?;
 
     let total_pages = ((total as f64) / (per_page as f64)).ceil() as u32;
     let mut note_responses = Vec::new();
     
     for note in notes {
-        note_responses.push(note.to_response(&mut conn)?);
+        note_responses.push(note.to_rendered_response(&mut conn, render_html)?);
     }
 
     let response = NoteListResponse {
@@ -155,10 +248,11 @@ pub async fn list_notes(
 #[utoipa::path(
     get,
     path = "/api/books/{book_id}/notes",
-    params(BookNotesPath, NoteListQuery),
+    params(BookNotesPath, BookNotesQuery),
     responses(
         (status = 200, description = "Notes retrieved successfully", body = NoteListResponse),
         (status = 404, description = "Book not found", body = ErrorResponse),
+        (status = 422, description = "Invalid sort or unsupported render format", body = ErrorResponse),
         (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
     tag = "Notes"
@@ -166,9 +260,14 @@ pub async fn list_notes(
 pub async fn get_book_notes(
     pool: web::Data<DbPool>,
     path: web::Path<BookNotesPath>,
-    query: web::Query<NoteListQuery>,
+    query: web::Query<BookNotesQuery>,
 ) -> Result<HttpResponse, AppError> {
     let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+    let render_html = wants_html(query.render.as_deref())?;
+    let sort: NoteSort = match query.sort {
+        Some(ref sort) => sort.parse()?,
+        None => NoteSort::Manual,
+    };
     
     // Verify book exists
     use crate::models::book::Book;
@@ -178,12 +277,19 @@ pub async fn get_book_notes(
     let page = query.page.unwrap_or(1).max(1);
     let per_page = query.per_page.unwrap_or(20).clamp(1, 100);
     
-    let (notes, total) = ReadingNote::find_by_book_id(&mut conn, path.book_id, page, per_page)?;
+    let (notes, total) = ReadingNote::find_by_book_id(
+        &mut conn,
+        path.book_id,
+        sort,
+        query.collapse_replies.unwrap_or(false),
+        page,
+        per_page,
+    )?;
     let total_pages = ((total as f64) / (per_page as f64)).ceil() as u32;
     
     let mut note_responses = Vec::new();
     for note in notes {
-        note_responses.push(note.to_response(&mut conn)?);
+        note_responses.push(note.to_rendered_response(&mut conn, render_html)?);
     }
 
     let response = NoteListResponse {
@@ -197,6 +303,42 @@ pub async fn get_book_notes(
     Ok(HttpResponse::Ok().json(response))
 }
 
+/// Arranges a book's notes into a manual order
+///
+/// Notes of the book that are not listed keep following the listed ones,
+/// newest first. Pinned notes are still listed before all others.
+#[utoipa::path(
+    put,
+    path = "/api/books/{book_id}/notes/order",
+    params(BookNotesPath),
+    request_body = ReorderNotesRequest,
+    responses(
+        (status = 200, description = "Notes reordered successfully", body = [NoteResponse]),
+        (status = 404, description = "Book not found", body = ErrorResponse),
+        (status = 422, description = "Duplicate note or note of another book", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Notes"
+)]
+pub async fn reorder_book_notes(
+    pool: web::Data<DbPool>,
+    path: web::Path<BookNotesPath>,
+    order: web::Json<ReorderNotesRequest>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+
+    use crate::models::book::Book;
+    Book::find_by_id(&mut conn, path.book_id)?;
+
+    let notes = ReadingNote::reorder(&mut conn, path.book_id, &order.note_ids)?;
+    let mut responses = Vec::new();
+    for note in notes {
+        responses.push(note.to_response(&mut conn)?);
+    }
+
+    Ok(HttpResponse::Ok().json(responses))
+}
+
 /// Updates a note
 #[utoipa::path(
     put,
@@ -231,6 +373,44 @@ pub async fn update_note(
     Ok(HttpResponse::Ok().json(response))
 }
 
+/// Partially updates a note
+/// 
+/// Accepts a JSON Merge Patch document: absent fields are left unchanged
+/// and `null` clears optional fields such as `title` or `page_reference`.
+#[utoipa::path(
+    patch,
+    path = "/api/notes/{id}",
+    params(NotePath),
+    request_body(content = PatchNoteRequest, content_type = "application/merge-patch+json"),
+    responses(
+        (status = 200, description = "Note updated successfully", body = NoteResponse),
+        (status = 400, description = "Malformed patch document", body = ErrorResponse),
+        (status = 404, description = "Note not found", body = ErrorResponse),
+        (status = 422, description = "Validation error", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Notes"
+)]
+pub async fn patch_note(
+    pool: web::Data<DbPool>,
+    path: web::Path<NotePath>,
+    req: HttpRequest,
+    body: web::Bytes,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+    
+    let patch: PatchNoteRequest = parse_merge_patch(&req, &body)?;
+    let changes = NoteChangeset::try_from(patch)?;
+    if changes.content.as_ref().is_some_and(|c| c.trim().is_empty()) {
+        return Err(AppError::ValidationError("Content cannot be empty".to_string()));
+    }
+
+    let note = ReadingNote::patch(&mut conn, path.id, changes)?;
+    let response = note.to_response(&mut conn)?;
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
 /// Updates tags for a note
 #[utoipa::path(
     put,
@@ -282,7 +462,108 @@ pub async fn delete_note(
     Ok(HttpResponse::NoContent().finish())
 }
 
+/// Restores a soft deleted note from the trash
+#[utoipa::path(
+    post,
+    path = "/api/notes/{id}/restore",
+    params(NotePath),
+    responses(
+        (status = 200, description = "Note restored successfully", body = NoteResponse),
+        (status = 404, description = "Note not found in trash", body = ErrorResponse),
+        (status = 409, description = "Note's book is deleted", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Notes"
+)]
+pub async fn restore_note(
+    pool: web::Data<DbPool>,
+    path: web::Path<NotePath>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+    
+    let note = ReadingNote::restore(&mut conn, path.id)?;
+    let response = note.to_response(&mut conn)?;
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Lists notes that link to a note with `[[note:id]]`
+#[utoipa::path(
+    get,
+    path = "/api/notes/{id}/backlinks",
+    params(NotePath),
+    responses(
+        (status = 200, description = "Backlinks retrieved successfully", body = [NoteResponse]),
+        (status = 404, description = "Note not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Notes"
+)]
+pub async fn get_note_backlinks(
+    pool: web::Data<DbPool>,
+    path: web::Path<NotePath>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+
+    let mut responses = Vec::new();
+    for note in NoteLink::backlinks(&mut conn, path.id)? {
+        responses.push(note.to_response(&mut conn)?);
+    }
+
+    Ok(HttpResponse::Ok().json(responses))
+}
+
+/// Gets the thread a note belongs to, from its topmost active ancestor down
+#[utoipa::path(
+    get,
+    path = "/api/notes/{id}/thread",
+    params(NotePath),
+    responses(
+        (status = 200, description = "Thread retrieved successfully", body = NoteThreadResponse),
+        (status = 404, description = "Note not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Notes"
+)]
+pub async fn get_note_thread(
+    pool: web::Data<DbPool>,
+    path: web::Path<NotePath>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+
+    let thread = ReadingNote::thread(&mut conn, path.id)?;
+
+    Ok(HttpResponse::Ok().json(thread.to_response(&mut conn)?))
+}
+
+/// Applies one operation to many notes in a single transaction
+///
+/// Items that fail are rolled back and reported in the results while the
+/// other items are applied.
+#[utoipa::path(
+    post,
+    path = "/api/notes/bulk",
+    request_body = BulkNoteRequest,
+    responses(
+        (status = 200, description = "Bulk operation applied, see per-item results", body = BulkNoteResponse),
+        (status = 404, description = "Target book not found", body = ErrorResponse),
+        (status = 422, description = "No items, too many items or invalid note type or tags", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Notes"
+)]
+pub async fn bulk_notes(
+    pool: web::Data<DbPool>,
+    request: web::Json<BulkNoteRequest>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+
+    let response = request.into_inner().execute(&mut conn)?;
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
 #[cfg(test)]
 mod tests {
     // Unit tests can be added here
-}
\ No newline at end of file
+}
@@ -0,0 +1,74 @@
+//! Reading status HTTP handlers
+//! 
+//! Provides endpoints for tracking reading status and format-aware progress
+
+use actix_web::{web, HttpResponse, Result};
+use serde::Deserialize;
+use utoipa::IntoParams;
+use crate::db::DbPool;
+use crate::errors::AppError;
+use crate::models::book::Book;
+use crate::models::reading_status::{ReadingStatus, UpdateReadingStatusRequest};
+
+/// Path parameters for a book's reading status
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct BookStatusPath {
+    /// Book ID
+    #[param(example = 1)]
+    pub book_id: i64,
+}
+
+/// Gets the reading status of a book
+#[utoipa::path(
+    get,
+    path = "/api/books/{book_id}/status",
+    params(BookStatusPath),
+    responses(
+        (status = 200, description = "Reading status found", body = ReadingStatusResponse),
+        (status = 404, description = "Book or reading status not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Reading Status"
+)]
+pub async fn get_reading_status(
+    pool: web::Data<DbPool>,
+    path: web::Path<BookStatusPath>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+    
+    let book = Book::find_by_id(&mut conn, path.book_id)?;
+    let status = ReadingStatus::find_by_book_id(&mut conn, book.id)?;
+
+    Ok(HttpResponse::Ok().json(status.to_response(&book)))
+}
+
+/// Creates or updates the reading status of a book
+/// 
+/// Progress is computed from the marker matching the book's format:
+/// pages for paper editions, minutes listened for audiobooks and
+/// locations (or an explicit percentage) for ebooks.
+#[utoipa::path(
+    put,
+    path = "/api/books/{book_id}/status",
+    params(BookStatusPath),
+    request_body = UpdateReadingStatusRequest,
+    responses(
+        (status = 200, description = "Reading status updated successfully", body = ReadingStatusResponse),
+        (status = 404, description = "Book not found", body = ErrorResponse),
+        (status = 422, description = "Validation error", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Reading Status"
+)]
+pub async fn update_reading_status(
+    pool: web::Data<DbPool>,
+    path: web::Path<BookStatusPath>,
+    status_data: web::Json<UpdateReadingStatusRequest>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+    
+    let book = Book::find_by_id(&mut conn, path.book_id)?;
+    let status = ReadingStatus::upsert(&mut conn, &book, status_data.into_inner())?;
+
+    Ok(HttpResponse::Ok().json(status.to_response(&book)))
+}
@@ -0,0 +1,79 @@
+//! Recommendation HTTP handlers
+//!
+//! Provides endpoints for related books and what to read next, scored
+//! locally from tags, categories, authors and ratings
+
+use actix_web::{web, HttpResponse, Result};
+use serde::Deserialize;
+use utoipa::IntoParams;
+use crate::db::DbPool;
+use crate::errors::AppError;
+use crate::models::recommendation;
+
+/// Path parameters for book recommendation operations
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct BookPath {
+    /// Book ID
+    #[param(example = 1)]
+    pub id: i64,
+}
+
+/// Query parameters for recommendation lists
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct RecommendationQuery {
+    /// Maximum number of results (default: 10, max: 50)
+    #[param(example = 10)]
+    pub limit: Option<usize>,
+}
+
+impl RecommendationQuery {
+    fn limit(&self) -> usize {
+        self.limit.unwrap_or(10).clamp(1, 50)
+    }
+}
+
+/// Lists books similar to the given book
+#[utoipa::path(
+    get,
+    path = "/api/books/{id}/similar",
+    params(BookPath, RecommendationQuery),
+    responses(
+        (status = 200, description = "Similar books retrieved successfully", body = [SimilarBook]),
+        (status = 404, description = "Book not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Recommendations"
+)]
+pub async fn get_similar_books(
+    pool: web::Data<DbPool>,
+    path: web::Path<BookPath>,
+    query: web::Query<RecommendationQuery>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+
+    let similar = recommendation::similar_books(&mut conn, path.id, query.limit())?;
+
+    Ok(HttpResponse::Ok().json(similar))
+}
+
+/// Suggests what to read next from the to-read pile
+#[utoipa::path(
+    get,
+    path = "/api/recommendations/next",
+    params(RecommendationQuery),
+    responses(
+        (status = 200, description = "Recommendations retrieved successfully", body = [Recommendation]),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Recommendations"
+)]
+pub async fn get_next_reads(
+    pool: web::Data<DbPool>,
+    query: web::Query<RecommendationQuery>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+
+    let recommendations = recommendation::next_reads(&mut conn, query.limit())?;
+
+    Ok(HttpResponse::Ok().json(recommendations))
+}
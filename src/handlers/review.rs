@@ -0,0 +1,97 @@
+//! Spaced-repetition review HTTP handlers
+//!
+//! Provides endpoints for listing notes due for review and grading them,
+//! scheduling each note's next review with SM-2
+
+use actix_web::{web, HttpResponse, Result};
+use chrono::Utc;
+use serde::Deserialize;
+use utoipa::IntoParams;
+use crate::db::DbPool;
+use crate::errors::AppError;
+use crate::models::review::{NoteReview, ReviewScope, ReviewRequest, ReviewStateResponse, DueNoteResponse, DueReviewResponse};
+
+/// Query parameters for due reviews
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct DueReviewQuery {
+    /// Maximum number of notes (default: 20, max: 100)
+    #[param(example = 20)]
+    pub limit: Option<i64>,
+    /// Only review quotes
+    #[param(example = true)]
+    pub quotes_only: Option<bool>,
+    /// Only review favorite notes
+    #[param(example = false)]
+    pub favorites_only: Option<bool>,
+}
+
+/// Path parameters for review operations
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ReviewPath {
+    /// Note ID
+    #[param(example = 1)]
+    pub note_id: i64,
+}
+
+/// Lists notes due for review today
+///
+/// Overdue notes come first, followed by notes that were never reviewed.
+#[utoipa::path(
+    get,
+    path = "/api/review/due",
+    params(DueReviewQuery),
+    responses(
+        (status = 200, description = "Due notes retrieved successfully", body = DueReviewResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Review"
+)]
+pub async fn get_due_reviews(
+    pool: web::Data<DbPool>,
+    query: web::Query<DueReviewQuery>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+
+    let limit = query.limit.unwrap_or(20).clamp(1, 100);
+    let scope = ReviewScope {
+        quotes_only: query.quotes_only.unwrap_or(false),
+        favorites_only: query.favorites_only.unwrap_or(false),
+    };
+
+    let (due, total) = NoteReview::due(&mut conn, scope, Utc::now().date_naive(), limit)?;
+    let mut notes = Vec::new();
+    for (note, review) in due {
+        notes.push(DueNoteResponse {
+            note: note.to_response(&mut conn)?,
+            review: review.map(ReviewStateResponse::from),
+        });
+    }
+
+    Ok(HttpResponse::Ok().json(DueReviewResponse { notes, total }))
+}
+
+/// Grades a review of a note and schedules the next one
+#[utoipa::path(
+    post,
+    path = "/api/review/{note_id}",
+    params(ReviewPath),
+    request_body = ReviewRequest,
+    responses(
+        (status = 200, description = "Review recorded successfully", body = ReviewStateResponse),
+        (status = 400, description = "Invalid grade", body = ErrorResponse),
+        (status = 404, description = "Note not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Review"
+)]
+pub async fn review_note(
+    pool: web::Data<DbPool>,
+    path: web::Path<ReviewPath>,
+    review_data: web::Json<ReviewRequest>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+
+    let review = NoteReview::record(&mut conn, path.note_id, review_data.grade, Utc::now().date_naive())?;
+
+    Ok(HttpResponse::Ok().json(ReviewStateResponse::from(review)))
+}
@@ -0,0 +1,236 @@
+//! Share link HTTP handlers
+//!
+//! Provides endpoints for sharing a note or a book's notes through
+//! unguessable read-only links, and the public page those links open
+
+use actix_web::http::header;
+use actix_web::{web, HttpResponse, Result};
+use chrono::Utc;
+use serde::Deserialize;
+use utoipa::IntoParams;
+use crate::db::DbPool;
+use crate::errors::AppError;
+use crate::models::share_link::{ShareLink, CreateShareLinkRequest, ShareLinkResponse};
+
+/// Path parameters for sharing a note or book
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct SharePath {
+    /// Note or book ID
+    #[param(example = 1)]
+    pub id: i64,
+}
+
+/// Query parameters for share link listing
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ShareListQuery {
+    /// Only links to this note
+    #[param(example = 1)]
+    pub note_id: Option<i64>,
+    /// Only links to this book
+    #[param(example = 1)]
+    pub book_id: Option<i64>,
+}
+
+/// Path parameters for a public share link
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ShareTokenPath {
+    /// Share token
+    pub token: String,
+}
+
+/// Path parameters for an attachment of a public share link
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ShareAttachmentPath {
+    /// Share token
+    pub token: String,
+    /// Attachment ID
+    #[param(example = 1)]
+    pub id: i64,
+}
+
+/// Query parameters for a public share link
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct SharedContentQuery {
+    /// Response format: html (default) or json
+    #[param(example = "json")]
+    pub format: Option<String>,
+}
+
+/// Shares a note through a read-only link
+#[utoipa::path(
+    post,
+    path = "/api/notes/{id}/share",
+    params(SharePath),
+    request_body = CreateShareLinkRequest,
+    responses(
+        (status = 201, description = "Share link created successfully", body = ShareLinkResponse),
+        (status = 404, description = "Note not found", body = ErrorResponse),
+        (status = 422, description = "Validation error", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Sharing"
+)]
+pub async fn share_note(
+    pool: web::Data<DbPool>,
+    path: web::Path<SharePath>,
+    body: Option<web::Json<CreateShareLinkRequest>>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+
+    let request = body.map(|b| b.into_inner()).unwrap_or_default();
+    let link = ShareLink::create_for_note(&mut conn, path.id, request)?;
+
+    Ok(HttpResponse::Created().json(link.to_response(Utc::now())))
+}
+
+/// Shares all notes of a book through a read-only link
+#[utoipa::path(
+    post,
+    path = "/api/books/{id}/share",
+    params(SharePath),
+    request_body = CreateShareLinkRequest,
+    responses(
+        (status = 201, description = "Share link created successfully", body = ShareLinkResponse),
+        (status = 404, description = "Book not found", body = ErrorResponse),
+        (status = 422, description = "Validation error", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Sharing"
+)]
+pub async fn share_book(
+    pool: web::Data<DbPool>,
+    path: web::Path<SharePath>,
+    body: Option<web::Json<CreateShareLinkRequest>>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+
+    let request = body.map(|b| b.into_inner()).unwrap_or_default();
+    let link = ShareLink::create_for_book(&mut conn, path.id, request)?;
+
+    Ok(HttpResponse::Created().json(link.to_response(Utc::now())))
+}
+
+/// Lists share links, newest first
+#[utoipa::path(
+    get,
+    path = "/api/shares",
+    params(ShareListQuery),
+    responses(
+        (status = 200, description = "Share links retrieved successfully", body = [ShareLinkResponse]),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Sharing"
+)]
+pub async fn list_shares(
+    pool: web::Data<DbPool>,
+    query: web::Query<ShareListQuery>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+
+    let now = Utc::now();
+    let responses: Vec<ShareLinkResponse> = ShareLink::list(&mut conn, query.note_id, query.book_id)?
+        .iter()
+        .map(|link| link.to_response(now))
+        .collect();
+
+    Ok(HttpResponse::Ok().json(responses))
+}
+
+/// Revokes a share link
+#[utoipa::path(
+    delete,
+    path = "/api/shares/{id}",
+    params(SharePath),
+    responses(
+        (status = 204, description = "Share link revoked successfully"),
+        (status = 404, description = "Share link not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Sharing"
+)]
+pub async fn revoke_share(
+    pool: web::Data<DbPool>,
+    path: web::Path<SharePath>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+
+    ShareLink::revoke(&mut conn, path.id)?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Opens a share link
+///
+/// Public: the token alone grants read-only access to the shared note or
+/// book's notes, and nothing else.
+#[utoipa::path(
+    get,
+    path = "/s/{token}",
+    params(ShareTokenPath, SharedContentQuery),
+    responses(
+        (status = 200, description = "Shared notes as an HTML page or JSON", body = SharedContentResponse),
+        (status = 404, description = "Unknown, expired or revoked link", body = ErrorResponse),
+        (status = 422, description = "Unsupported format", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Sharing"
+)]
+pub async fn view_share(
+    pool: web::Data<DbPool>,
+    path: web::Path<ShareTokenPath>,
+    query: web::Query<SharedContentQuery>,
+) -> Result<HttpResponse, AppError> {
+    let as_json = match query.format.as_deref() {
+        None | Some("html") => false,
+        Some("json") => true,
+        Some(other) => return Err(AppError::ValidationError(format!("Unsupported format: {}", other))),
+    };
+
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+
+    let link = ShareLink::find_by_token(&mut conn, &path.token, Utc::now())?;
+    let content = link.shared_content(&mut conn)?;
+
+    // Revoked links must not linger in caches, and the token must not leak
+    // through links followed from the page
+    let mut response = HttpResponse::Ok();
+    response
+        .insert_header((header::CACHE_CONTROL, "no-store"))
+        .insert_header((header::REFERRER_POLICY, "no-referrer"));
+    if as_json {
+        Ok(response.json(content))
+    } else {
+        Ok(response.content_type("text/html; charset=utf-8").body(content.to_html()))
+    }
+}
+
+/// Downloads an image embedded in shared notes
+///
+/// Public: only attachments of the notes the token gives access to are served.
+#[utoipa::path(
+    get,
+    path = "/s/{token}/attachments/{id}",
+    params(ShareAttachmentPath),
+    responses(
+        (status = 200, description = "Image contents", content_type = "image/*"),
+        (status = 404, description = "Unknown link or attachment not shared", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Sharing"
+)]
+pub async fn view_share_attachment(
+    pool: web::Data<DbPool>,
+    path: web::Path<ShareAttachmentPath>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+
+    let link = ShareLink::find_by_token(&mut conn, &path.token, Utc::now())?;
+    let attachment = link.attachment(&mut conn, path.id)?;
+    let bytes = attachment.read()?;
+
+    Ok(HttpResponse::Ok()
+        .content_type(attachment.content_type.as_str())
+        .insert_header((header::CACHE_CONTROL, "no-store"))
+        .insert_header((header::REFERRER_POLICY, "no-referrer"))
+        .body(bytes))
+}
@@ -0,0 +1,40 @@
+//! Statistics HTTP handlers
+//!
+//! Reports how much was written in notes, per book and per month
+
+use actix_web::{web, HttpResponse, Result};
+use serde::Deserialize;
+use utoipa::IntoParams;
+use crate::db::DbPool;
+use crate::errors::AppError;
+use crate::models::writing_stats;
+
+/// Query parameters for the writing statistics
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct WritingStatsQuery {
+    /// Only include notes created in this year
+    #[param(example = 2024)]
+    pub year: Option<i32>,
+}
+
+/// Reports note counts, lengths and reading time per book and month
+#[utoipa::path(
+    get,
+    path = "/api/statistics/writing",
+    params(WritingStatsQuery),
+    responses(
+        (status = 200, description = "Writing statistics", body = WritingStatsResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Statistics"
+)]
+pub async fn get_writing_stats(
+    pool: web::Data<DbPool>,
+    query: web::Query<WritingStatsQuery>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+
+    let report = writing_stats::writing_report(&mut conn, query.year)?;
+
+    Ok(HttpResponse::Ok().json(report))
+}
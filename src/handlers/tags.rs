@@ -2,12 +2,13 @@
 //! 
 //! Provides RESTful API endpoints for tag CRUD operations
 
-use actix_web::{web, HttpResponse, Result};
+use actix_web::{web, HttpRequest, HttpResponse, Result};
 use serde::Deserialize;
 use utoipa::IntoParams;
 use crate::db::DbPool;
 use crate::errors::AppError;
-use crate::models::tag::{Tag, CreateTagRequest, UpdateTag, TagListResponse};
+use crate::models::tag::{Tag, CreateTagRequest, UpdateTag, PatchTagRequest, MergeTagRequest, TagListResponse};
+use crate::utils::patch::parse_merge_patch;
 
 /// Query parameters for tag listing
 #[derive(Debug, Deserialize, IntoParams)]
@@ -31,6 +32,14 @@ pub struct TagPath {
     pub id: i64,
 }
 
+/// Query parameters for tag deletion
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct DeleteTagQuery {
+    /// Tag that takes over the deleted tag's notes and books
+    #[param(example = 2)]
+    pub reassign_to: Option<i64>,
+}
+
 /// Query parameters for popular tags
 #[derive(Debug, Deserialize, IntoParams)]
 pub struct PopularTagsQuery {
@@ -195,14 +204,54 @@ pub async fn update_tag(
     Ok(HttpResponse::Ok().json(response))
 }
 
+/// Partially updates a tag
+#[utoipa::path(
+    patch,
+    path = "/api/tags/{id}",
+    params(TagPath),
+    request_body(content = PatchTagRequest, content_type = "application/merge-patch+json"),
+    responses(
+        (status = 200, description = "Tag updated successfully", body = TagResponse),
+        (status = 400, description = "Malformed patch document or tag name already exists", body = ErrorResponse),
+        (status = 404, description = "Tag not found", body = ErrorResponse),
+        (status = 422, description = "Validation error", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Tags"
+)]
+pub async fn patch_tag(
+    pool: web::Data<DbPool>,
+    path: web::Path<TagPath>,
+    req: HttpRequest,
+    body: web::Bytes,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+    
+    let patch: PatchTagRequest = parse_merge_patch(&req, &body)?;
+    let update_data = UpdateTag::try_from(patch)?;
+    if update_data.name.as_ref().is_some_and(|n| n.trim().is_empty()) {
+        return Err(AppError::ValidationError("Tag name cannot be empty".to_string()));
+    }
+
+    let tag = Tag::update(&mut conn, path.id, update_data)?;
+    let response = tag.to_response(&mut conn)?;
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
 /// Soft deletes a tag
+///
+/// Without `reassign_to`, the tag's associations are trashed with it and
+/// come back when it is restored. With `reassign_to`, they move to that
+/// tag first, as with a merge.
 #[utoipa::path(
     delete,
     path = "/api/tags/{id}",
-    params(TagPath),
+    params(TagPath, DeleteTagQuery),
     responses(
         (status = 204, description = "Tag deleted successfully"),
         (status = 404, description = "Tag not found", body = ErrorResponse),
+        (status = 422, description = "Tag cannot be reassigned to itself", body = ErrorResponse),
         (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
     tag = "Tags"
@@ -210,14 +259,73 @@ pub async fn update_tag(
 pub async fn delete_tag(
     pool: web::Data<DbPool>,
     path: web::Path<TagPath>,
+    query: web::Query<DeleteTagQuery>,
 ) -> Result<HttpResponse, AppError> {
     let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
     
-    Tag::soft_delete(&mut conn, path.id)?;
+    match query.reassign_to {
+        Some(target_id) => Tag::merge(&mut conn, path.id, target_id).map(|_| ())?,
+        None => Tag::soft_delete(&mut conn, path.id)?,
+    }
 
     Ok(HttpResponse::NoContent().finish())
 }
 
+/// Merges a tag into another
+///
+/// The tag's notes and books move to the target tag and the tag is soft
+/// deleted. Returns the target tag with its updated counts.
+#[utoipa::path(
+    post,
+    path = "/api/tags/{id}/merge",
+    params(TagPath),
+    request_body = MergeTagRequest,
+    responses(
+        (status = 200, description = "Tags merged successfully", body = TagResponse),
+        (status = 404, description = "Tag not found", body = ErrorResponse),
+        (status = 422, description = "Tag cannot be merged into itself", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Tags"
+)]
+pub async fn merge_tag(
+    pool: web::Data<DbPool>,
+    path: web::Path<TagPath>,
+    merge_data: web::Json<MergeTagRequest>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+
+    let tag = Tag::merge(&mut conn, path.id, merge_data.target_id)?;
+    let response = tag.to_response(&mut conn)?;
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Restores a soft deleted tag from the trash
+#[utoipa::path(
+    post,
+    path = "/api/tags/{id}/restore",
+    params(TagPath),
+    responses(
+        (status = 200, description = "Tag restored successfully", body = TagResponse),
+        (status = 404, description = "Tag not found in trash", body = ErrorResponse),
+        (status = 409, description = "An active tag already uses the slug", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Tags"
+)]
+pub async fn restore_tag(
+    pool: web::Data<DbPool>,
+    path: web::Path<TagPath>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+    
+    let tag = Tag::restore(&mut conn, path.id)?;
+    let response = tag.to_response(&mut conn)?;
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
 #[cfg(test)]
 mod tests {
     // Unit tests can be added here
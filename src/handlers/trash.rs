@@ -0,0 +1,67 @@
+//! Trash HTTP handlers
+//! 
+//! Lists soft deleted records and permanently purges them
+
+use actix_web::{web, HttpResponse, Result};
+use serde::Deserialize;
+use utoipa::IntoParams;
+use crate::db::DbPool;
+use crate::errors::AppError;
+use crate::models::trash::{self, TrashEntity};
+
+/// Path parameters for purging a trashed record
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct TrashItemPath {
+    /// Entity type: books, notes or tags
+    #[param(example = "books")]
+    pub entity: String,
+    /// Record ID
+    #[param(example = 1)]
+    pub id: i64,
+}
+
+/// Lists soft deleted books, notes and tags
+#[utoipa::path(
+    get,
+    path = "/api/trash",
+    responses(
+        (status = 200, description = "Trash retrieved successfully", body = TrashResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Trash"
+)]
+pub async fn list_trash(
+    pool: web::Data<DbPool>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+    
+    let response = trash::list(&mut conn)?;
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Permanently deletes a record from the trash
+/// 
+/// Only records that are already soft deleted can be purged.
+#[utoipa::path(
+    delete,
+    path = "/api/trash/{entity}/{id}",
+    params(TrashItemPath),
+    responses(
+        (status = 204, description = "Record purged successfully"),
+        (status = 404, description = "Record not found in trash", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Trash"
+)]
+pub async fn purge_trash_item(
+    pool: web::Data<DbPool>,
+    path: web::Path<TrashItemPath>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.get().map_err(|_| AppError::InternalError)?;
+    
+    let entity: TrashEntity = path.entity.parse()?;
+    trash::purge(&mut conn, entity, path.id)?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
@@ -29,20 +29,81 @@ use utoipa_swagger_ui::SwaggerUi;
         handlers::books::get_book,
         handlers::books::list_books,
         handlers::books::update_book,
+        handlers::books::patch_book,
         handlers::books::delete_book,
+        handlers::books::restore_book,
+        handlers::acquisition::list_wishlist,
+        handlers::acquisition::acquire_book,
+        handlers::acquisition::get_spending,
         handlers::notes::create_note,
         handlers::notes::get_note,
         handlers::notes::list_notes,
         handlers::notes::get_book_notes,
+        handlers::notes::reorder_book_notes,
         handlers::notes::update_note,
+        handlers::notes::patch_note,
         handlers::notes::update_note_tags,
         handlers::notes::delete_note,
+        handlers::notes::restore_note,
+        handlers::notes::get_note_backlinks,
+        handlers::notes::get_note_thread,
+        handlers::notes::bulk_notes,
+        handlers::attachments::upload_attachments,
+        handlers::attachments::get_attachment,
+        handlers::attachments::delete_attachment,
+        handlers::note_revisions::list_note_revisions,
+        handlers::note_revisions::diff_note_revisions,
+        handlers::note_revisions::restore_note_revision,
         handlers::tags::create_tag,
         handlers::tags::get_tag,
         handlers::tags::list_tags,
         handlers::tags::get_popular_tags,
         handlers::tags::update_tag,
+        handlers::tags::patch_tag,
         handlers::tags::delete_tag,
+        handlers::tags::restore_tag,
+        handlers::tags::merge_tag,
+        handlers::reading_status::get_reading_status,
+        handlers::reading_status::update_reading_status,
+        handlers::trash::list_trash,
+        handlers::trash::purge_trash_item,
+        handlers::loans::create_loan,
+        handlers::loans::get_book_loans,
+        handlers::loans::list_loans,
+        handlers::loans::return_loan,
+        handlers::locations::create_location,
+        handlers::locations::list_locations,
+        handlers::locations::get_location,
+        handlers::locations::update_location,
+        handlers::locations::delete_location,
+        handlers::locations::get_location_books,
+        handlers::custom_fields::create_custom_field,
+        handlers::custom_fields::list_custom_fields,
+        handlers::custom_fields::update_custom_field,
+        handlers::custom_fields::delete_custom_field,
+        handlers::note_types::create_note_type,
+        handlers::note_types::list_note_types,
+        handlers::note_types::get_note_type,
+        handlers::note_types::update_note_type,
+        handlers::note_types::delete_note_type,
+        handlers::note_templates::create_note_template,
+        handlers::note_templates::list_note_templates,
+        handlers::note_templates::get_note_template,
+        handlers::note_templates::update_note_template,
+        handlers::note_templates::delete_note_template,
+        handlers::note_templates::create_note_from_template,
+        handlers::review::get_due_reviews,
+        handlers::review::review_note,
+        handlers::digest::get_daily_digest,
+        handlers::statistics::get_writing_stats,
+        handlers::shares::share_note,
+        handlers::shares::share_book,
+        handlers::shares::list_shares,
+        handlers::shares::revoke_share,
+        handlers::shares::view_share,
+        handlers::shares::view_share_attachment,
+        handlers::recommendations::get_similar_books,
+        handlers::recommendations::get_next_reads,
     ),
     components(
         schemas(
@@ -50,23 +111,101 @@ use utoipa_swagger_ui::SwaggerUi;
             models::book::BookResponse,
             models::book::BookListResponse,
             models::book::UpdateBook,
+            models::book::PatchBookRequest,
+            models::book::BookFormat,
+            models::book::AcquisitionStatus,
+            models::acquisition::AcquireBookRequest,
+            models::acquisition::SpendingPeriod,
+            models::acquisition::SpendingEntry,
             models::note::CreateNoteRequest,
             models::note::NoteResponse,
             models::note::NoteListResponse,
             models::note::UpdateReadingNote,
-            models::note::NoteType,
+            models::note::PatchNoteRequest,
+            models::note::NoteSort,
+            models::note::ReorderNotesRequest,
+            models::note::NoteThreadResponse,
+            models::note_bulk::BulkNoteRequest,
+            models::note_bulk::BulkNoteResult,
+            models::note_bulk::BulkNoteResponse,
+            models::note_location::NoteLocation,
+            models::attachment::AttachmentUploadForm,
+            models::attachment::AttachmentResponse,
+            models::note_link::LinkTargetType,
+            models::note_link::NoteLinkResponse,
+            models::note_revision::NoteRevisionResponse,
+            models::note_revision::NoteDiffResponse,
+            models::note_revision::DiffSegment,
+            models::note_revision::DiffOp,
             models::tag::CreateTagRequest,
             models::tag::TagResponse,
             models::tag::TagListResponse,
             models::tag::PopularTagResponse,
             models::tag::UpdateTag,
+            models::tag::PatchTagRequest,
+            models::tag::MergeTagRequest,
+            models::reading_status::UpdateReadingStatusRequest,
+            models::reading_status::ReadingStatusResponse,
+            models::trash::TrashItem,
+            models::trash::TrashResponse,
+            models::loan::LoanState,
+            models::loan::CreateLoanRequest,
+            models::loan::ReturnLoanRequest,
+            models::loan::LoanResponse,
+            models::loan::LoanListResponse,
+            models::location::LocationKind,
+            models::location::CreateLocationRequest,
+            models::location::UpdateLocation,
+            models::location::LocationResponse,
+            models::custom_field::CustomFieldType,
+            models::custom_field::CreateCustomFieldRequest,
+            models::custom_field::UpdateCustomFieldRequest,
+            models::custom_field::CustomFieldResponse,
+            models::note_type::CreateNoteTypeRequest,
+            models::note_type::UpdateNoteTypeRequest,
+            models::note_type::NoteTypeResponse,
+            models::note_template::CreateNoteTemplateRequest,
+            models::note_template::UpdateNoteTemplateRequest,
+            models::note_template::NoteTemplateResponse,
+            models::note_template::CreateNoteFromTemplateRequest,
+            models::review::ReviewGrade,
+            models::review::ReviewRequest,
+            models::review::ReviewStateResponse,
+            models::review::DueNoteResponse,
+            models::review::DueReviewResponse,
+            models::digest::OnThisDayNote,
+            models::digest::DigestResponse,
+            models::writing_stats::WritingTotals,
+            models::writing_stats::BookWritingStats,
+            models::writing_stats::MonthWritingStats,
+            models::writing_stats::WritingStatsResponse,
+            models::share_link::CreateShareLinkRequest,
+            models::share_link::ShareLinkResponse,
+            models::share_link::SharedBook,
+            models::share_link::SharedNote,
+            models::share_link::SharedContentResponse,
+            models::recommendation::SimilarBook,
+            models::recommendation::Recommendation,
             errors::ErrorResponse,
         )
     ),
     tags(
         (name = "Books", description = "Book management operations"),
         (name = "Notes", description = "Reading note management operations"),
-        (name = "Tags", description = "Tag management operations")
+        (name = "Tags", description = "Tag management operations"),
+        (name = "Reading Status", description = "Reading status and progress tracking"),
+        (name = "Trash", description = "Soft deleted records and permanent purge"),
+        (name = "Loans", description = "Book lending tracker"),
+        (name = "Acquisition", description = "Wishlist, purchases and spending"),
+        (name = "Locations", description = "Physical locations of the home library"),
+        (name = "Custom Fields", description = "User-defined fields on books"),
+        (name = "Note Types", description = "Built-in and user-defined note types"),
+        (name = "Note Templates", description = "Reusable templates for new notes"),
+        (name = "Recommendations", description = "Similar books and what to read next"),
+        (name = "Review", description = "Spaced-repetition review of notes"),
+        (name = "Digest", description = "Daily highlights and notes from this day in past years"),
+        (name = "Statistics", description = "How much was written in notes"),
+        (name = "Sharing", description = "Read-only links to notes and books")
     ),
     info(
         title = "Personal Reading Notes API",
@@ -117,6 +256,9 @@ pub fn create_app(pool: DbPool) -> App<impl actix_web::dev::ServiceFactory<
         )
         // Configure API routes
         .service(configure_api_routes())
+        // Public read-only share links
+        .route("/s/{token}", web::get().to(handlers::shares::view_share))
+        .route("/s/{token}/attachments/{id}", web::get().to(handlers::shares::view_share_attachment))
         // Serve static files (frontend) - must be last
         .service(fs::Files::new("/", "./static").index_file("index.html"))
 }
@@ -131,7 +273,7 @@ fn configure_cors() -> Cors {
         .allowed_origin("http://localhost:3000")  // React default
         .allowed_origin("http://localhost:5173")  // Vite default
         // Allow standard HTTP methods
-        .allowed_methods(vec!["GET", "POST", "PUT", "DELETE"])
+        .allowed_methods(vec!["GET", "POST", "PUT", "PATCH", "DELETE"])
         // Allow necessary headers
         .allowed_headers(vec!["Content-Type", "Authorization"])
         // Cache preflight requests for 1 hour
@@ -149,8 +291,31 @@ fn configure_api_routes() -> actix_web::Scope {
         .service(configure_note_routes())
         // Tag management routes
         .service(configure_tag_routes())
+        // Trash routes
+        .service(configure_trash_routes())
+        // Loan routes
+        .service(configure_loan_routes())
+        // Location routes
+        .service(configure_location_routes())
+        // Custom field routes
+        .service(configure_custom_field_routes())
+        // Note type routes
+        .service(configure_note_type_routes())
+        // Note template routes
+        .service(configure_note_template_routes())
+        // Recommendation routes
+        .service(configure_recommendation_routes())
+        // Review routes
+        .service(configure_review_routes())
+        // Digest routes
+        .service(configure_digest_routes())
+        // Statistics routes
+        .service(configure_statistics_routes())
+        // Share link routes
+        .service(configure_share_routes())
+        // Attachment routes
+        .service(configure_attachment_routes())
         // TODO: Add category routes
-        // TODO: Add reading status routes
 }
 
 /// Configures book management routes
@@ -158,10 +323,22 @@ fn configure_book_routes() -> actix_web::Scope {
     web::scope("/books")
         .route("", web::post().to(handlers::books::create_book))
         .route("", web::get().to(handlers::books::list_books))
+        .route("/wishlist", web::get().to(handlers::acquisition::list_wishlist))
+        .route("/spending", web::get().to(handlers::acquisition::get_spending))
         .route("/{id}", web::get().to(handlers::books::get_book))
         .route("/{id}", web::put().to(handlers::books::update_book))
+        .route("/{id}", web::patch().to(handlers::books::patch_book))
         .route("/{id}", web::delete().to(handlers::books::delete_book))
+        .route("/{id}/restore", web::post().to(handlers::books::restore_book))
+        .route("/{id}/acquire", web::post().to(handlers::acquisition::acquire_book))
+        .route("/{id}/share", web::post().to(handlers::shares::share_book))
+        .route("/{id}/similar", web::get().to(handlers::recommendations::get_similar_books))
         .route("/{book_id}/notes", web::get().to(handlers::notes::get_book_notes))
+        .route("/{book_id}/notes/order", web::put().to(handlers::notes::reorder_book_notes))
+        .route("/{book_id}/status", web::get().to(handlers::reading_status::get_reading_status))
+        .route("/{book_id}/status", web::put().to(handlers::reading_status::update_reading_status))
+        .route("/{book_id}/loans", web::post().to(handlers::loans::create_loan))
+        .route("/{book_id}/loans", web::get().to(handlers::loans::get_book_loans))
 }
 
 /// Configures note management routes
@@ -169,10 +346,21 @@ fn configure_note_routes() -> actix_web::Scope {
     web::scope("/notes")
         .route("", web::post().to(handlers::notes::create_note))
         .route("", web::get().to(handlers::notes::list_notes))
+        .route("/bulk", web::post().to(handlers::notes::bulk_notes))
+        .route("/from-template", web::post().to(handlers::note_templates::create_note_from_template))
         .route("/{id}", web::get().to(handlers::notes::get_note))
         .route("/{id}", web::put().to(handlers::notes::update_note))
+        .route("/{id}", web::patch().to(handlers::notes::patch_note))
         .route("/{id}", web::delete().to(handlers::notes::delete_note))
+        .route("/{id}/restore", web::post().to(handlers::notes::restore_note))
         .route("/{id}/tags", web::put().to(handlers::notes::update_note_tags))
+        .route("/{id}/backlinks", web::get().to(handlers::notes::get_note_backlinks))
+        .route("/{id}/thread", web::get().to(handlers::notes::get_note_thread))
+        .route("/{id}/share", web::post().to(handlers::shares::share_note))
+        .route("/{id}/attachments", web::post().to(handlers::attachments::upload_attachments))
+        .route("/{id}/revisions", web::get().to(handlers::note_revisions::list_note_revisions))
+        .route("/{id}/revisions/diff", web::get().to(handlers::note_revisions::diff_note_revisions))
+        .route("/{id}/revisions/{rev}/restore", web::post().to(handlers::note_revisions::restore_note_revision))
 }
 
 /// Configures tag management routes
@@ -183,7 +371,103 @@ fn configure_tag_routes() -> actix_web::Scope {
         .route("/popular", web::get().to(handlers::tags::get_popular_tags))
         .route("/{id}", web::get().to(handlers::tags::get_tag))
         .route("/{id}", web::put().to(handlers::tags::update_tag))
+        .route("/{id}", web::patch().to(handlers::tags::patch_tag))
         .route("/{id}", web::delete().to(handlers::tags::delete_tag))
+        .route("/{id}/restore", web::post().to(handlers::tags::restore_tag))
+        .route("/{id}/merge", web::post().to(handlers::tags::merge_tag))
+}
+
+/// Configures loan routes
+fn configure_loan_routes() -> actix_web::Scope {
+    web::scope("/loans")
+        .route("", web::get().to(handlers::loans::list_loans))
+        .route("/{id}/return", web::post().to(handlers::loans::return_loan))
+}
+
+/// Configures location routes
+fn configure_location_routes() -> actix_web::Scope {
+    web::scope("/locations")
+        .route("", web::post().to(handlers::locations::create_location))
+        .route("", web::get().to(handlers::locations::list_locations))
+        .route("/{id}", web::get().to(handlers::locations::get_location))
+        .route("/{id}", web::put().to(handlers::locations::update_location))
+        .route("/{id}", web::delete().to(handlers::locations::delete_location))
+        .route("/{id}/books", web::get().to(handlers::locations::get_location_books))
+}
+
+/// Configures custom field routes
+fn configure_custom_field_routes() -> actix_web::Scope {
+    web::scope("/custom-fields")
+        .route("", web::post().to(handlers::custom_fields::create_custom_field))
+        .route("", web::get().to(handlers::custom_fields::list_custom_fields))
+        .route("/{id}", web::put().to(handlers::custom_fields::update_custom_field))
+        .route("/{id}", web::delete().to(handlers::custom_fields::delete_custom_field))
+}
+
+/// Configures note type routes
+fn configure_note_type_routes() -> actix_web::Scope {
+    web::scope("/note-types")
+        .route("", web::post().to(handlers::note_types::create_note_type))
+        .route("", web::get().to(handlers::note_types::list_note_types))
+        .route("/{id}", web::get().to(handlers::note_types::get_note_type))
+        .route("/{id}", web::put().to(handlers::note_types::update_note_type))
+        .route("/{id}", web::delete().to(handlers::note_types::delete_note_type))
+}
+
+/// Configures note template routes
+fn configure_note_template_routes() -> actix_web::Scope {
+    web::scope("/note-templates")
+        .route("", web::post().to(handlers::note_templates::create_note_template))
+        .route("", web::get().to(handlers::note_templates::list_note_templates))
+        .route("/{id}", web::get().to(handlers::note_templates::get_note_template))
+        .route("/{id}", web::put().to(handlers::note_templates::update_note_template))
+        .route("/{id}", web::delete().to(handlers::note_templates::delete_note_template))
+}
+
+/// Configures recommendation routes
+fn configure_recommendation_routes() -> actix_web::Scope {
+    web::scope("/recommendations")
+        .route("/next", web::get().to(handlers::recommendations::get_next_reads))
+}
+
+/// Configures review routes
+fn configure_review_routes() -> actix_web::Scope {
+    web::scope("/review")
+        .route("/due", web::get().to(handlers::review::get_due_reviews))
+        .route("/{note_id}", web::post().to(handlers::review::review_note))
+}
+
+/// Configures digest routes
+fn configure_digest_routes() -> actix_web::Scope {
+    web::scope("/digest")
+        .route("/today", web::get().to(handlers::digest::get_daily_digest))
+}
+
+/// Configures statistics routes
+fn configure_statistics_routes() -> actix_web::Scope {
+    web::scope("/statistics")
+        .route("/writing", web::get().to(handlers::statistics::get_writing_stats))
+}
+
+/// Configures share link routes
+fn configure_share_routes() -> actix_web::Scope {
+    web::scope("/shares")
+        .route("", web::get().to(handlers::shares::list_shares))
+        .route("/{id}", web::delete().to(handlers::shares::revoke_share))
+}
+
+/// Configures attachment routes
+fn configure_attachment_routes() -> actix_web::Scope {
+    web::scope("/attachments")
+        .route("/{id}", web::get().to(handlers::attachments::get_attachment))
+        .route("/{id}", web::delete().to(handlers::attachments::delete_attachment))
+}
+
+/// Configures trash routes
+fn configure_trash_routes() -> actix_web::Scope {
+    web::scope("/trash")
+        .route("", web::get().to(handlers::trash::list_trash))
+        .route("/{entity}/{id}", web::delete().to(handlers::trash::purge_trash_item))
 }
 
 /// Health check endpoint
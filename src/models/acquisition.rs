@@ -0,0 +1,145 @@
+use bigdecimal::{BigDecimal, Signed};
+use chrono::NaiveDate;
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Nullable, Numeric, Text, Varchar};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use crate::errors::{AppError, Result};
+
+/// Longest purchase place, matching its column
+pub const MAX_PURCHASE_PLACE_LENGTH: usize = 100;
+
+/// Request structure for moving a wishlist book to owned
+#[derive(Debug, Default, Deserialize, Serialize, ToSchema)]
+pub struct AcquireBookRequest {
+    /// Purchase date (default: today)
+    #[schema(example = "2024-03-15")]
+    pub purchase_date: Option<NaiveDate>,
+
+    /// Purchase price as a decimal string
+    #[schema(value_type = Option<String>, example = "59.90")]
+    pub purchase_price: Option<BigDecimal>,
+
+    /// ISO 4217 currency code, required with a price
+    #[schema(example = "CNY")]
+    pub currency: Option<String>,
+
+    #[schema(example = "JD.com")]
+    pub purchase_place: Option<String>,
+}
+
+/// Grouping period for the spending report
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SpendingPeriod {
+    #[default]
+    Month,
+    Year,
+}
+
+impl SpendingPeriod {
+    /// PostgreSQL `to_char` pattern for the period label
+    fn date_format(&self) -> &'static str {
+        match self {
+            SpendingPeriod::Month => "YYYY-MM",
+            SpendingPeriod::Year => "YYYY",
+        }
+    }
+}
+
+/// Spending total for one period and currency
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SpendingEntry {
+    /// Period label, e.g. "2024-03" or "2024"
+    #[schema(example = "2024-03")]
+    pub period: String,
+
+    #[schema(example = "CNY")]
+    pub currency: Option<String>,
+
+    /// Sum of purchase prices as a decimal string
+    #[schema(value_type = String, example = "189.70")]
+    pub total: BigDecimal,
+
+    #[schema(example = 3)]
+    pub book_count: i64,
+}
+
+#[derive(QueryableByName)]
+struct SpendingRow {
+    #[diesel(sql_type = Text)]
+    period: String,
+    #[diesel(sql_type = Nullable<Varchar>)]
+    currency: Option<String>,
+    #[diesel(sql_type = Numeric)]
+    total: BigDecimal,
+    #[diesel(sql_type = BigInt)]
+    book_count: i64,
+}
+
+/// Validates a purchase price and its currency
+pub fn validate_purchase(price: Option<&BigDecimal>, currency: Option<&str>) -> Result<()> {
+    if price.is_some_and(Signed::is_negative) {
+        return Err(AppError::ValidationError("Purchase price cannot be negative".to_string()));
+    }
+    if let Some(currency) = currency {
+        let currency = currency.trim();
+        if currency.len() != 3 || !currency.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(AppError::ValidationError(format!(
+                "Invalid currency '{}', expected a 3-letter ISO 4217 code",
+                currency
+            )));
+        }
+    } else if price.is_some() {
+        return Err(AppError::ValidationError("Currency is required with a purchase price".to_string()));
+    }
+    Ok(())
+}
+
+/// Sums purchase prices of owned books per period and currency, newest first
+pub fn spending_report(
+    conn: &mut PgConnection,
+    period: SpendingPeriod,
+    year: Option<i32>,
+) -> Result<Vec<SpendingEntry>> {
+    let rows = diesel::sql_query(
+        "SELECT to_char(purchase_date, $1) AS period, currency, \
+                SUM(purchase_price) AS total, COUNT(*) AS book_count \
+         FROM books \
+         WHERE deleted_at IS NULL \
+           AND acquisition_status = 'owned' \
+           AND purchase_date IS NOT NULL \
+           AND purchase_price IS NOT NULL \
+           AND ($2 IS NULL OR EXTRACT(YEAR FROM purchase_date) = $2) \
+         GROUP BY 1, 2 \
+         ORDER BY 1 DESC, 2",
+    )
+    .bind::<Text, _>(period.date_format())
+    .bind::<Nullable<diesel::sql_types::Integer>, _>(year)
+    .load::<SpendingRow>(conn)?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| SpendingEntry {
+            period: row.period,
+            currency: row.currency,
+            total: row.total,
+            book_count: row.book_count,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_purchase() {
+        let price = |p: &str| p.parse::<BigDecimal>().unwrap();
+        assert!(validate_purchase(Some(&price("59.90")), Some("CNY")).is_ok());
+        assert!(validate_purchase(None, None).is_ok());
+        assert!(validate_purchase(Some(&price("-1")), Some("USD")).is_err());
+        assert!(validate_purchase(Some(&price("10")), None).is_err());
+        assert!(validate_purchase(None, Some("EURO")).is_err());
+    }
+}
@@ -0,0 +1,309 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use crate::db::schema::{note_attachments, reading_notes};
+use crate::errors::{AppError, Result};
+use crate::models::note::ReadingNote;
+use crate::utils::markdown::{self, ATTACHMENT_URL_SCHEME};
+
+/// Largest accepted attachment in bytes
+pub const MAX_ATTACHMENT_SIZE: usize = 10 * 1024 * 1024;
+/// Most files accepted in one upload request
+pub const MAX_ATTACHMENTS_PER_UPLOAD: usize = 10;
+/// Largest accepted total of all files in one upload request, in bytes
+pub const MAX_UPLOAD_SIZE: usize = 25 * 1024 * 1024;
+/// Longest accepted filename, in characters
+const MAX_FILENAME_LENGTH: usize = 255;
+/// Directory used when `ATTACHMENTS_DIR` is not set
+const DEFAULT_ATTACHMENTS_DIR: &str = "./uploads";
+
+/// Directory where attachment files are stored
+pub fn attachments_dir() -> PathBuf {
+    env::var("ATTACHMENTS_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from(DEFAULT_ATTACHMENTS_DIR))
+}
+
+/// Detects the image type from the file's magic bytes
+///
+/// Returns the content type and file extension, or `None` for anything
+/// that is not a PNG, JPEG, GIF or WebP image.
+pub fn detect_image_type(bytes: &[u8]) -> Option<(&'static str, &'static str)> {
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some(("image/png", "png"))
+    } else if bytes.starts_with(b"\xff\xd8\xff") {
+        Some(("image/jpeg", "jpg"))
+    } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        Some(("image/gif", "gif"))
+    } else if bytes.len() >= 12 && bytes.starts_with(b"RIFF") && &bytes[8..12] == b"WEBP" {
+        Some(("image/webp", "webp"))
+    } else {
+        None
+    }
+}
+
+/// Image attached to a note
+#[derive(Debug, Serialize, Deserialize, Queryable, Selectable, Identifiable)]
+#[diesel(table_name = note_attachments)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct NoteAttachment {
+    pub id: i64,
+    pub note_id: i64,
+    pub filename: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub checksum: String,
+    pub storage_key: String,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// New note attachment for insertion
+#[derive(Debug, Insertable)]
+#[diesel(table_name = note_attachments)]
+pub struct NewNoteAttachment {
+    pub note_id: i64,
+    pub filename: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub checksum: String,
+    pub storage_key: String,
+}
+
+/// Uploaded file before it is stored
+#[derive(Debug)]
+pub struct AttachmentUpload {
+    pub filename: String,
+    pub bytes: Vec<u8>,
+}
+
+/// Multipart form for uploading attachments (documentation only)
+#[derive(Debug, ToSchema)]
+pub struct AttachmentUploadForm {
+    /// One or more image files
+    #[schema(value_type = Vec<String>, format = Binary)]
+    pub file: Vec<Vec<u8>>,
+}
+
+/// Note attachment response
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AttachmentResponse {
+    #[schema(example = 1)]
+    pub id: i64,
+
+    #[schema(example = 1)]
+    pub note_id: i64,
+
+    #[schema(example = "diagram.png")]
+    pub filename: String,
+
+    #[schema(example = "image/png")]
+    pub content_type: String,
+
+    #[schema(example = 48213)]
+    pub size_bytes: i64,
+
+    /// SHA-256 of the file contents, hex encoded
+    #[schema(example = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08")]
+    pub checksum: String,
+
+    /// Where the image can be downloaded
+    #[schema(example = "/api/attachments/1")]
+    pub url: String,
+
+    /// Markdown that embeds the image in note content
+    #[schema(example = "![diagram.png](attachment:1)")]
+    pub markdown: String,
+
+    #[schema(example = "2024-01-01T12:00:00Z")]
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl From<NoteAttachment> for AttachmentResponse {
+    fn from(attachment: NoteAttachment) -> Self {
+        AttachmentResponse {
+            url: format!("/api/attachments/{}", attachment.id),
+            markdown: format!("![{}]({}{})", markdown::escape_link_text(&attachment.filename), ATTACHMENT_URL_SCHEME, attachment.id),
+            id: attachment.id,
+            note_id: attachment.note_id,
+            filename: attachment.filename,
+            content_type: attachment.content_type,
+            size_bytes: attachment.size_bytes,
+            checksum: attachment.checksum.trim_end().to_string(),
+            created_at: attachment.created_at,
+        }
+    }
+}
+
+impl AttachmentUpload {
+    /// Checks that the upload is a supported image within the size limit
+    pub fn validate(&self) -> Result<()> {
+        if self.filename.chars().count() > MAX_FILENAME_LENGTH {
+            return Err(AppError::ValidationError(format!(
+                "Filename cannot exceed {} characters",
+                MAX_FILENAME_LENGTH
+            )));
+        }
+        if self.bytes.is_empty() {
+            return Err(AppError::ValidationError(format!("File '{}' is empty", self.filename)));
+        }
+        if self.bytes.len() > MAX_ATTACHMENT_SIZE {
+            return Err(AppError::ValidationError(format!(
+                "File '{}' exceeds the {} MB limit",
+                self.filename,
+                MAX_ATTACHMENT_SIZE / (1024 * 1024)
+            )));
+        }
+        if detect_image_type(&self.bytes).is_none() {
+            return Err(AppError::ValidationError(format!(
+                "File '{}' is not a PNG, JPEG, GIF or WebP image",
+                self.filename
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl NoteAttachment {
+    /// Stores uploaded images and attaches them to a note
+    ///
+    /// All uploads are validated before anything is written. Files written
+    /// before a failure are removed again.
+    pub fn create_all(
+        conn: &mut PgConnection,
+        note_id: i64,
+        uploads: Vec<AttachmentUpload>,
+    ) -> Result<Vec<NoteAttachment>> {
+        ReadingNote::find_by_id(conn, note_id)?;
+        for upload in &uploads {
+            upload.validate()?;
+        }
+
+        let dir = attachments_dir();
+        fs::create_dir_all(&dir).map_err(|e| {
+            log::error!("Failed to create attachment directory {}: {}", dir.display(), e);
+            AppError::InternalError
+        })?;
+
+        let mut written = Vec::new();
+        let result = conn.transaction(|conn| {
+            let mut attachments = Vec::new();
+            for upload in uploads {
+                let (content_type, extension) = detect_image_type(&upload.bytes).ok_or(AppError::InternalError)?;
+                let storage_key = format!("{}.{}", Uuid::now_v7(), extension);
+
+                fs::write(dir.join(&storage_key), &upload.bytes).map_err(|e| {
+                    log::error!("Failed to write attachment {}: {}", storage_key, e);
+                    AppError::InternalError
+                })?;
+                written.push(storage_key.clone());
+
+                let new_attachment = NewNoteAttachment {
+                    note_id,
+                    filename: upload.filename,
+                    content_type: content_type.to_string(),
+                    size_bytes: upload.bytes.len() as i64,
+                    checksum: format!("{:x}", Sha256::digest(&upload.bytes)),
+                    storage_key,
+                };
+                attachments.push(
+                    diesel::insert_into(note_attachments::table)
+                        .values(&new_attachment)
+                        .returning(NoteAttachment::as_returning())
+                        .get_result(conn)?,
+                );
+            }
+            Ok(attachments)
+        });
+
+        if result.is_err() {
+            remove_files(&written);
+        }
+        result
+    }
+
+    /// Finds an attachment of an active note
+    pub fn find_by_id(conn: &mut PgConnection, attachment_id: i64) -> Result<NoteAttachment> {
+        note_attachments::table
+            .inner_join(reading_notes::table)
+            .filter(note_attachments::id.eq(attachment_id))
+            .filter(reading_notes::deleted_at.is_null())
+            .select(NoteAttachment::as_select())
+            .first(conn)
+            .map_err(|e| match e {
+                diesel::result::Error::NotFound => {
+                    AppError::NotFound(format!("Attachment with id {} not found", attachment_id))
+                }
+                _ => AppError::from(e),
+            })
+    }
+
+    /// Lists the attachments of a note, oldest first
+    pub fn list_for_note(conn: &mut PgConnection, note_id: i64) -> Result<Vec<NoteAttachment>> {
+        note_attachments::table
+            .filter(note_attachments::note_id.eq(note_id))
+            .order(note_attachments::id)
+            .select(NoteAttachment::as_select())
+            .load(conn)
+            .map_err(AppError::from)
+    }
+
+    /// Storage keys of all attachments on the given notes
+    pub fn storage_keys(conn: &mut PgConnection, note_ids: &[i64]) -> Result<Vec<String>> {
+        note_attachments::table
+            .filter(note_attachments::note_id.eq_any(note_ids))
+            .select(note_attachments::storage_key)
+            .load(conn)
+            .map_err(AppError::from)
+    }
+
+    /// Reads the stored file
+    pub fn read(&self) -> Result<Vec<u8>> {
+        fs::read(attachments_dir().join(&self.storage_key)).map_err(|e| {
+            log::error!("Failed to read attachment {}: {}", self.storage_key, e);
+            AppError::InternalError
+        })
+    }
+
+    /// Deletes an attachment and its file
+    pub fn delete(conn: &mut PgConnection, attachment_id: i64) -> Result<()> {
+        let attachment = Self::find_by_id(conn, attachment_id)?;
+
+        diesel::delete(note_attachments::table.find(attachment.id)).execute(conn)?;
+        remove_files(&[attachment.storage_key]);
+
+        Ok(())
+    }
+}
+
+/// Removes stored attachment files, logging files that cannot be removed
+pub fn remove_files(storage_keys: &[String]) {
+    let dir = attachments_dir();
+    for key in storage_keys {
+        if let Err(e) = fs::remove_file(dir.join(key)) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                log::warn!("Failed to remove attachment {}: {}", key, e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_image_type() {
+        assert_eq!(detect_image_type(b"\x89PNG\r\n\x1a\n...."), Some(("image/png", "png")));
+        assert_eq!(detect_image_type(b"\xff\xd8\xff\xe0"), Some(("image/jpeg", "jpg")));
+        assert_eq!(detect_image_type(b"GIF89a"), Some(("image/gif", "gif")));
+        assert_eq!(detect_image_type(b"RIFF\0\0\0\0WEBPVP8 "), Some(("image/webp", "webp")));
+        assert_eq!(detect_image_type(b"<svg onload=alert(1)>"), None);
+        assert_eq!(detect_image_type(b""), None);
+    }
+}
@@ -1,9 +1,107 @@
+use std::str::FromStr;
+use bigdecimal::{BigDecimal, Signed};
 use chrono::{NaiveDate, DateTime, Utc};
+use diesel::dsl::sql;
 use diesel::prelude::*;
+use diesel::sql_types::{Nullable, Numeric, Text};
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use utoipa::ToSchema;
+use uuid::Uuid;
 use crate::db::schema::books;
 use crate::errors::{AppError, Result};
+use crate::models::acquisition::{validate_purchase, AcquireBookRequest, MAX_PURCHASE_PLACE_LENGTH};
+use crate::models::custom_field::{merge_custom_fields, validate_custom_fields, CustomFieldDefinition, CustomFieldType};
+use crate::models::loan::LoanResponse;
+use crate::models::note_link::NoteLink;
+use crate::models::tag::Tag;
+use crate::utils::patch::{nullable, required};
+use crate::utils::validation::optional_text;
+
+/// Format of an owned edition
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BookFormat {
+    Hardcover,
+    #[default]
+    Paperback,
+    Ebook,
+    Audiobook,
+}
+
+impl BookFormat {
+    /// Returns the value stored in the `books.format` column
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BookFormat::Hardcover => "hardcover",
+            BookFormat::Paperback => "paperback",
+            BookFormat::Ebook => "ebook",
+            BookFormat::Audiobook => "audiobook",
+        }
+    }
+
+    /// Whether progress is tracked by page number
+    pub fn is_paper(&self) -> bool {
+        matches!(self, BookFormat::Hardcover | BookFormat::Paperback)
+    }
+}
+
+impl FromStr for BookFormat {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "hardcover" => Ok(BookFormat::Hardcover),
+            "paperback" => Ok(BookFormat::Paperback),
+            "ebook" => Ok(BookFormat::Ebook),
+            "audiobook" => Ok(BookFormat::Audiobook),
+            _ => Err(AppError::ValidationError(format!(
+                "Invalid book format '{}', expected one of: hardcover, paperback, ebook, audiobook",
+                s
+            ))),
+        }
+    }
+}
+
+/// How a book came to be on the shelf
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AcquisitionStatus {
+    /// Bought or otherwise owned
+    #[default]
+    Owned,
+    /// Wanted but not yet acquired
+    Wishlist,
+    /// Borrowed from a library
+    Library,
+}
+
+impl AcquisitionStatus {
+    /// Returns the value stored in the `books.acquisition_status` column
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AcquisitionStatus::Owned => "owned",
+            AcquisitionStatus::Wishlist => "wishlist",
+            AcquisitionStatus::Library => "library",
+        }
+    }
+}
+
+impl FromStr for AcquisitionStatus {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "owned" => Ok(AcquisitionStatus::Owned),
+            "wishlist" => Ok(AcquisitionStatus::Wishlist),
+            "library" => Ok(AcquisitionStatus::Library),
+            _ => Err(AppError::ValidationError(format!(
+                "Invalid acquisition status '{}', expected one of: owned, wishlist, library",
+                s
+            ))),
+        }
+    }
+}
 
 #[derive(Debug, Serialize, Deserialize, Queryable, Selectable)]
 #[diesel(table_name = books)]
@@ -21,9 +119,20 @@ pub struct Book {
     pub deleted_at: Option<DateTime<Utc>>,
     pub created_at: Option<DateTime<Utc>>,
     pub updated_at: Option<DateTime<Utc>>,
+    pub format: String,
+    pub duration_minutes: Option<i32>,
+    pub total_locations: Option<i32>,
+    pub deletion_batch_id: Option<Uuid>,
+    pub acquisition_status: String,
+    pub purchase_date: Option<NaiveDate>,
+    pub purchase_price: Option<BigDecimal>,
+    pub currency: Option<String>,
+    pub purchase_place: Option<String>,
+    pub location_id: Option<i64>,
+    pub custom_fields: Value,
 }
 
-#[derive(Debug, Deserialize, Insertable)]
+#[derive(Debug, Default, Deserialize, Insertable)]
 #[diesel(table_name = books)]
 pub struct NewBook {
     pub isbn: Option<String>,
@@ -34,6 +143,16 @@ pub struct NewBook {
     pub page_count: Option<i32>,
     pub cover_image: Option<String>,
     pub description: Option<String>,
+    pub format: Option<String>,
+    pub duration_minutes: Option<i32>,
+    pub total_locations: Option<i32>,
+    pub acquisition_status: Option<String>,
+    pub purchase_date: Option<NaiveDate>,
+    pub purchase_price: Option<BigDecimal>,
+    pub currency: Option<String>,
+    pub purchase_place: Option<String>,
+    pub location_id: Option<i64>,
+    pub custom_fields: Option<Value>,
 }
 
 #[derive(Debug, Deserialize, AsChangeset, Default, ToSchema)]
@@ -70,10 +189,53 @@ pub struct UpdateBook {
     /// Book description (optional)
     #[schema(example = "Updated description")]
     pub description: Option<String>,
+    
+    /// Edition format: hardcover, paperback, ebook or audiobook (optional)
+    #[schema(example = "audiobook")]
+    pub format: Option<String>,
+    
+    /// Total audiobook length in minutes (optional)
+    #[schema(example = 720)]
+    pub duration_minutes: Option<i32>,
+    
+    /// Total ebook locations, e.g. Kindle locations (optional)
+    #[schema(example = 5400)]
+    pub total_locations: Option<i32>,
+    
+    /// Acquisition status: owned, wishlist or library (optional)
+    #[schema(example = "owned")]
+    pub acquisition_status: Option<String>,
+    
+    /// Purchase date (optional)
+    #[schema(example = "2024-03-15")]
+    pub purchase_date: Option<NaiveDate>,
+    
+    /// Purchase price as a decimal string (optional)
+    #[schema(value_type = Option<String>, example = "59.90")]
+    pub purchase_price: Option<BigDecimal>,
+    
+    /// ISO 4217 currency code of the purchase price (optional)
+    #[schema(example = "CNY")]
+    pub currency: Option<String>,
+    
+    /// Where the book was bought (optional)
+    #[schema(example = "JD.com")]
+    pub purchase_place: Option<String>,
+    
+    /// Shelf or other location the book sits on, owned books only (optional)
+    #[schema(example = 3)]
+    pub location_id: Option<i64>,
+    
+    /// Custom field values to set; a null value removes the field (optional)
+    ///
+    /// Merged and validated by `Book::update`, never written directly.
+    #[diesel(skip_update)]
+    #[schema(value_type = Option<Object>, example = json!({"signed": true, "condition": "good"}))]
+    pub custom_fields: Option<Map<String, Value>>,
 }
 
 /// Request structure for creating a new book
-#[derive(Debug, Deserialize, Serialize, ToSchema)]
+#[derive(Debug, Default, Deserialize, Serialize, ToSchema)]
 pub struct CreateBookRequest {
     /// ISBN number (optional)
     #[schema(example = "978-0134685991")]
@@ -106,6 +268,223 @@ pub struct CreateBookRequest {
     /// Book description (optional)
     #[schema(example = "Best practices for the Java platform")]
     pub description: Option<String>,
+    
+    /// Edition format (optional, default: paperback)
+    #[serde(default)]
+    #[schema(example = "paperback")]
+    pub format: Option<BookFormat>,
+    
+    /// Total audiobook length in minutes (optional)
+    #[serde(default)]
+    #[schema(example = 720)]
+    pub duration_minutes: Option<i32>,
+    
+    /// Total ebook locations, e.g. Kindle locations (optional)
+    #[serde(default)]
+    #[schema(example = 5400)]
+    pub total_locations: Option<i32>,
+    
+    /// Acquisition status (optional, default: owned)
+    #[serde(default)]
+    #[schema(example = "owned")]
+    pub acquisition_status: Option<AcquisitionStatus>,
+    
+    /// Purchase date (optional)
+    #[serde(default)]
+    #[schema(example = "2024-03-15")]
+    pub purchase_date: Option<NaiveDate>,
+    
+    /// Purchase price as a decimal string (optional)
+    #[serde(default)]
+    #[schema(value_type = Option<String>, example = "59.90")]
+    pub purchase_price: Option<BigDecimal>,
+    
+    /// ISO 4217 currency code, required with a price (optional)
+    #[serde(default)]
+    #[schema(example = "CNY")]
+    pub currency: Option<String>,
+    
+    /// Where the book was bought (optional)
+    #[serde(default)]
+    #[schema(example = "JD.com")]
+    pub purchase_place: Option<String>,
+    
+    /// Shelf or other location the book sits on, owned books only (optional)
+    #[serde(default)]
+    #[schema(example = 3)]
+    pub location_id: Option<i64>,
+    
+    /// Values of user-defined custom fields, keyed by field key (optional)
+    #[serde(default)]
+    #[schema(value_type = Option<Object>, example = json!({"signed": true, "condition": "good"}))]
+    pub custom_fields: Option<Map<String, Value>>,
+}
+
+/// Book changes with tri-state nullable columns
+///
+/// `None` leaves a column untouched and `Some(None)` sets it to NULL.
+#[derive(Debug, Default, AsChangeset)]
+#[diesel(table_name = books)]
+pub struct BookChangeset {
+    pub isbn: Option<Option<String>>,
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub publisher: Option<Option<String>>,
+    pub publication_date: Option<Option<NaiveDate>>,
+    pub page_count: Option<Option<i32>>,
+    pub cover_image: Option<Option<String>>,
+    pub description: Option<Option<String>>,
+    pub format: Option<String>,
+    pub duration_minutes: Option<Option<i32>>,
+    pub total_locations: Option<Option<i32>>,
+    pub acquisition_status: Option<String>,
+    pub purchase_date: Option<Option<NaiveDate>>,
+    pub purchase_price: Option<Option<BigDecimal>>,
+    pub currency: Option<Option<String>>,
+    pub purchase_place: Option<Option<String>>,
+    pub location_id: Option<Option<i64>>,
+    /// Merge patch applied to the stored custom field values, `Null` clears them
+    pub custom_fields: Option<Value>,
+}
+
+/// Request structure for patching a book (JSON Merge Patch)
+///
+/// Absent fields are left unchanged, `null` clears optional fields.
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct PatchBookRequest {
+    #[serde(default, deserialize_with = "nullable")]
+    #[schema(value_type = Option<String>, nullable, example = "978-0134685991")]
+    pub isbn: Option<Option<String>>,
+    
+    /// Cannot be null
+    #[serde(default, deserialize_with = "nullable")]
+    #[schema(value_type = Option<String>, example = "Effective Java")]
+    pub title: Option<Option<String>>,
+    
+    /// Cannot be null
+    #[serde(default, deserialize_with = "nullable")]
+    #[schema(value_type = Option<String>, example = "Joshua Bloch")]
+    pub author: Option<Option<String>>,
+    
+    #[serde(default, deserialize_with = "nullable")]
+    #[schema(value_type = Option<String>, nullable, example = "Addison-Wesley")]
+    pub publisher: Option<Option<String>>,
+    
+    #[serde(default, deserialize_with = "nullable")]
+    #[schema(value_type = Option<NaiveDate>, nullable, example = "2017-12-27")]
+    pub publication_date: Option<Option<NaiveDate>>,
+    
+    #[serde(default, deserialize_with = "nullable")]
+    #[schema(value_type = Option<i32>, nullable, example = 416)]
+    pub page_count: Option<Option<i32>>,
+    
+    #[serde(default, deserialize_with = "nullable")]
+    #[schema(value_type = Option<String>, nullable, example = "https://example.com/cover.jpg")]
+    pub cover_image: Option<Option<String>>,
+    
+    #[serde(default, deserialize_with = "nullable")]
+    #[schema(value_type = Option<String>, nullable, example = "Best practices for the Java platform")]
+    pub description: Option<Option<String>>,
+    
+    /// Cannot be null
+    #[serde(default, deserialize_with = "nullable")]
+    #[schema(value_type = Option<BookFormat>, example = "hardcover")]
+    pub format: Option<Option<BookFormat>>,
+    
+    #[serde(default, deserialize_with = "nullable")]
+    #[schema(value_type = Option<i32>, nullable, example = 720)]
+    pub duration_minutes: Option<Option<i32>>,
+    
+    #[serde(default, deserialize_with = "nullable")]
+    #[schema(value_type = Option<i32>, nullable, example = 5400)]
+    pub total_locations: Option<Option<i32>>,
+    
+    /// Cannot be null
+    #[serde(default, deserialize_with = "nullable")]
+    #[schema(value_type = Option<AcquisitionStatus>, example = "owned")]
+    pub acquisition_status: Option<Option<AcquisitionStatus>>,
+    
+    #[serde(default, deserialize_with = "nullable")]
+    #[schema(value_type = Option<NaiveDate>, nullable, example = "2024-03-15")]
+    pub purchase_date: Option<Option<NaiveDate>>,
+    
+    #[serde(default, deserialize_with = "nullable")]
+    #[schema(value_type = Option<String>, nullable, example = "59.90")]
+    pub purchase_price: Option<Option<BigDecimal>>,
+    
+    #[serde(default, deserialize_with = "nullable")]
+    #[schema(value_type = Option<String>, nullable, example = "CNY")]
+    pub currency: Option<Option<String>>,
+    
+    #[serde(default, deserialize_with = "nullable")]
+    #[schema(value_type = Option<String>, nullable, example = "JD.com")]
+    pub purchase_place: Option<Option<String>>,
+    
+    #[serde(default, deserialize_with = "nullable")]
+    #[schema(value_type = Option<i64>, nullable, example = 3)]
+    pub location_id: Option<Option<i64>>,
+    
+    /// Merged into the stored values: a null value removes that field, null clears all
+    #[serde(default, deserialize_with = "nullable")]
+    #[schema(value_type = Option<Object>, nullable, example = json!({"signed": null, "condition": "worn"}))]
+    pub custom_fields: Option<Option<Map<String, Value>>>,
+}
+
+impl From<UpdateBook> for BookChangeset {
+    fn from(update: UpdateBook) -> Self {
+        Self {
+            isbn: update.isbn.map(Some),
+            title: update.title,
+            author: update.author,
+            publisher: update.publisher.map(Some),
+            publication_date: update.publication_date.map(Some),
+            page_count: update.page_count.map(Some),
+            cover_image: update.cover_image.map(Some),
+            description: update.description.map(Some),
+            format: update.format,
+            duration_minutes: update.duration_minutes.map(Some),
+            total_locations: update.total_locations.map(Some),
+            acquisition_status: update.acquisition_status,
+            purchase_date: update.purchase_date.map(Some),
+            purchase_price: update.purchase_price.map(|p| Some(round_price(p))),
+            currency: update.currency.map(Some),
+            purchase_place: update.purchase_place.map(Some),
+            location_id: update.location_id.map(Some),
+            custom_fields: update.custom_fields.map(Value::Object),
+        }
+    }
+}
+
+impl TryFrom<PatchBookRequest> for BookChangeset {
+    type Error = AppError;
+
+    fn try_from(patch: PatchBookRequest) -> Result<Self> {
+        if patch.purchase_price.as_ref().and_then(|p| p.as_ref()).is_some_and(Signed::is_negative) {
+            return Err(AppError::ValidationError("Purchase price cannot be negative".to_string()));
+        }
+
+        Ok(Self {
+            isbn: patch.isbn,
+            title: required("title", patch.title)?,
+            author: required("author", patch.author)?,
+            publisher: patch.publisher,
+            publication_date: patch.publication_date,
+            page_count: patch.page_count,
+            cover_image: patch.cover_image,
+            description: patch.description,
+            format: required("format", patch.format)?.map(|f| f.as_str().to_string()),
+            duration_minutes: patch.duration_minutes,
+            total_locations: patch.total_locations,
+            acquisition_status: required("acquisition_status", patch.acquisition_status)?
+                .map(|a| a.as_str().to_string()),
+            purchase_date: patch.purchase_date,
+            purchase_price: patch.purchase_price.map(|p| p.map(round_price)),
+            currency: patch.currency,
+            purchase_place: patch.purchase_place,
+            location_id: patch.location_id,
+            custom_fields: patch.custom_fields.map(|c| c.map(Value::Object).unwrap_or(Value::Null)),
+        })
+    }
 }
 
 /// Response structure for book operations
@@ -147,6 +526,54 @@ pub struct BookResponse {
     #[schema(example = "Best practices for the Java platform")]
     pub description: Option<String>,
     
+    /// Edition format
+    #[schema(example = "paperback")]
+    pub format: BookFormat,
+    
+    /// Total audiobook length in minutes
+    #[schema(example = 720)]
+    pub duration_minutes: Option<i32>,
+    
+    /// Total ebook locations
+    #[schema(example = 5400)]
+    pub total_locations: Option<i32>,
+    
+    /// Acquisition status
+    #[schema(example = "owned")]
+    pub acquisition_status: AcquisitionStatus,
+    
+    /// Purchase date
+    #[schema(example = "2024-03-15")]
+    pub purchase_date: Option<NaiveDate>,
+    
+    /// Purchase price as a decimal string
+    #[schema(value_type = Option<String>, example = "59.90")]
+    pub purchase_price: Option<BigDecimal>,
+    
+    /// Currency of the purchase price
+    #[schema(example = "CNY")]
+    pub currency: Option<String>,
+    
+    /// Where the book was bought
+    #[schema(example = "JD.com")]
+    pub purchase_place: Option<String>,
+    
+    /// Location the book sits on
+    #[schema(example = 3)]
+    pub location_id: Option<i64>,
+    
+    /// Where the book is, from room down to shelf
+    #[schema(example = "Office / Bookcase A / Shelf 2")]
+    pub location_path: Option<String>,
+    
+    /// Values of user-defined custom fields, keyed by field key
+    #[schema(value_type = Object, example = json!({"signed": true, "condition": "good"}))]
+    pub custom_fields: Value,
+    
+    /// Loan history, most recent first (book detail only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub loans: Option<Vec<LoanResponse>>,
+    
     /// Creation timestamp
     #[schema(example = "2024-01-01T12:00:00Z")]
     pub created_at: Option<DateTime<Utc>>,
@@ -179,6 +606,11 @@ pub struct BookListResponse {
     pub total_pages: u32,
 }
 
+/// Rounds a price to the two decimals that are stored
+pub fn round_price(price: BigDecimal) -> BigDecimal {
+    price.with_scale_round(2, bigdecimal::RoundingMode::HalfUp)
+}
+
 impl From<CreateBookRequest> for NewBook {
     fn from(req: CreateBookRequest) -> Self {
         Self {
@@ -190,12 +622,24 @@ impl From<CreateBookRequest> for NewBook {
             page_count: req.page_count,
             cover_image: req.cover_image,
             description: req.description,
+            format: req.format.map(|f| f.as_str().to_string()),
+            duration_minutes: req.duration_minutes,
+            total_locations: req.total_locations,
+            acquisition_status: req.acquisition_status.map(|a| a.as_str().to_string()),
+            purchase_date: req.purchase_date,
+            purchase_price: req.purchase_price.map(round_price),
+            currency: req.currency.map(|c| c.trim().to_uppercase()),
+            purchase_place: req.purchase_place,
+            location_id: req.location_id,
+            custom_fields: req.custom_fields.map(Value::Object),
         }
     }
 }
 
 impl From<Book> for BookResponse {
     fn from(book: Book) -> Self {
+        let format = book.format();
+        let acquisition_status = book.acquisition_status();
         Self {
             id: book.id,
             isbn: book.isbn,
@@ -206,6 +650,18 @@ impl From<Book> for BookResponse {
             page_count: book.page_count,
             cover_image: book.cover_image,
             description: book.description,
+            format,
+            duration_minutes: book.duration_minutes,
+            total_locations: book.total_locations,
+            acquisition_status,
+            purchase_date: book.purchase_date,
+            purchase_price: book.purchase_price,
+            currency: book.currency,
+            purchase_place: book.purchase_place,
+            location_id: book.location_id,
+            location_path: None,
+            custom_fields: book.custom_fields,
+            loans: None,
             created_at: book.created_at,
             updated_at: book.updated_at,
         }
@@ -214,12 +670,23 @@ impl From<Book> for BookResponse {
 
 impl Book {
     /// Creates a new book in the database
-    pub fn create(conn: &mut PgConnection, new_book: NewBook) -> Result<Book> {
-        diesel::insert_into(books::table)
-            .values(&new_book)
-            .returning(Book::as_returning())
-            .get_result(conn)
-            .map_err(AppError::from)
+    pub fn create(conn: &mut PgConnection, mut new_book: NewBook) -> Result<Book> {
+        if let Some(values) = new_book.custom_fields.take() {
+            let values = merge_custom_fields(&Value::Object(Map::new()), values)?;
+            validate_custom_fields(conn, &values)?;
+            new_book.custom_fields = Some(values);
+        }
+
+        conn.transaction(|conn| {
+            let book = diesel::insert_into(books::table)
+                .values(&new_book)
+                .returning(Book::as_returning())
+                .get_result(conn)?;
+
+            NoteLink::link_book(conn, &book)?;
+
+            Ok(book)
+        })
     }
 
     /// Finds a book by ID (excluding soft deleted)
@@ -287,38 +754,411 @@ impl Book {
         Ok((books, total))
     }
 
+    /// Lists active books with the given acquisition status
+    pub fn list_by_acquisition_status(
+        conn: &mut PgConnection,
+        status: AcquisitionStatus,
+        page: u32,
+        per_page: u32,
+    ) -> Result<(Vec<Book>, i64)> {
+        let offset = ((page.saturating_sub(1)) * per_page) as i64;
+
+        let books = books::table
+            .filter(books::deleted_at.is_null())
+            .filter(books::acquisition_status.eq(status.as_str()))
+            .order(books::created_at.desc())
+            .limit(per_page as i64)
+            .offset(offset)
+            .load::<Book>(conn)?;
+
+        let total = books::table
+            .filter(books::deleted_at.is_null())
+            .filter(books::acquisition_status.eq(status.as_str()))
+            .count()
+            .get_result::<i64>(conn)?;
+
+        Ok((books, total))
+    }
+
+    /// Lists books filtered and/or sorted by a custom field, with optional search
+    ///
+    /// `filter` is a `(key, value)` pair matched exactly against the typed value;
+    /// books without the sort field come last.
+    pub fn list_by_custom_field(
+        conn: &mut PgConnection,
+        search: Option<&str>,
+        filter: Option<(&str, &str)>,
+        sort: Option<(&str, bool)>,
+        page: u32,
+        per_page: u32,
+    ) -> Result<(Vec<Book>, i64)> {
+        let offset = ((page.saturating_sub(1)) * per_page) as i64;
+
+        let filter = match filter {
+            Some((key, raw)) => {
+                let definition = CustomFieldDefinition::find_by_key(conn, key)?
+                    .ok_or_else(|| AppError::ValidationError(format!("Unknown custom field '{}'", key)))?;
+                let value = definition.parse_query_value(raw)?;
+                Some(serde_json::json!({ key: value }))
+            }
+            None => None,
+        };
+        let sort = match sort {
+            Some((key, descending)) => {
+                let definition = CustomFieldDefinition::find_by_key(conn, key)?
+                    .ok_or_else(|| AppError::ValidationError(format!("Unknown custom field '{}'", key)))?;
+                Some((definition, descending))
+            }
+            None => None,
+        };
+        let search_pattern = search.map(|q| format!("%{}%", q));
+
+        let build_query = || {
+            let mut query = books::table
+                .filter(books::deleted_at.is_null())
+                .into_boxed();
+
+            if let Some(ref pattern) = search_pattern {
+                query = query.filter(books::title.ilike(pattern.clone()).or(books::author.ilike(pattern.clone())));
+            }
+            if let Some(ref filter) = filter {
+                query = query.filter(books::custom_fields.contains(filter.clone()));
+            }
+
+            query
+        };
+
+        let mut query = build_query();
+        if let Some((ref definition, descending)) = sort {
+            // Numbers are compared numerically, everything else by its text form
+            // (ISO dates sort correctly as text)
+            let cast = if definition.field_type() == CustomFieldType::Number { "::numeric" } else { "" };
+            let expression = sql::<Nullable<Numeric>>("(custom_fields ->> ")
+                .bind::<Text, _>(definition.key.clone())
+                .sql(&format!("){}", cast));
+            query = if descending {
+                query.order(expression.desc().nulls_last())
+            } else {
+                query.order(expression.asc().nulls_last())
+            };
+        }
+
+        let books = query
+            .then_order_by(books::created_at.desc())
+            .limit(per_page as i64)
+            .offset(offset)
+            .load::<Book>(conn)?;
+
+        let total = build_query().count().get_result::<i64>(conn)?;
+
+        Ok((books, total))
+    }
+
     /// Updates a book
     pub fn update(
         conn: &mut PgConnection,
         book_id: i64,
         update_data: UpdateBook,
     ) -> Result<Book> {
+        Self::patch(conn, book_id, update_data.into())
+    }
+
+    /// Applies changes to a book, clearing nullable columns set to `Some(None)`
+    pub fn patch(
+        conn: &mut PgConnection,
+        book_id: i64,
+        mut changes: BookChangeset,
+    ) -> Result<Book> {
+        changes.currency = changes.currency.map(|c| c.map(|c| c.trim().to_uppercase()));
+        // Only owned books sit on a shelf
+        let clears_location = changes
+            .acquisition_status
+            .as_deref()
+            .is_some_and(|s| s != AcquisitionStatus::Owned.as_str());
+        let renames = changes.title.is_some();
+
+        conn.transaction(|conn| {
+            if let Some(patch) = changes.custom_fields.take() {
+                let current = Self::find_by_id(conn, book_id)?;
+                let values = merge_custom_fields(&current.custom_fields, patch)?;
+                validate_custom_fields(conn, &values)?;
+                changes.custom_fields = Some(values);
+            }
+
+            let mut book = diesel::update(books::table.find(book_id))
+                .filter(books::deleted_at.is_null())
+                .set((
+                    &changes,
+                    books::updated_at.eq(Some(Utc::now().naive_utc())),
+                ))
+                .returning(Book::as_returning())
+                .get_result(conn)
+                .map_err(|e| match e {
+                    diesel::result::Error::NotFound => {
+                        AppError::NotFound(format!("Book with id {} not found", book_id))
+                    }
+                    _ => AppError::from(e),
+                })?;
+
+            if clears_location && book.location_id.is_some() {
+                book = diesel::update(books::table.find(book_id))
+                    .set(books::location_id.eq(None::<i64>))
+                    .returning(Book::as_returning())
+                    .get_result(conn)?;
+            }
+
+            if renames {
+                NoteLink::link_book(conn, &book)?;
+            }
+
+            Ok(book)
+        })
+    }
+
+    /// Moves a wishlist book to owned and records the purchase
+    pub fn acquire(conn: &mut PgConnection, book_id: i64, request: AcquireBookRequest) -> Result<Book> {
+        validate_purchase(request.purchase_price.as_ref(), request.currency.as_deref())?;
+        let purchase_place = optional_text("Purchase place", request.purchase_place, MAX_PURCHASE_PLACE_LENGTH)?;
+
+        let book = Self::find_by_id(conn, book_id)?;
+        if book.acquisition_status() != AcquisitionStatus::Wishlist {
+            return Err(AppError::Conflict(format!(
+                "Book {} is not on the wishlist (status: {})",
+                book_id,
+                book.acquisition_status().as_str()
+            )));
+        }
+
         diesel::update(books::table.find(book_id))
-            .filter(books::deleted_at.is_null())
             .set((
-                &update_data,
-                books::updated_at.eq(Some(Utc::now().naive_utc())),
+                books::acquisition_status.eq(AcquisitionStatus::Owned.as_str()),
+                books::purchase_date.eq(request.purchase_date.or_else(|| Some(Utc::now().date_naive()))),
+                books::purchase_price.eq(request.purchase_price.map(round_price)),
+                books::currency.eq(request.currency.map(|c| c.trim().to_uppercase())),
+                books::purchase_place.eq(purchase_place),
             ))
             .returning(Book::as_returning())
             .get_result(conn)
-            .map_err(|e| match e {
-                diesel::result::Error::NotFound => {
-                    AppError::NotFound(format!("Book with id {} not found", book_id))
-                }
-                _ => AppError::from(e),
-            })
+            .map_err(AppError::from)
     }
 
-    /// Soft deletes a book
+    /// Soft deletes a book together with its notes, reading status, loans and associations
+    /// 
+    /// All rows are marked in one transaction with a shared deletion batch id,
+    /// so that `restore` brings back exactly what was deleted with the book.
     pub fn soft_delete(conn: &mut PgConnection, book_id: i64) -> Result<()> {
-        let affected = diesel::update(books::table.find(book_id))
-            .filter(books::deleted_at.is_null())
-            .set(books::deleted_at.eq(Some(Utc::now().naive_utc())))
-            .execute(conn)?;
+        use crate::db::schema::{book_categories, book_tags, loans, note_tags, reading_notes, reading_status};
+        conn.transaction(|conn| {
+            let batch_id = Uuid::now_v7();
+            let now = Utc::now();
 
-        if affected == 0 {
-            return Err(AppError::NotFound(format!("Book with id {} not found", book_id)));
-        }
+            let affected = diesel::update(books::table.find(book_id))
+                .filter(books::deleted_at.is_null())
+                .set((books::deleted_at.eq(Some(now)), books::deletion_batch_id.eq(Some(batch_id))))
+                .execute(conn)?;
+
+            if affected == 0 {
+                return Err(AppError::NotFound(format!("Book with id {} not found", book_id)));
+            }
+
+            let note_ids = diesel::update(reading_notes::table)
+                .filter(reading_notes::book_id.eq(book_id))
+                .filter(reading_notes::deleted_at.is_null())
+                .set((
+                    reading_notes::deleted_at.eq(Some(now)),
+                    reading_notes::deletion_batch_id.eq(Some(batch_id)),
+                ))
+                .returning(reading_notes::id)
+                .get_results::<i64>(conn)?;
+
+            let mut tag_ids = diesel::update(note_tags::table)
+                .filter(note_tags::note_id.eq_any(&note_ids))
+                .filter(note_tags::deleted_at.is_null())
+                .set((note_tags::deleted_at.eq(Some(now)), note_tags::deletion_batch_id.eq(Some(batch_id))))
+                .returning(note_tags::tag_id)
+                .get_results::<i64>(conn)?;
+
+            tag_ids.extend(
+                diesel::update(book_tags::table)
+                    .filter(book_tags::book_id.eq(book_id))
+                    .filter(book_tags::deleted_at.is_null())
+                    .set((book_tags::deleted_at.eq(Some(now)), book_tags::deletion_batch_id.eq(Some(batch_id))))
+                    .returning(book_tags::tag_id)
+                    .get_results::<i64>(conn)?,
+            );
+            Tag::refresh_usage_counts(conn, &tag_ids)?;
+
+            diesel::update(book_categories::table)
+                .filter(book_categories::book_id.eq(book_id))
+                .filter(book_categories::deleted_at.is_null())
+                .set((
+                    book_categories::deleted_at.eq(Some(now)),
+                    book_categories::deletion_batch_id.eq(Some(batch_id)),
+                ))
+                .execute(conn)?;
+
+            diesel::update(reading_status::table)
+                .filter(reading_status::book_id.eq(book_id))
+                .filter(reading_status::deleted_at.is_null())
+                .set((
+                    reading_status::deleted_at.eq(Some(now)),
+                    reading_status::deletion_batch_id.eq(Some(batch_id)),
+                ))
+                .execute(conn)?;
+
+            diesel::update(loans::table)
+                .filter(loans::book_id.eq(book_id))
+                .filter(loans::deleted_at.is_null())
+                .set((loans::deleted_at.eq(Some(now)), loans::deletion_batch_id.eq(Some(batch_id))))
+                .execute(conn)?;
+
+            Ok(())
+        })
+    }
+
+    /// Lists soft deleted books, most recently deleted first
+    pub fn list_deleted(conn: &mut PgConnection) -> Result<Vec<Book>> {
+        books::table
+            .filter(books::deleted_at.is_not_null())
+            .order(books::deleted_at.desc())
+            .load::<Book>(conn)
+            .map_err(AppError::from)
+    }
+
+    /// Restores a soft deleted book with everything deleted in the same batch
+    /// 
+    /// Notes deleted individually before the book stay in the trash.
+    pub fn restore(conn: &mut PgConnection, book_id: i64) -> Result<Book> {
+        use crate::db::schema::{book_categories, book_tags, loans, note_tags, reading_notes, reading_status};
+        conn.transaction(|conn| {
+            let book = books::table
+                .filter(books::id.eq(book_id))
+                .filter(books::deleted_at.is_not_null())
+                .first::<Book>(conn)
+                .optional()?
+                .ok_or_else(|| AppError::NotFound(format!("Deleted book with id {} not found", book_id)))?;
+
+            let restored = diesel::update(books::table.find(book_id))
+                .set((
+                    books::deleted_at.eq(None::<DateTime<Utc>>),
+                    books::deletion_batch_id.eq(None::<Uuid>),
+                ))
+                .returning(Book::as_returning())
+                .get_result(conn)?;
+            NoteLink::link_book(conn, &restored)?;
+
+            let Some(batch_id) = book.deletion_batch_id else {
+                return Ok(restored);
+            };
+
+            // Only one active reading status is allowed per book
+            let status_active = reading_status::table
+                .filter(reading_status::book_id.eq(book_id))
+                .filter(reading_status::deleted_at.is_null())
+                .select(reading_status::id)
+                .first::<i64>(conn)
+                .optional()?;
+
+            if status_active.is_some() {
+                return Err(AppError::Conflict(format!(
+                    "Cannot restore book {}: it already has an active reading status",
+                    book_id
+                )));
+            }
+
+            diesel::update(reading_status::table)
+                .filter(reading_status::deletion_batch_id.eq(batch_id))
+                .set((
+                    reading_status::deleted_at.eq(None::<DateTime<Utc>>),
+                    reading_status::deletion_batch_id.eq(None::<Uuid>),
+                ))
+                .execute(conn)?;
+
+            diesel::update(reading_notes::table)
+                .filter(reading_notes::deletion_batch_id.eq(batch_id))
+                .set((
+                    reading_notes::deleted_at.eq(None::<DateTime<Utc>>),
+                    reading_notes::deletion_batch_id.eq(None::<Uuid>),
+                ))
+                .execute(conn)?;
+
+            diesel::update(book_categories::table)
+                .filter(book_categories::deletion_batch_id.eq(batch_id))
+                .set((
+                    book_categories::deleted_at.eq(None::<DateTime<Utc>>),
+                    book_categories::deletion_batch_id.eq(None::<Uuid>),
+                ))
+                .execute(conn)?;
+
+            let mut tag_ids = diesel::update(note_tags::table)
+                .filter(note_tags::deletion_batch_id.eq(batch_id))
+                .set((
+                    note_tags::deleted_at.eq(None::<DateTime<Utc>>),
+                    note_tags::deletion_batch_id.eq(None::<Uuid>),
+                ))
+                .returning(note_tags::tag_id)
+                .get_results::<i64>(conn)?;
+
+            tag_ids.extend(
+                diesel::update(book_tags::table)
+                    .filter(book_tags::deletion_batch_id.eq(batch_id))
+                    .set((
+                        book_tags::deleted_at.eq(None::<DateTime<Utc>>),
+                        book_tags::deletion_batch_id.eq(None::<Uuid>),
+                    ))
+                    .returning(book_tags::tag_id)
+                    .get_results::<i64>(conn)?,
+            );
+            Tag::refresh_usage_counts(conn, &tag_ids)?;
+
+            diesel::update(loans::table)
+                .filter(loans::deletion_batch_id.eq(batch_id))
+                .set((
+                    loans::deleted_at.eq(None::<DateTime<Utc>>),
+                    loans::deletion_batch_id.eq(None::<Uuid>),
+                ))
+                .execute(conn)?;
+
+            Ok(restored)
+        })
+    }
+
+    /// Permanently deletes a soft deleted book
+    /// 
+    /// Notes, tag and category associations are removed by the foreign key
+    /// cascades; reading status rows have no cascade and are deleted explicitly.
+    /// Files of the notes' attachments are removed afterwards.
+    pub fn purge(conn: &mut PgConnection, book_id: i64) -> Result<()> {
+        use crate::db::schema::{reading_notes, reading_status};
+        use crate::models::attachment::{self, NoteAttachment};
+
+        let storage_keys = conn.transaction(|conn| {
+            let exists = books::table
+                .filter(books::id.eq(book_id))
+                .filter(books::deleted_at.is_not_null())
+                .select(books::id)
+                .first::<i64>(conn)
+                .optional()?;
+
+            if exists.is_none() {
+                return Err(AppError::NotFound(format!("Deleted book with id {} not found", book_id)));
+            }
+
+            let note_ids: Vec<i64> = reading_notes::table
+                .filter(reading_notes::book_id.eq(book_id))
+                .select(reading_notes::id)
+                .load(conn)?;
+            let storage_keys = NoteAttachment::storage_keys(conn, &note_ids)?;
+
+            diesel::delete(reading_status::table.filter(reading_status::book_id.eq(book_id)))
+                .execute(conn)?;
+            diesel::delete(books::table.find(book_id))
+                .execute(conn)?;
+
+            Ok(storage_keys)
+        })?;
+
+        attachment::remove_files(&storage_keys);
 
         Ok(())
     }
@@ -327,4 +1167,14 @@ impl Book {
     pub fn is_deleted(&self) -> bool {
         self.deleted_at.is_some()
     }
+
+    /// Returns the edition format (constrained by the database check)
+    pub fn format(&self) -> BookFormat {
+        self.format.parse().unwrap_or_default()
+    }
+
+    /// Returns the acquisition status (constrained by the database check)
+    pub fn acquisition_status(&self) -> AcquisitionStatus {
+        self.acquisition_status.parse().unwrap_or_default()
+    }
 }
\ No newline at end of file
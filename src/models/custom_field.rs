@@ -0,0 +1,484 @@
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use chrono::{DateTime, NaiveDate, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use utoipa::ToSchema;
+use crate::db::schema::{books, custom_field_definitions};
+use crate::errors::{AppError, Result};
+use crate::utils::validation::{optional_text, required_text};
+
+/// Longest field name, matching its column
+const MAX_NAME_LENGTH: usize = 100;
+
+/// Value type of a custom field
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CustomFieldType {
+    Text,
+    Number,
+    Date,
+    Bool,
+    Enum,
+}
+
+impl CustomFieldType {
+    /// Returns the value stored in the `custom_field_definitions.field_type` column
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CustomFieldType::Text => "text",
+            CustomFieldType::Number => "number",
+            CustomFieldType::Date => "date",
+            CustomFieldType::Bool => "bool",
+            CustomFieldType::Enum => "enum",
+        }
+    }
+}
+
+impl FromStr for CustomFieldType {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "text" => Ok(CustomFieldType::Text),
+            "number" => Ok(CustomFieldType::Number),
+            "date" => Ok(CustomFieldType::Date),
+            "bool" => Ok(CustomFieldType::Bool),
+            "enum" => Ok(CustomFieldType::Enum),
+            _ => Err(AppError::ValidationError(format!(
+                "Invalid field type '{}', expected one of: text, number, date, bool, enum",
+                s
+            ))),
+        }
+    }
+}
+
+/// Custom field definition database model
+#[derive(Debug, Serialize, Deserialize, Queryable, Selectable, Identifiable)]
+#[diesel(table_name = custom_field_definitions)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct CustomFieldDefinition {
+    pub id: i64,
+    pub key: String,
+    pub name: String,
+    pub field_type: String,
+    pub enum_values: Option<Value>,
+    pub description: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// New custom field definition for insertion
+#[derive(Debug, Insertable)]
+#[diesel(table_name = custom_field_definitions)]
+pub struct NewCustomFieldDefinition {
+    pub key: String,
+    pub name: String,
+    pub field_type: String,
+    pub enum_values: Option<Value>,
+    pub description: Option<String>,
+}
+
+/// Custom field definition changes
+#[derive(Debug, Default, AsChangeset)]
+#[diesel(table_name = custom_field_definitions)]
+pub struct UpdateCustomFieldDefinition {
+    pub name: Option<String>,
+    pub enum_values: Option<Value>,
+    pub description: Option<String>,
+}
+
+/// Request structure for defining a custom field
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct CreateCustomFieldRequest {
+    /// Key used in `custom_fields` objects: lowercase letters, digits and underscores
+    #[schema(example = "condition")]
+    pub key: String,
+
+    #[schema(example = "Condition")]
+    pub name: String,
+
+    #[schema(example = "enum")]
+    pub field_type: CustomFieldType,
+
+    /// Allowed values, required for enum fields
+    #[schema(example = json!(["new", "good", "worn"]))]
+    pub enum_values: Option<Vec<String>>,
+
+    #[schema(example = "Physical condition of the copy")]
+    pub description: Option<String>,
+}
+
+/// Request structure for updating a custom field definition
+///
+/// The key and type cannot change once values may have been stored.
+#[derive(Debug, Default, Deserialize, Serialize, ToSchema)]
+pub struct UpdateCustomFieldRequest {
+    #[schema(example = "Condition")]
+    pub name: Option<String>,
+
+    /// Replaces the allowed values of an enum field
+    #[schema(example = json!(["new", "good", "worn", "damaged"]))]
+    pub enum_values: Option<Vec<String>>,
+
+    #[schema(example = "Physical condition of the copy")]
+    pub description: Option<String>,
+}
+
+/// Response structure for a custom field definition
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CustomFieldResponse {
+    #[schema(example = 1)]
+    pub id: i64,
+
+    #[schema(example = "condition")]
+    pub key: String,
+
+    #[schema(example = "Condition")]
+    pub name: String,
+
+    #[schema(example = "enum")]
+    pub field_type: CustomFieldType,
+
+    #[schema(example = json!(["new", "good", "worn"]))]
+    pub enum_values: Option<Vec<String>>,
+
+    #[schema(example = "Physical condition of the copy")]
+    pub description: Option<String>,
+
+    #[schema(example = "2024-01-01T12:00:00Z")]
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl From<CustomFieldDefinition> for CustomFieldResponse {
+    fn from(definition: CustomFieldDefinition) -> Self {
+        let field_type = definition.field_type();
+        let enum_values = definition.enum_values.as_ref().map(|_| definition.allowed_values());
+        Self {
+            id: definition.id,
+            key: definition.key,
+            name: definition.name,
+            field_type,
+            enum_values,
+            description: definition.description,
+            created_at: definition.created_at,
+        }
+    }
+}
+
+impl CustomFieldDefinition {
+    /// Returns the parsed field type
+    pub fn field_type(&self) -> CustomFieldType {
+        self.field_type.parse().unwrap_or(CustomFieldType::Text)
+    }
+
+    /// Allowed values of an enum field
+    pub fn allowed_values(&self) -> Vec<String> {
+        self.enum_values
+            .as_ref()
+            .and_then(|v| v.as_array())
+            .map(|values| values.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+            .unwrap_or_default()
+    }
+
+    /// Checks a single value against this definition
+    pub fn check_value(&self, value: &Value) -> Result<()> {
+        let valid = match self.field_type() {
+            CustomFieldType::Text => value.is_string(),
+            CustomFieldType::Number => value.is_number(),
+            CustomFieldType::Bool => value.is_boolean(),
+            CustomFieldType::Date => value
+                .as_str()
+                .is_some_and(|s| NaiveDate::parse_from_str(s, "%Y-%m-%d").is_ok()),
+            CustomFieldType::Enum => value
+                .as_str()
+                .is_some_and(|s| self.allowed_values().iter().any(|v| v == s)),
+        };
+
+        if valid {
+            return Ok(());
+        }
+        let expected = match self.field_type() {
+            CustomFieldType::Date => "a YYYY-MM-DD date".to_string(),
+            CustomFieldType::Enum => format!("one of: {}", self.allowed_values().join(", ")),
+            other => format!("a {}", other.as_str()),
+        };
+        Err(AppError::ValidationError(format!(
+            "Invalid value {} for custom field '{}', expected {}",
+            value, self.key, expected
+        )))
+    }
+
+    /// Parses a query string value into the JSON value stored for this field
+    pub fn parse_query_value(&self, raw: &str) -> Result<Value> {
+        let value = match self.field_type() {
+            CustomFieldType::Number => raw
+                .parse::<f64>()
+                .ok()
+                .and_then(serde_json::Number::from_f64)
+                .map(Value::Number)
+                .unwrap_or_else(|| Value::String(raw.to_string())),
+            CustomFieldType::Bool => match raw {
+                "true" => Value::Bool(true),
+                "false" => Value::Bool(false),
+                _ => Value::String(raw.to_string()),
+            },
+            _ => Value::String(raw.to_string()),
+        };
+        self.check_value(&value)?;
+        Ok(value)
+    }
+
+    /// Defines a new custom field
+    pub fn create(conn: &mut PgConnection, request: CreateCustomFieldRequest) -> Result<CustomFieldDefinition> {
+        let key = request.key.trim().to_string();
+        if key.is_empty()
+            || key.len() > 50
+            || !key.starts_with(|c: char| c.is_ascii_lowercase())
+            || !key.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        {
+            return Err(AppError::ValidationError(format!(
+                "Invalid key '{}', use lowercase letters, digits and underscores",
+                key
+            )));
+        }
+        let name = required_text("Name", &request.name, MAX_NAME_LENGTH)?;
+        let enum_values = validate_enum_values(request.field_type, request.enum_values)?;
+
+        if Self::find_by_key(conn, &key)?.is_some() {
+            return Err(AppError::Conflict(format!("Custom field '{}' already exists", key)));
+        }
+
+        diesel::insert_into(custom_field_definitions::table)
+            .values(&NewCustomFieldDefinition {
+                key,
+                name,
+                field_type: request.field_type.as_str().to_string(),
+                enum_values: enum_values.map(Value::from),
+                description: request.description,
+            })
+            .returning(CustomFieldDefinition::as_returning())
+            .get_result(conn)
+            .map_err(AppError::from)
+    }
+
+    /// Finds a definition by ID
+    pub fn find_by_id(conn: &mut PgConnection, field_id: i64) -> Result<CustomFieldDefinition> {
+        custom_field_definitions::table
+            .find(field_id)
+            .first(conn)
+            .map_err(|_| AppError::NotFound(format!("Custom field with id {} not found", field_id)))
+    }
+
+    /// Finds a definition by key
+    pub fn find_by_key(conn: &mut PgConnection, key: &str) -> Result<Option<CustomFieldDefinition>> {
+        custom_field_definitions::table
+            .filter(custom_field_definitions::key.eq(key))
+            .first(conn)
+            .optional()
+            .map_err(AppError::from)
+    }
+
+    /// Lists all definitions ordered by name
+    pub fn list_all(conn: &mut PgConnection) -> Result<Vec<CustomFieldDefinition>> {
+        custom_field_definitions::table
+            .order(custom_field_definitions::name.asc())
+            .load::<CustomFieldDefinition>(conn)
+            .map_err(AppError::from)
+    }
+
+    /// Updates a definition
+    ///
+    /// Enum values still used by a book cannot be removed.
+    pub fn update(
+        conn: &mut PgConnection,
+        field_id: i64,
+        request: UpdateCustomFieldRequest,
+    ) -> Result<CustomFieldDefinition> {
+        let definition = Self::find_by_id(conn, field_id)?;
+        let name = optional_text("Name", request.name, MAX_NAME_LENGTH)?;
+
+        let values = match request.enum_values {
+            Some(values) => validate_enum_values(definition.field_type(), Some(values))?,
+            None => None,
+        };
+        let enum_values = match values {
+            Some(values) => {
+                let removed: Vec<String> = definition
+                    .allowed_values()
+                    .into_iter()
+                    .filter(|v| !values.contains(v))
+                    .collect();
+                for value in removed {
+                    let in_use = books::table
+                        .filter(books::custom_fields.contains(serde_json::json!({ &definition.key: value })))
+                        .count()
+                        .get_result::<i64>(conn)?;
+                    if in_use > 0 {
+                        return Err(AppError::Conflict(format!(
+                            "Value '{}' of custom field '{}' is used by {} books",
+                            value, definition.key, in_use
+                        )));
+                    }
+                }
+                Some(Value::from(values))
+            }
+            None => None,
+        };
+
+        diesel::update(custom_field_definitions::table.find(field_id))
+            .set((
+                &UpdateCustomFieldDefinition {
+                    name,
+                    enum_values,
+                    description: request.description,
+                },
+                custom_field_definitions::updated_at.eq(Some(Utc::now())),
+            ))
+            .returning(CustomFieldDefinition::as_returning())
+            .get_result(conn)
+            .map_err(AppError::from)
+    }
+
+    /// Deletes a definition and removes its values from all books
+    pub fn delete(conn: &mut PgConnection, field_id: i64) -> Result<()> {
+        let definition = Self::find_by_id(conn, field_id)?;
+
+        conn.transaction(|conn| {
+            diesel::update(books::table.filter(books::custom_fields.has_key(&definition.key)))
+                .set(books::custom_fields.eq(books::custom_fields.remove(definition.key.as_str())))
+                .execute(conn)?;
+            diesel::delete(custom_field_definitions::table.find(field_id)).execute(conn)?;
+            Ok(())
+        })
+    }
+}
+
+/// Checks that enum fields have a non-empty list of values and other fields none
+///
+/// Values are trimmed and must be non-blank and distinct.
+fn validate_enum_values(field_type: CustomFieldType, values: Option<Vec<String>>) -> Result<Option<Vec<String>>> {
+    match (field_type, values) {
+        (CustomFieldType::Enum, Some(values)) if !values.is_empty() => {
+            let values: Vec<String> = values.iter().map(|v| v.trim().to_string()).collect();
+            if values.iter().any(String::is_empty) {
+                return Err(AppError::ValidationError("Enum values cannot be empty".to_string()));
+            }
+            let mut seen = HashSet::new();
+            if let Some(duplicate) = values.iter().find(|v| !seen.insert(v.as_str())) {
+                return Err(AppError::ValidationError(format!("Duplicate enum value '{}'", duplicate)));
+            }
+            Ok(Some(values))
+        }
+        (CustomFieldType::Enum, _) => {
+            Err(AppError::ValidationError("Enum fields require at least one value".to_string()))
+        }
+        (_, Some(_)) => Err(AppError::ValidationError("Only enum fields can have values".to_string())),
+        (_, None) => Ok(None),
+    }
+}
+
+/// Applies a merge patch to custom field values
+///
+/// `null` clears all values, and a `null` value inside the object removes
+/// that field, following JSON Merge Patch (RFC 7396).
+pub fn merge_custom_fields(current: &Value, patch: Value) -> Result<Value> {
+    let patch = match patch {
+        Value::Null => return Ok(Value::Object(Map::new())),
+        Value::Object(patch) => patch,
+        _ => return Err(AppError::ValidationError("Custom fields must be a JSON object".to_string())),
+    };
+
+    let mut merged = current.as_object().cloned().unwrap_or_default();
+    for (key, value) in patch {
+        if value.is_null() {
+            merged.remove(&key);
+        } else {
+            merged.insert(key, value);
+        }
+    }
+    Ok(Value::Object(merged))
+}
+
+/// Validates custom field values against their definitions
+pub fn validate_custom_fields(conn: &mut PgConnection, values: &Value) -> Result<()> {
+    let Some(values) = values.as_object() else {
+        return Err(AppError::ValidationError("Custom fields must be a JSON object".to_string()));
+    };
+    if values.is_empty() {
+        return Ok(());
+    }
+
+    let keys: Vec<&String> = values.keys().collect();
+    let definitions: HashMap<String, CustomFieldDefinition> = custom_field_definitions::table
+        .filter(custom_field_definitions::key.eq_any(&keys))
+        .load::<CustomFieldDefinition>(conn)?
+        .into_iter()
+        .map(|d| (d.key.clone(), d))
+        .collect();
+
+    for (key, value) in values {
+        match definitions.get(key) {
+            Some(definition) => definition.check_value(value)?,
+            None => return Err(AppError::ValidationError(format!("Unknown custom field '{}'", key))),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn definition(field_type: &str, enum_values: Option<Value>) -> CustomFieldDefinition {
+        CustomFieldDefinition {
+            id: 1,
+            key: "field".to_string(),
+            name: "Field".to_string(),
+            field_type: field_type.to_string(),
+            enum_values,
+            description: None,
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    #[test]
+    fn test_check_value() {
+        assert!(definition("number", None).check_value(&json!(3)).is_ok());
+        assert!(definition("number", None).check_value(&json!("3")).is_err());
+        assert!(definition("date", None).check_value(&json!("2024-02-29")).is_ok());
+        assert!(definition("date", None).check_value(&json!("2023-02-29")).is_err());
+        assert!(definition("bool", None).check_value(&json!(true)).is_ok());
+
+        let condition = definition("enum", Some(json!(["new", "worn"])));
+        assert!(condition.check_value(&json!("worn")).is_ok());
+        assert!(condition.check_value(&json!("mint")).is_err());
+    }
+
+    #[test]
+    fn test_validate_enum_values() {
+        let values = |v: &[&str]| Some(v.iter().map(|s| s.to_string()).collect());
+        assert_eq!(
+            validate_enum_values(CustomFieldType::Enum, values(&[" new", "good "])).unwrap(),
+            values(&["new", "good"])
+        );
+        assert!(validate_enum_values(CustomFieldType::Enum, values(&["good", " good "])).is_err());
+        assert!(validate_enum_values(CustomFieldType::Enum, values(&["good", ""])).is_err());
+        assert!(validate_enum_values(CustomFieldType::Enum, values(&[])).is_err());
+        assert!(validate_enum_values(CustomFieldType::Text, values(&["good"])).is_err());
+    }
+
+    #[test]
+    fn test_merge_custom_fields() {
+        let current = json!({ "signed": true, "language": "German" });
+
+        let merged = merge_custom_fields(&current, json!({ "signed": null, "edition": 2 })).unwrap();
+        assert_eq!(merged, json!({ "language": "German", "edition": 2 }));
+
+        assert_eq!(merge_custom_fields(&current, Value::Null).unwrap(), json!({}));
+        assert!(merge_custom_fields(&current, json!([1])).is_err());
+    }
+}
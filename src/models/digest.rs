@@ -0,0 +1,253 @@
+use std::collections::HashMap;
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use diesel::prelude::*;
+use diesel::dsl::sql;
+use diesel::sql_types::{Nullable, Text};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use utoipa::ToSchema;
+use crate::db::schema::{digest_notes, digests, reading_notes};
+use crate::errors::Result;
+use crate::models::note::{NoteResponse, ReadingNote};
+
+/// Number of highlights picked per day
+pub const DIGEST_SIZE: usize = 5;
+/// Weight multiplier for favorite notes
+const FAVORITE_WEIGHT: f64 = 3.0;
+/// Days after which a shown note is as likely to be picked as one never shown
+const RESURFACE_DAYS: i64 = 30;
+/// Relative weight of a note shown yesterday
+const MIN_RECENCY_WEIGHT: f64 = 0.1;
+
+/// Note written on the same calendar date in an earlier year
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OnThisDayNote {
+    #[schema(example = 2)]
+    pub years_ago: i32,
+
+    pub note: NoteResponse,
+}
+
+/// Highlights digest of a day
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DigestResponse {
+    #[schema(value_type = String, format = Date, example = "2024-01-01")]
+    pub date: NaiveDate,
+
+    pub highlights: Vec<NoteResponse>,
+
+    /// Notes created on this date in previous years, most recent year first
+    pub on_this_day: Vec<OnThisDayNote>,
+}
+
+/// Candidate note for the digest
+#[derive(Debug, Clone, Copy)]
+struct Candidate {
+    note_id: i64,
+    is_favorite: bool,
+    /// Days since the note last appeared in a digest
+    days_since_shown: Option<i64>,
+}
+
+impl Candidate {
+    fn weight(&self) -> f64 {
+        let favorite = if self.is_favorite { FAVORITE_WEIGHT } else { 1.0 };
+        let recency = match self.days_since_shown {
+            None => 1.0,
+            Some(days) => (days.min(RESURFACE_DAYS) as f64 / RESURFACE_DAYS as f64).max(MIN_RECENCY_WEIGHT),
+        };
+        favorite * recency
+    }
+}
+
+/// Uniform value in (0, 1) derived from the date and note, stable across runs
+fn day_random(date: NaiveDate, note_id: i64) -> f64 {
+    let hash = Sha256::digest(format!("{}:{}", date, note_id).as_bytes());
+    let bits = u64::from_be_bytes(hash[..8].try_into().expect("hash has 32 bytes")) >> 11;
+    (bits as f64 + 0.5) / (1u64 << 53) as f64
+}
+
+/// Weighted sampling without replacement (Efraimidis-Spirakis)
+///
+/// Each candidate gets the key `u^(1/w)` for its day random `u` and
+/// weight `w`; the highest keys win.
+fn pick(date: NaiveDate, candidates: &[Candidate], count: usize) -> Vec<i64> {
+    let mut keyed: Vec<(f64, i64)> = candidates
+        .iter()
+        .map(|c| (day_random(date, c.note_id).powf(1.0 / c.weight()), c.note_id))
+        .collect();
+    keyed.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.cmp(&b.1)));
+    keyed.into_iter().take(count).map(|(_, id)| id).collect()
+}
+
+/// Returns the digest of a day, picking its highlights on first request
+///
+/// Highlights are picked at random, weighted toward favorites and notes
+/// that have not been shown for a while. Today's picks are stored, so the
+/// digest stays the same for the frontend and for any emails sent later.
+/// Other days return their stored picks, or a preview that is not stored.
+pub fn for_day(conn: &mut PgConnection, date: NaiveDate) -> Result<DigestResponse> {
+    let note_ids = if date == Utc::now().date_naive() {
+        highlight_ids(conn, date)?
+    } else {
+        preview_ids(conn, date)?
+    };
+
+    let mut by_id: HashMap<i64, ReadingNote> = reading_notes::table
+        .filter(reading_notes::id.eq_any(&note_ids))
+        .filter(reading_notes::deleted_at.is_null())
+        .load::<ReadingNote>(conn)?
+        .into_iter()
+        .map(|n| (n.id, n))
+        .collect();
+    let mut highlights = Vec::new();
+    for id in note_ids {
+        if let Some(note) = by_id.remove(&id) {
+            highlights.push(note.to_response(conn)?);
+        }
+    }
+
+    let year_start = NaiveDate::from_ymd_opt(date.year(), 1, 1)
+        .unwrap_or(date)
+        .and_time(chrono::NaiveTime::MIN)
+        .and_utc();
+    let earlier = reading_notes::table
+        .filter(reading_notes::deleted_at.is_null())
+        .filter(reading_notes::created_at.lt(year_start))
+        .filter(
+            sql::<Nullable<Text>>("to_char(reading_notes.created_at AT TIME ZONE 'UTC', 'MM-DD')")
+                .eq(date.format("%m-%d").to_string()),
+        )
+        .order(reading_notes::created_at.desc())
+        .load::<ReadingNote>(conn)?;
+    let mut on_this_day = Vec::new();
+    for note in earlier {
+        let years_ago = note.created_at.map(|c| date.year() - c.year()).unwrap_or_default();
+        on_this_day.push(OnThisDayNote { years_ago, note: note.to_response(conn)? });
+    }
+
+    Ok(DigestResponse { date, highlights, on_this_day })
+}
+
+/// Loads the stored highlights of a day, or picks and stores them
+///
+/// Concurrent first requests are serialized by the insert into `digests`;
+/// the losing request reads the winner's picks. Nothing is stored while
+/// there are no notes to pick from.
+fn highlight_ids(conn: &mut PgConnection, date: NaiveDate) -> Result<Vec<i64>> {
+    conn.transaction(|conn| {
+        let created = diesel::insert_into(digests::table)
+            .values(digests::digest_date.eq(date))
+            .on_conflict_do_nothing()
+            .execute(conn)?;
+
+        if created == 0 {
+            return digest_notes::table
+                .filter(digest_notes::digest_date.eq(date))
+                .order(digest_notes::position.asc())
+                .select(digest_notes::note_id)
+                .load::<i64>(conn)
+                .map_err(Into::into);
+        }
+
+        let picked = pick(date, &candidates(conn, date)?, DIGEST_SIZE);
+        if picked.is_empty() {
+            diesel::delete(digests::table.find(date)).execute(conn)?;
+            return Ok(picked);
+        }
+
+        let rows: Vec<_> = picked
+            .iter()
+            .enumerate()
+            .map(|(position, &note_id)| {
+                (
+                    digest_notes::digest_date.eq(date),
+                    digest_notes::note_id.eq(note_id),
+                    digest_notes::position.eq(position as i32),
+                )
+            })
+            .collect();
+        diesel::insert_into(digest_notes::table).values(&rows).execute(conn)?;
+
+        Ok(picked)
+    })
+}
+
+/// Loads the stored highlights of a day, or picks them without storing
+fn preview_ids(conn: &mut PgConnection, date: NaiveDate) -> Result<Vec<i64>> {
+    let stored: Vec<i64> = digest_notes::table
+        .filter(digest_notes::digest_date.eq(date))
+        .order(digest_notes::position.asc())
+        .select(digest_notes::note_id)
+        .load(conn)?;
+    if !stored.is_empty() {
+        return Ok(stored);
+    }
+
+    Ok(pick(date, &candidates(conn, date)?, DIGEST_SIZE))
+}
+
+/// Active notes with their favorite flag and when they were last shown before `date`
+fn candidates(conn: &mut PgConnection, date: NaiveDate) -> Result<Vec<Candidate>> {
+    let notes: Vec<(i64, Option<bool>, Option<DateTime<Utc>>)> = reading_notes::table
+        .filter(reading_notes::deleted_at.is_null())
+        .select((reading_notes::id, reading_notes::is_favorite, reading_notes::created_at))
+        .load(conn)?;
+
+    let last_shown: HashMap<i64, NaiveDate> = digest_notes::table
+        .filter(digest_notes::digest_date.lt(date))
+        .group_by(digest_notes::note_id)
+        .select((digest_notes::note_id, diesel::dsl::max(digest_notes::digest_date)))
+        .load::<(i64, Option<NaiveDate>)>(conn)?
+        .into_iter()
+        .filter_map(|(id, shown)| shown.map(|d| (id, d)))
+        .collect();
+
+    Ok(notes
+        .into_iter()
+        // Notes written after the day cannot be highlighted on it
+        .filter(|(_, _, created_at)| created_at.is_none_or(|c| c.date_naive() <= date))
+        .map(|(note_id, is_favorite, _)| Candidate {
+            note_id,
+            is_favorite: is_favorite.unwrap_or(false),
+            days_since_shown: last_shown.get(&note_id).map(|shown| (date - *shown).num_days()),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(note_id: i64, is_favorite: bool, days_since_shown: Option<i64>) -> Candidate {
+        Candidate { note_id, is_favorite, days_since_shown }
+    }
+
+    #[test]
+    fn test_weights() {
+        assert_eq!(candidate(1, false, None).weight(), 1.0);
+        assert_eq!(candidate(1, true, None).weight(), 3.0);
+        assert_eq!(candidate(1, false, Some(15)).weight(), 0.5);
+        assert_eq!(candidate(1, false, Some(1)).weight(), MIN_RECENCY_WEIGHT);
+        assert_eq!(candidate(1, false, Some(400)).weight(), 1.0);
+    }
+
+    #[test]
+    fn test_pick_is_stable_per_day() {
+        let candidates: Vec<Candidate> = (1..=50).map(|id| candidate(id, id % 5 == 0, None)).collect();
+        let day = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+
+        let picked = pick(day, &candidates, DIGEST_SIZE);
+        assert_eq!(picked.len(), DIGEST_SIZE);
+        assert_eq!(picked, pick(day, &candidates, DIGEST_SIZE));
+        assert_ne!(picked, pick(day.succ_opt().unwrap(), &candidates, DIGEST_SIZE));
+
+        // Favorites are picked more often over many days
+        let favorites: usize = (0..200)
+            .map(|offset| day + chrono::Days::new(offset))
+            .flat_map(|d| pick(d, &candidates, DIGEST_SIZE))
+            .filter(|id| id % 5 == 0)
+            .count();
+        assert!(favorites > 200 * DIGEST_SIZE / 5);
+    }
+}
@@ -0,0 +1,353 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use diesel::prelude::*;
+use diesel::result::DatabaseErrorKind;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use crate::db::schema::{books, loans};
+use crate::errors::{AppError, Result};
+use crate::models::book::Book;
+use crate::utils::validation::required_text;
+
+/// Longest borrower name, matching its column
+const MAX_BORROWER_LENGTH: usize = 100;
+
+/// Book loan database model
+#[derive(Debug, Serialize, Deserialize, Queryable, Selectable, Identifiable)]
+#[diesel(table_name = loans)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Loan {
+    pub id: i64,
+    pub book_id: i64,
+    pub borrower: String,
+    pub lent_date: NaiveDate,
+    pub due_date: Option<NaiveDate>,
+    pub returned_date: Option<NaiveDate>,
+    pub notes: Option<String>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub deletion_batch_id: Option<Uuid>,
+}
+
+/// New loan for insertion
+#[derive(Debug, Insertable)]
+#[diesel(table_name = loans)]
+pub struct NewLoan {
+    pub book_id: i64,
+    pub borrower: String,
+    pub lent_date: Option<NaiveDate>,
+    pub due_date: Option<NaiveDate>,
+    pub notes: Option<String>,
+}
+
+/// Loan state used to filter loan listings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LoanState {
+    /// Currently lent out
+    Active,
+    /// Lent out and past the due date
+    Overdue,
+    /// Returned
+    Returned,
+}
+
+/// Request structure for lending a book
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct CreateLoanRequest {
+    #[schema(example = "Alice")]
+    pub borrower: String,
+
+    /// Date the book was lent (default: today)
+    #[schema(example = "2024-01-01")]
+    pub lent_date: Option<NaiveDate>,
+
+    #[schema(example = "2024-02-01")]
+    pub due_date: Option<NaiveDate>,
+
+    #[schema(example = "Lent at the team offsite")]
+    pub notes: Option<String>,
+}
+
+/// Request structure for marking a loan returned
+#[derive(Debug, Default, Deserialize, Serialize, ToSchema)]
+pub struct ReturnLoanRequest {
+    /// Date the book came back (default: today)
+    #[schema(example = "2024-01-20")]
+    pub returned_date: Option<NaiveDate>,
+}
+
+/// Response structure for a loan
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LoanResponse {
+    #[schema(example = 1)]
+    pub id: i64,
+
+    #[schema(example = 1)]
+    pub book_id: i64,
+
+    #[schema(example = "Effective Java")]
+    pub book_title: String,
+
+    #[schema(example = "Alice")]
+    pub borrower: String,
+
+    #[schema(example = "2024-01-01")]
+    pub lent_date: NaiveDate,
+
+    #[schema(example = "2024-02-01")]
+    pub due_date: Option<NaiveDate>,
+
+    #[schema(example = "2024-01-20")]
+    pub returned_date: Option<NaiveDate>,
+
+    #[schema(example = "Lent at the team offsite")]
+    pub notes: Option<String>,
+
+    /// Whether the book is still out past its due date
+    #[schema(example = false)]
+    pub is_overdue: bool,
+
+    #[schema(example = "2024-01-01T12:00:00Z")]
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// Paginated loan list response
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LoanListResponse {
+    pub loans: Vec<LoanResponse>,
+
+    #[schema(example = 5)]
+    pub total: i64,
+
+    #[schema(example = 1)]
+    pub page: u32,
+
+    #[schema(example = 20)]
+    pub per_page: u32,
+
+    #[schema(example = 1)]
+    pub total_pages: u32,
+}
+
+impl Loan {
+    /// Lends a book to a borrower
+    ///
+    /// Only physical editions can be lent, and only when not already out.
+    pub fn create(conn: &mut PgConnection, book: &Book, request: CreateLoanRequest) -> Result<Loan> {
+        let borrower = required_text("Borrower", &request.borrower, MAX_BORROWER_LENGTH)?;
+        if !book.format().is_paper() {
+            return Err(AppError::ValidationError(format!(
+                "Only physical books can be lent, book {} is an {}",
+                book.id,
+                book.format().as_str()
+            )));
+        }
+
+        let lent_date = request.lent_date.unwrap_or_else(|| Utc::now().date_naive());
+        if request.due_date.is_some_and(|due| due < lent_date) {
+            return Err(AppError::ValidationError("Due date cannot be before the lent date".to_string()));
+        }
+
+        conn.transaction(|conn| {
+            if let Some(active) = Self::find_active_by_book_id(conn, book.id)? {
+                return Err(AppError::Conflict(format!(
+                    "Book {} is already lent to {} since {}",
+                    book.id, active.borrower, active.lent_date
+                )));
+            }
+
+            diesel::insert_into(loans::table)
+                .values(&NewLoan {
+                    book_id: book.id,
+                    borrower,
+                    lent_date: Some(lent_date),
+                    due_date: request.due_date,
+                    notes: request.notes,
+                })
+                .returning(Loan::as_returning())
+                .get_result(conn)
+                .map_err(|e| match e {
+                    // A concurrent request lent the book after the check above
+                    diesel::result::Error::DatabaseError(DatabaseErrorKind::UniqueViolation, ref info)
+                        if info.constraint_name() == Some("idx_loans_book_active_unique") =>
+                    {
+                        AppError::Conflict(format!("Book {} is already lent", book.id))
+                    }
+                    _ => AppError::from(e),
+                })
+        })
+    }
+
+    /// Finds a loan by ID (excluding soft deleted)
+    pub fn find_by_id(conn: &mut PgConnection, loan_id: i64) -> Result<Loan> {
+        loans::table
+            .filter(loans::id.eq(loan_id))
+            .filter(loans::deleted_at.is_null())
+            .first(conn)
+            .map_err(|_| AppError::NotFound(format!("Loan with id {} not found", loan_id)))
+    }
+
+    /// Finds the loan a book is currently out on, if any
+    pub fn find_active_by_book_id(conn: &mut PgConnection, book_id: i64) -> Result<Option<Loan>> {
+        loans::table
+            .filter(loans::book_id.eq(book_id))
+            .filter(loans::returned_date.is_null())
+            .filter(loans::deleted_at.is_null())
+            .first(conn)
+            .optional()
+            .map_err(AppError::from)
+    }
+
+    /// Loan history of a book, most recent first
+    pub fn find_by_book_id(conn: &mut PgConnection, book_id: i64) -> Result<Vec<Loan>> {
+        loans::table
+            .filter(loans::book_id.eq(book_id))
+            .filter(loans::deleted_at.is_null())
+            .order((loans::lent_date.desc(), loans::id.desc()))
+            .load::<Loan>(conn)
+            .map_err(AppError::from)
+    }
+
+    /// Lists loans of active books with an optional state filter
+    pub fn list_with_filters(
+        conn: &mut PgConnection,
+        state: Option<LoanState>,
+        page: u32,
+        per_page: u32,
+    ) -> Result<(Vec<Loan>, i64)> {
+        let offset = ((page.saturating_sub(1)) * per_page) as i64;
+        let today = Utc::now().date_naive();
+
+        let build_query = || {
+            let mut query = loans::table
+                .inner_join(books::table)
+                .filter(loans::deleted_at.is_null())
+                .filter(books::deleted_at.is_null())
+                .select(Loan::as_select())
+                .into_boxed();
+
+            match state {
+                Some(LoanState::Active) => {
+                    query = query.filter(loans::returned_date.is_null());
+                }
+                Some(LoanState::Overdue) => {
+                    query = query
+                        .filter(loans::returned_date.is_null())
+                        .filter(loans::due_date.lt(today));
+                }
+                Some(LoanState::Returned) => {
+                    query = query.filter(loans::returned_date.is_not_null());
+                }
+                None => {}
+            }
+
+            query
+        };
+
+        let loans = build_query()
+            .order((loans::due_date.asc().nulls_last(), loans::lent_date.desc()))
+            .limit(per_page as i64)
+            .offset(offset)
+            .load::<Loan>(conn)?;
+
+        let total = build_query().count().get_result::<i64>(conn)?;
+
+        Ok((loans, total))
+    }
+
+    /// Marks a loan as returned
+    pub fn mark_returned(
+        conn: &mut PgConnection,
+        loan_id: i64,
+        request: ReturnLoanRequest,
+    ) -> Result<Loan> {
+        let loan = Self::find_by_id(conn, loan_id)?;
+
+        if loan.returned_date.is_some() {
+            return Err(AppError::Conflict(format!("Loan {} has already been returned", loan_id)));
+        }
+
+        let returned_date = request.returned_date.unwrap_or_else(|| Utc::now().date_naive());
+        if returned_date < loan.lent_date {
+            return Err(AppError::ValidationError("Returned date cannot be before the lent date".to_string()));
+        }
+
+        diesel::update(loans::table.find(loan_id))
+            .set(loans::returned_date.eq(Some(returned_date)))
+            .returning(Loan::as_returning())
+            .get_result(conn)
+            .map_err(AppError::from)
+    }
+
+    /// Whether the book is still out past its due date on the given day
+    pub fn is_overdue_on(&self, today: NaiveDate) -> bool {
+        self.returned_date.is_none() && self.due_date.is_some_and(|due| due < today)
+    }
+
+    /// Converts to a response with the book title
+    pub fn to_response(&self, book_title: &str) -> LoanResponse {
+        LoanResponse {
+            id: self.id,
+            book_id: self.book_id,
+            book_title: book_title.to_string(),
+            borrower: self.borrower.clone(),
+            lent_date: self.lent_date,
+            due_date: self.due_date,
+            returned_date: self.returned_date,
+            notes: self.notes.clone(),
+            is_overdue: self.is_overdue_on(Utc::now().date_naive()),
+            created_at: self.created_at,
+        }
+    }
+
+    /// Converts loans to responses, looking up their book titles
+    pub fn to_responses(conn: &mut PgConnection, loans: Vec<Loan>) -> Result<Vec<LoanResponse>> {
+        let book_ids: Vec<i64> = loans.iter().map(|l| l.book_id).collect();
+        let titles: std::collections::HashMap<i64, String> = books::table
+            .filter(books::id.eq_any(&book_ids))
+            .select((books::id, books::title))
+            .load::<(i64, String)>(conn)?
+            .into_iter()
+            .collect();
+
+        Ok(loans
+            .iter()
+            .map(|loan| loan.to_response(titles.get(&loan.book_id).map(String::as_str).unwrap_or_default()))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn loan(due_date: Option<NaiveDate>, returned_date: Option<NaiveDate>) -> Loan {
+        Loan {
+            id: 1,
+            book_id: 1,
+            borrower: "Alice".to_string(),
+            lent_date: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+            due_date,
+            returned_date,
+            notes: None,
+            deleted_at: None,
+            created_at: None,
+            updated_at: None,
+            deletion_batch_id: None,
+        }
+    }
+
+    #[test]
+    fn test_is_overdue_on() {
+        let due = NaiveDate::from_ymd_opt(2024, 2, 1);
+        let before = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let after = NaiveDate::from_ymd_opt(2024, 2, 2).unwrap();
+
+        assert!(!loan(due, None).is_overdue_on(before));
+        assert!(loan(due, None).is_overdue_on(after));
+        assert!(!loan(due, Some(before)).is_overdue_on(after));
+        assert!(!loan(None, None).is_overdue_on(after));
+    }
+}
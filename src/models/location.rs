@@ -0,0 +1,436 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use crate::db::schema::{books, locations};
+use crate::errors::{AppError, Result};
+use crate::models::book::{AcquisitionStatus, Book, BookResponse};
+use crate::utils::validation::{optional_text, required_text};
+
+/// Separator between the levels of a location path
+const PATH_SEPARATOR: &str = " / ";
+/// Longest location name, matching its column
+const MAX_NAME_LENGTH: usize = 100;
+
+/// Level of a physical location in the home library
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LocationKind {
+    Room,
+    Bookcase,
+    Shelf,
+}
+
+impl LocationKind {
+    /// Returns the value stored in the `locations.kind` column
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LocationKind::Room => "room",
+            LocationKind::Bookcase => "bookcase",
+            LocationKind::Shelf => "shelf",
+        }
+    }
+
+    /// Kind the parent location must have, `None` for top-level rooms
+    pub fn parent_kind(&self) -> Option<LocationKind> {
+        match self {
+            LocationKind::Room => None,
+            LocationKind::Bookcase => Some(LocationKind::Room),
+            LocationKind::Shelf => Some(LocationKind::Bookcase),
+        }
+    }
+}
+
+impl FromStr for LocationKind {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "room" => Ok(LocationKind::Room),
+            "bookcase" => Ok(LocationKind::Bookcase),
+            "shelf" => Ok(LocationKind::Shelf),
+            _ => Err(AppError::ValidationError(format!(
+                "Invalid location kind '{}', expected one of: room, bookcase, shelf",
+                s
+            ))),
+        }
+    }
+}
+
+/// Physical location database model
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Selectable, Identifiable)]
+#[diesel(table_name = locations)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Location {
+    pub id: i64,
+    pub name: String,
+    pub kind: String,
+    pub parent_id: Option<i64>,
+    pub description: Option<String>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// New location for insertion
+#[derive(Debug, Insertable)]
+#[diesel(table_name = locations)]
+pub struct NewLocation {
+    pub name: String,
+    pub kind: String,
+    pub parent_id: Option<i64>,
+    pub description: Option<String>,
+}
+
+/// Update location structure
+#[derive(Debug, Deserialize, AsChangeset, Default, ToSchema)]
+#[diesel(table_name = locations)]
+pub struct UpdateLocation {
+    #[schema(example = "Shelf 2")]
+    pub name: Option<String>,
+
+    /// Moves the location under another parent of the matching kind
+    #[schema(example = 2)]
+    pub parent_id: Option<i64>,
+
+    #[schema(example = "Second shelf from the top")]
+    pub description: Option<String>,
+}
+
+/// Request structure for creating a location
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct CreateLocationRequest {
+    #[schema(example = "Shelf 2")]
+    pub name: String,
+
+    #[schema(example = "shelf")]
+    pub kind: LocationKind,
+
+    /// Parent location: a room for bookcases, a bookcase for shelves
+    #[schema(example = 2)]
+    pub parent_id: Option<i64>,
+
+    #[schema(example = "Second shelf from the top")]
+    pub description: Option<String>,
+}
+
+/// Response structure for a location
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LocationResponse {
+    #[schema(example = 3)]
+    pub id: i64,
+
+    #[schema(example = "Shelf 2")]
+    pub name: String,
+
+    #[schema(example = "shelf")]
+    pub kind: LocationKind,
+
+    #[schema(example = 2)]
+    pub parent_id: Option<i64>,
+
+    /// Full path from the room down to this location
+    #[schema(example = "Office / Bookcase A / Shelf 2")]
+    pub path: String,
+
+    #[schema(example = "Second shelf from the top")]
+    pub description: Option<String>,
+
+    #[schema(example = "2024-01-01T12:00:00Z")]
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl Location {
+    /// Returns the parsed location kind
+    pub fn kind(&self) -> LocationKind {
+        self.kind.parse().unwrap_or(LocationKind::Shelf)
+    }
+
+    /// Creates a location under a parent of the matching kind
+    pub fn create(conn: &mut PgConnection, request: CreateLocationRequest) -> Result<Location> {
+        let name = required_text("Name", &request.name, MAX_NAME_LENGTH)?;
+        validate_parent(conn, request.kind, request.parent_id)?;
+
+        diesel::insert_into(locations::table)
+            .values(&NewLocation {
+                name,
+                kind: request.kind.as_str().to_string(),
+                parent_id: request.parent_id,
+                description: request.description,
+            })
+            .returning(Location::as_returning())
+            .get_result(conn)
+            .map_err(AppError::from)
+    }
+
+    /// Finds a location by ID (excluding soft deleted)
+    pub fn find_by_id(conn: &mut PgConnection, location_id: i64) -> Result<Location> {
+        locations::table
+            .filter(locations::id.eq(location_id))
+            .filter(locations::deleted_at.is_null())
+            .first(conn)
+            .map_err(|_| AppError::NotFound(format!("Location with id {} not found", location_id)))
+    }
+
+    /// Lists all active locations
+    pub fn list_all(conn: &mut PgConnection) -> Result<Vec<Location>> {
+        locations::table
+            .filter(locations::deleted_at.is_null())
+            .order((locations::parent_id.asc().nulls_first(), locations::name.asc()))
+            .load::<Location>(conn)
+            .map_err(AppError::from)
+    }
+
+    /// Updates a location
+    pub fn update(conn: &mut PgConnection, location_id: i64, mut update_data: UpdateLocation) -> Result<Location> {
+        let location = Self::find_by_id(conn, location_id)?;
+        update_data.name = optional_text("Name", update_data.name, MAX_NAME_LENGTH)?;
+        if update_data.parent_id.is_some() {
+            validate_parent(conn, location.kind(), update_data.parent_id)?;
+        }
+
+        diesel::update(locations::table.find(location_id))
+            .set((&update_data, locations::updated_at.eq(Some(Utc::now()))))
+            .returning(Location::as_returning())
+            .get_result(conn)
+            .map_err(AppError::from)
+    }
+
+    /// Soft deletes an empty location
+    ///
+    /// Locations that still contain other locations or books cannot be deleted.
+    pub fn soft_delete(conn: &mut PgConnection, location_id: i64) -> Result<()> {
+        Self::find_by_id(conn, location_id)?;
+
+        let children = locations::table
+            .filter(locations::parent_id.eq(location_id))
+            .filter(locations::deleted_at.is_null())
+            .count()
+            .get_result::<i64>(conn)?;
+        let books = books::table
+            .filter(books::location_id.eq(location_id))
+            .filter(books::deleted_at.is_null())
+            .count()
+            .get_result::<i64>(conn)?;
+        if children > 0 || books > 0 {
+            return Err(AppError::Conflict(format!(
+                "Location {} still contains {} locations and {} books",
+                location_id, children, books
+            )));
+        }
+
+        diesel::update(locations::table.find(location_id))
+            .set(locations::deleted_at.eq(Some(Utc::now())))
+            .execute(conn)?;
+
+        Ok(())
+    }
+
+    /// Returns the ids of a location and everything nested below it
+    pub fn subtree_ids(conn: &mut PgConnection, location_id: i64) -> Result<Vec<i64>> {
+        let mut ids = vec![location_id];
+        let mut frontier = vec![location_id];
+
+        while !frontier.is_empty() {
+            frontier = locations::table
+                .filter(locations::parent_id.eq_any(&frontier))
+                .filter(locations::deleted_at.is_null())
+                .select(locations::id)
+                .load::<i64>(conn)?;
+            ids.extend(&frontier);
+        }
+
+        Ok(ids)
+    }
+
+    /// Lists active books on a location or anywhere below it
+    pub fn list_books(
+        conn: &mut PgConnection,
+        location_id: i64,
+        page: u32,
+        per_page: u32,
+    ) -> Result<(Vec<Book>, i64)> {
+        Self::find_by_id(conn, location_id)?;
+        let ids = Self::subtree_ids(conn, location_id)?;
+        let offset = ((page.saturating_sub(1)) * per_page) as i64;
+
+        let books = books::table
+            .filter(books::location_id.eq_any(&ids))
+            .filter(books::deleted_at.is_null())
+            .order(books::title.asc())
+            .limit(per_page as i64)
+            .offset(offset)
+            .load::<Book>(conn)?;
+
+        let total = books::table
+            .filter(books::location_id.eq_any(&ids))
+            .filter(books::deleted_at.is_null())
+            .count()
+            .get_result::<i64>(conn)?;
+
+        Ok((books, total))
+    }
+
+    /// Resolves full paths ("Office / Bookcase A / Shelf 2") for the given locations
+    pub fn paths(conn: &mut PgConnection, location_ids: &[i64]) -> Result<HashMap<i64, String>> {
+        let mut known: HashMap<i64, Location> = HashMap::new();
+        let mut pending: Vec<i64> = location_ids.to_vec();
+
+        // Walk up one level per query; the hierarchy is at most three levels deep
+        while !pending.is_empty() {
+            let loaded = locations::table
+                .filter(locations::id.eq_any(&pending))
+                .load::<Location>(conn)?;
+            pending = loaded
+                .iter()
+                .filter_map(|l| l.parent_id)
+                .filter(|id| !known.contains_key(id))
+                .collect();
+            known.extend(loaded.into_iter().map(|l| (l.id, l)));
+        }
+
+        Ok(location_ids
+            .iter()
+            .filter(|id| known.contains_key(id))
+            .map(|&id| (id, build_path(&known, id)))
+            .collect())
+    }
+
+    /// Converts to a response with the full path
+    pub fn to_response(&self, path: String) -> LocationResponse {
+        LocationResponse {
+            id: self.id,
+            name: self.name.clone(),
+            kind: self.kind(),
+            parent_id: self.parent_id,
+            path,
+            description: self.description.clone(),
+            created_at: self.created_at,
+        }
+    }
+
+    /// Converts locations to responses, resolving their paths
+    pub fn to_responses(conn: &mut PgConnection, locations: &[Location]) -> Result<Vec<LocationResponse>> {
+        let ids: Vec<i64> = locations.iter().map(|l| l.id).collect();
+        let paths = Self::paths(conn, &ids)?;
+
+        let mut responses: Vec<LocationResponse> = locations
+            .iter()
+            .map(|l| l.to_response(paths.get(&l.id).cloned().unwrap_or_else(|| l.name.clone())))
+            .collect();
+        responses.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(responses)
+    }
+}
+
+/// Builds the path of a location from already loaded ancestors
+fn build_path(known: &HashMap<i64, Location>, location_id: i64) -> String {
+    let mut names = Vec::new();
+    let mut current = known.get(&location_id);
+    while let Some(location) = current {
+        names.push(location.name.as_str());
+        current = location.parent_id.and_then(|id| known.get(&id));
+    }
+    names.reverse();
+    names.join(PATH_SEPARATOR)
+}
+
+/// Checks that a location of `kind` may be placed under `parent_id`
+fn validate_parent(conn: &mut PgConnection, kind: LocationKind, parent_id: Option<i64>) -> Result<()> {
+    match (kind.parent_kind(), parent_id) {
+        (None, None) => Ok(()),
+        (None, Some(_)) => Err(AppError::ValidationError("A room cannot have a parent location".to_string())),
+        (Some(expected), None) => Err(AppError::ValidationError(format!(
+            "A {} must be placed in a {}",
+            kind.as_str(),
+            expected.as_str()
+        ))),
+        (Some(expected), Some(parent_id)) => {
+            let parent = Location::find_by_id(conn, parent_id)?;
+            if parent.kind() != expected {
+                return Err(AppError::ValidationError(format!(
+                    "A {} must be placed in a {}, location {} is a {}",
+                    kind.as_str(),
+                    expected.as_str(),
+                    parent_id,
+                    parent.kind
+                )));
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Fills in `location_path` on book responses
+pub fn attach_location_paths(conn: &mut PgConnection, responses: &mut [BookResponse]) -> Result<()> {
+    let ids: Vec<i64> = responses.iter().filter_map(|b| b.location_id).collect();
+    if ids.is_empty() {
+        return Ok(());
+    }
+
+    let paths = Location::paths(conn, &ids)?;
+    for response in responses.iter_mut() {
+        response.location_path = response.location_id.and_then(|id| paths.get(&id).cloned());
+    }
+    Ok(())
+}
+
+/// Checks that a book with the given acquisition status can be placed on a location
+pub fn validate_book_location(
+    conn: &mut PgConnection,
+    location_id: Option<i64>,
+    status: AcquisitionStatus,
+) -> Result<()> {
+    if let Some(location_id) = location_id {
+        if status != AcquisitionStatus::Owned {
+            return Err(AppError::ValidationError(format!(
+                "Only owned books can be assigned a location, this book is {}",
+                status.as_str()
+            )));
+        }
+        Location::find_by_id(conn, location_id)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn location(id: i64, name: &str, kind: &str, parent_id: Option<i64>) -> Location {
+        Location {
+            id,
+            name: name.to_string(),
+            kind: kind.to_string(),
+            parent_id,
+            description: None,
+            deleted_at: None,
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    #[test]
+    fn test_build_path() {
+        let known: HashMap<i64, Location> = [
+            location(1, "Office", "room", None),
+            location(2, "Bookcase A", "bookcase", Some(1)),
+            location(3, "Shelf 2", "shelf", Some(2)),
+        ]
+        .into_iter()
+        .map(|l| (l.id, l))
+        .collect();
+
+        assert_eq!(build_path(&known, 3), "Office / Bookcase A / Shelf 2");
+        assert_eq!(build_path(&known, 1), "Office");
+    }
+
+    #[test]
+    fn test_location_kind_hierarchy() {
+        assert_eq!(LocationKind::Room.parent_kind(), None);
+        assert_eq!(LocationKind::Shelf.parent_kind(), Some(LocationKind::Bookcase));
+        assert!("drawer".parse::<LocationKind>().is_err());
+    }
+}
@@ -1,11 +1,45 @@
+pub mod acquisition;
+pub mod attachment;
 pub mod book;
 pub mod category;
+pub mod custom_field;
+pub mod digest;
+pub mod loan;
+pub mod location;
 pub mod tag;
 pub mod note;
+pub mod note_bulk;
+pub mod note_link;
+pub mod note_location;
+pub mod note_revision;
+pub mod note_template;
+pub mod note_type;
 pub mod reading_status;
+pub mod recommendation;
+pub mod review;
+pub mod share_link;
+pub mod trash;
+pub mod writing_stats;
 
-pub use book::{Book, NewBook, UpdateBook, CreateBookRequest, BookResponse, BookListResponse};
+pub use acquisition::{AcquireBookRequest, SpendingPeriod, SpendingEntry};
+pub use attachment::{NoteAttachment, NewNoteAttachment, AttachmentResponse};
+pub use book::{Book, BookFormat, AcquisitionStatus, NewBook, UpdateBook, BookChangeset, PatchBookRequest, CreateBookRequest, BookResponse, BookListResponse};
+pub use custom_field::{CustomFieldDefinition, CustomFieldType, CreateCustomFieldRequest, UpdateCustomFieldRequest, CustomFieldResponse};
 pub use category::{Category, NewCategory};
-pub use tag::{Tag, NewTag, UpdateTag, CreateTagRequest, TagResponse, TagListResponse, PopularTagResponse};
-pub use note::{ReadingNote, NewReadingNote, UpdateReadingNote, CreateNoteRequest, NoteResponse, NoteListResponse, NoteType};
-pub use reading_status::{ReadingStatus, NewReadingStatus, UpdateReadingStatus};
\ No newline at end of file
+pub use digest::{DigestResponse, OnThisDayNote};
+pub use loan::{Loan, NewLoan, LoanState, CreateLoanRequest, ReturnLoanRequest, LoanResponse, LoanListResponse};
+pub use location::{Location, NewLocation, UpdateLocation, LocationKind, CreateLocationRequest, LocationResponse};
+pub use tag::{Tag, NewTag, UpdateTag, PatchTagRequest, MergeTagRequest, CreateTagRequest, TagResponse, TagListResponse, PopularTagResponse};
+pub use note::{ReadingNote, NewReadingNote, UpdateReadingNote, NoteChangeset, PatchNoteRequest, CreateNoteRequest, NoteResponse, NoteListResponse, NoteFilter, NoteSort, ReorderNotesRequest, NoteThread, NoteThreadResponse};
+pub use recommendation::{SimilarBook, Recommendation};
+pub use review::{NoteReview, ReviewGrade, ReviewRequest, ReviewStateResponse, DueNoteResponse, DueReviewResponse};
+pub use note_bulk::{BulkNoteRequest, BulkNoteResult, BulkNoteResponse};
+pub use note_link::{NoteLink, NewNoteLink, LinkTargetType, NoteLinkResponse};
+pub use note_location::NoteLocation;
+pub use note_revision::{NoteRevision, NewNoteRevision, NoteRevisionResponse, NoteDiffResponse, DiffSegment, DiffOp};
+pub use note_template::{NoteTemplate, NewNoteTemplate, UpdateNoteTemplate, CreateNoteTemplateRequest, UpdateNoteTemplateRequest, NoteTemplateResponse, CreateNoteFromTemplateRequest};
+pub use note_type::{NoteType, NewNoteType, UpdateNoteType, CreateNoteTypeRequest, UpdateNoteTypeRequest, NoteTypeResponse};
+pub use reading_status::{ReadingStatus, NewReadingStatus, UpdateReadingStatus, UpdateReadingStatusRequest, ReadingStatusResponse};
+pub use share_link::{ShareLink, NewShareLink, CreateShareLinkRequest, ShareLinkResponse, SharedBook, SharedNote, SharedContentResponse};
+pub use trash::{TrashEntity, TrashItem, TrashResponse};
+pub use writing_stats::{WritingTotals, BookWritingStats, MonthWritingStats, WritingStatsResponse};
\ No newline at end of file
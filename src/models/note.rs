@@ -1,42 +1,140 @@
-use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use diesel::pg::Pg;
 use diesel::prelude::*;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
-use crate::db::schema::{reading_notes, note_tags};
+use uuid::Uuid;
+use crate::db::schema::{books, reading_notes, note_tags, tags};
 use crate::errors::{AppError, Result};
+use crate::models::attachment::{self, AttachmentResponse, NoteAttachment};
+use crate::models::note_link::{NoteLink, NoteLinkResponse};
+use crate::models::note_location::NoteLocation;
+use crate::models::note_revision::NoteRevision;
+use crate::models::note_type::validate_note_type;
+use crate::models::tag::{slugify, Tag};
+use crate::utils::markdown;
+use crate::utils::text_stats::{self, TextStats};
+use crate::utils::patch::{nullable, required};
 
-/// Note type enumeration
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+/// Order of listed notes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "snake_case")]
-pub enum NoteType {
-    Quote,      // 摘录
-    Summary,    // 总结
-    Thought,    // 感想
-    General,    // 一般笔记
+pub enum NoteSort {
+    /// Newest first
+    #[default]
+    Created,
+    /// Most recently updated first
+    Updated,
+    /// Reading order by page, Kindle location, percentage and chapter
+    Position,
+    /// Alphabetical by title, untitled notes last
+    Title,
+    /// Alphabetical by book title, in reading order within each book
+    Book,
+    /// Manually arranged order, unarranged notes last and newest first
+    Manual,
 }
 
-impl From<String> for NoteType {
-    fn from(s: String) -> Self {
-        match s.as_str() {
-            "quote" => NoteType::Quote,
-            "summary" => NoteType::Summary,
-            "thought" => NoteType::Thought,
-            _ => NoteType::General,
+impl FromStr for NoteSort {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "created" => Ok(NoteSort::Created),
+            "updated" => Ok(NoteSort::Updated),
+            "position" => Ok(NoteSort::Position),
+            "title" => Ok(NoteSort::Title),
+            "book" => Ok(NoteSort::Book),
+            "manual" => Ok(NoteSort::Manual),
+            _ => Err(AppError::ValidationError(format!(
+                "Invalid sort '{}', expected one of: created, updated, position, title, book, manual",
+                s
+            ))),
         }
     }
 }
 
-impl From<NoteType> for String {
-    fn from(note_type: NoteType) -> Self {
-        match note_type {
-            NoteType::Quote => "quote".to_string(),
-            NoteType::Summary => "summary".to_string(),
-            NoteType::Thought => "thought".to_string(),
-            NoteType::General => "general".to_string(),
+/// Filters for listing notes; all set filters must match
+#[derive(Debug, Default)]
+pub struct NoteFilter {
+    /// Matched against title and content
+    pub search: Option<String>,
+    pub note_type: Option<String>,
+    pub book_id: Option<i64>,
+    /// Tag names or slugs; notes must carry every tag
+    pub tags: Vec<String>,
+    pub is_favorite: Option<bool>,
+    /// Inclusive bounds on the creation date
+    pub created_from: Option<NaiveDate>,
+    pub created_to: Option<NaiveDate>,
+    /// Inclusive bounds on the date of the last update
+    pub updated_from: Option<NaiveDate>,
+    pub updated_to: Option<NaiveDate>,
+    /// Only list notes that are not replies to an active note
+    pub collapse_replies: bool,
+}
+
+impl NoteFilter {
+    /// Checks that the date ranges are not reversed
+    pub fn validate(&self) -> Result<()> {
+        for (name, from, to) in [
+            ("created", self.created_from, self.created_to),
+            ("updated", self.updated_from, self.updated_to),
+        ] {
+            if let (Some(from), Some(to)) = (from, to) {
+                if from > to {
+                    return Err(AppError::ValidationError(format!(
+                        "{}_from {} is after {}_to {}",
+                        name, from, name, to
+                    )));
+                }
+            }
         }
+        Ok(())
+    }
+}
+
+type NoteQuery<'a> = diesel::dsl::IntoBoxed<'a, diesel::dsl::InnerJoin<reading_notes::table, books::table>, Pg>;
+
+/// Applies the sort order, breaking ties by creation time
+///
+/// With `pinned_first`, pinned notes come before all others in that order.
+fn ordered(query: NoteQuery<'_>, sort: NoteSort, pinned_first: bool) -> NoteQuery<'_> {
+    let in_reading_order = (
+        reading_notes::page_start.asc().nulls_last(),
+        reading_notes::page_end.asc().nulls_last(),
+        reading_notes::kindle_location.asc().nulls_last(),
+        reading_notes::percentage.asc().nulls_last(),
+        reading_notes::chapter.asc().nulls_last(),
+        reading_notes::created_at.asc(),
+    );
+    let query = if pinned_first {
+        query.order(reading_notes::is_pinned.desc())
+    } else {
+        query
+    };
+    match sort {
+        NoteSort::Created => query.then_order_by(reading_notes::created_at.desc()),
+        NoteSort::Updated => query.then_order_by((reading_notes::updated_at.desc().nulls_last(), reading_notes::created_at.desc())),
+        NoteSort::Position => query.then_order_by(in_reading_order),
+        NoteSort::Title => query.then_order_by((reading_notes::title.asc().nulls_last(), reading_notes::created_at.desc())),
+        NoteSort::Book => query.then_order_by((books::title.asc(), books::id.asc())).then_order_by(in_reading_order),
+        NoteSort::Manual => query.then_order_by((reading_notes::sort_order.asc().nulls_last(), reading_notes::created_at.desc())),
     }
 }
 
+/// Start of a day in UTC
+fn day_start(date: NaiveDate) -> DateTime<Utc> {
+    date.and_time(chrono::NaiveTime::MIN).and_utc()
+}
+
+/// Start of the following day in UTC, the exclusive end of an inclusive date bound
+fn day_end(date: NaiveDate) -> DateTime<Utc> {
+    day_start(date.checked_add_days(Days::new(1)).unwrap_or(date))
+}
+
 /// Reading note database model
 #[derive(Debug, Serialize, Deserialize, Queryable, Selectable, Identifiable)]
 #[diesel(table_name = reading_notes)]
@@ -52,10 +150,25 @@ pub struct ReadingNote {
     pub deleted_at: Option<DateTime<Utc>>,
     pub created_at: Option<DateTime<Utc>>,
     pub updated_at: Option<DateTime<Utc>>,
+    pub deletion_batch_id: Option<Uuid>,
+    pub page_start: Option<i32>,
+    pub page_end: Option<i32>,
+    pub chapter: Option<i32>,
+    pub kindle_location: Option<i32>,
+    pub percentage: Option<i32>,
+    pub sort_order: Option<i32>,
+    pub is_pinned: bool,
+    pub parent_id: Option<i64>,
+    pub char_count: i32,
+    pub word_count: i32,
+    pub reading_time_seconds: i32,
 }
 
 /// New reading note for insertion
-#[derive(Debug, Insertable)]
+///
+/// The structured location and text statistics columns are filled by
+/// `ReadingNote::create`.
+#[derive(Debug, Default, Insertable)]
 #[diesel(table_name = reading_notes)]
 pub struct NewReadingNote {
     pub book_id: i64,
@@ -64,6 +177,15 @@ pub struct NewReadingNote {
     pub note_type: Option<String>,
     pub page_reference: Option<String>,
     pub is_favorite: Option<bool>,
+    pub page_start: Option<i32>,
+    pub page_end: Option<i32>,
+    pub chapter: Option<i32>,
+    pub kindle_location: Option<i32>,
+    pub percentage: Option<i32>,
+    pub parent_id: Option<i64>,
+    pub char_count: i32,
+    pub word_count: i32,
+    pub reading_time_seconds: i32,
 }
 
 /// Update reading note structure
@@ -84,6 +206,169 @@ pub struct UpdateReadingNote {
     
     #[schema(example = true)]
     pub is_favorite: Option<bool>,
+
+    #[schema(example = false)]
+    pub is_pinned: Option<bool>,
+}
+
+/// Reading note changes with tri-state nullable columns
+///
+/// `None` leaves a column untouched and `Some(None)` sets it to NULL.
+#[derive(Debug, Default, AsChangeset)]
+#[diesel(table_name = reading_notes)]
+pub struct NoteChangeset {
+    pub title: Option<Option<String>>,
+    pub content: Option<String>,
+    pub note_type: Option<Option<String>>,
+    pub page_reference: Option<Option<String>>,
+    pub is_favorite: Option<Option<bool>>,
+    pub page_start: Option<Option<i32>>,
+    pub page_end: Option<Option<i32>>,
+    pub chapter: Option<Option<i32>>,
+    pub kindle_location: Option<Option<i32>>,
+    pub percentage: Option<Option<i32>>,
+    pub is_pinned: Option<bool>,
+    pub parent_id: Option<Option<i64>>,
+    pub char_count: Option<i32>,
+    pub word_count: Option<i32>,
+    pub reading_time_seconds: Option<i32>,
+}
+
+impl NoteChangeset {
+    /// Sets the structured location columns
+    fn set_location(&mut self, location: NoteLocation) {
+        self.page_start = Some(location.page_start);
+        self.page_end = Some(location.page_end);
+        self.chapter = Some(location.chapter);
+        self.kindle_location = Some(location.kindle_location);
+        self.percentage = Some(location.percentage);
+    }
+
+    /// Sets the text statistics columns
+    fn set_stats(&mut self, stats: TextStats) {
+        self.char_count = Some(stats.char_count);
+        self.word_count = Some(stats.word_count);
+        self.reading_time_seconds = Some(stats.reading_time_seconds);
+    }
+
+    /// Whether applying the changes alters any revisioned field of the note
+    pub fn revises(&self, note: &ReadingNote) -> bool {
+        self.title.as_ref().is_some_and(|title| *title != note.title)
+            || self.content.as_ref().is_some_and(|content| *content != note.content)
+            || self.note_type.as_ref().is_some_and(|note_type| *note_type != note.note_type)
+            || self.page_reference.as_ref().is_some_and(|page| *page != note.page_reference)
+    }
+}
+
+/// Request structure for patching a note (JSON Merge Patch)
+///
+/// Absent fields are left unchanged, `null` clears optional fields.
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct PatchNoteRequest {
+    #[serde(default, deserialize_with = "nullable")]
+    #[schema(value_type = Option<String>, nullable, example = "Chapter 1 Summary")]
+    pub title: Option<Option<String>>,
+    
+    /// Cannot be null
+    #[serde(default, deserialize_with = "nullable")]
+    #[schema(value_type = Option<String>, example = "Updated note content in Markdown")]
+    pub content: Option<Option<String>>,
+    
+    #[serde(default, deserialize_with = "nullable")]
+    #[schema(value_type = Option<String>, nullable, example = "summary")]
+    pub note_type: Option<Option<String>>,
+    
+    #[serde(default, deserialize_with = "nullable")]
+    #[schema(value_type = Option<String>, nullable, example = "Pages 10-20")]
+    pub page_reference: Option<Option<String>>,
+    
+    /// Cannot be null
+    #[serde(default, deserialize_with = "nullable")]
+    #[schema(value_type = Option<bool>, example = true)]
+    pub is_favorite: Option<Option<bool>>,
+
+    /// Pinned notes are listed first among a book's notes; cannot be null
+    #[serde(default, deserialize_with = "nullable")]
+    #[schema(value_type = Option<bool>, example = true)]
+    pub is_pinned: Option<Option<bool>>,
+
+    /// Note of the same book this note replies to; `null` makes it a top-level note
+    #[serde(default, deserialize_with = "nullable")]
+    #[schema(value_type = Option<i64>, nullable, example = 1)]
+    pub parent_id: Option<Option<i64>>,
+}
+
+impl From<UpdateReadingNote> for NoteChangeset {
+    fn from(update: UpdateReadingNote) -> Self {
+        Self {
+            title: update.title.map(Some),
+            content: update.content,
+            note_type: update.note_type.map(Some),
+            page_reference: update.page_reference.map(Some),
+            is_favorite: update.is_favorite.map(Some),
+            is_pinned: update.is_pinned,
+            ..Default::default()
+        }
+    }
+}
+
+impl TryFrom<PatchNoteRequest> for NoteChangeset {
+    type Error = AppError;
+
+    fn try_from(patch: PatchNoteRequest) -> Result<Self> {
+        Ok(Self {
+            title: patch.title,
+            content: required("content", patch.content)?,
+            note_type: patch.note_type,
+            page_reference: patch.page_reference,
+            is_favorite: required("is_favorite", patch.is_favorite)?.map(Some),
+            is_pinned: required("is_pinned", patch.is_pinned)?,
+            parent_id: patch.parent_id,
+            ..Default::default()
+        })
+    }
+}
+
+/// Note with its replies, each with their own replies
+#[derive(Debug)]
+pub struct NoteThread {
+    pub note: ReadingNote,
+    pub replies: Vec<NoteThread>,
+}
+
+impl NoteThread {
+    /// Builds the tree below `note` from replies grouped by parent
+    fn assemble(note: ReadingNote, replies: &mut HashMap<i64, Vec<ReadingNote>>) -> NoteThread {
+        let children = replies.remove(&note.id).unwrap_or_default();
+        NoteThread {
+            replies: children.into_iter().map(|child| Self::assemble(child, replies)).collect(),
+            note,
+        }
+    }
+
+    /// Converts the thread to a response
+    pub fn to_response(&self, conn: &mut PgConnection) -> Result<NoteThreadResponse> {
+        let mut replies = Vec::new();
+        for reply in &self.replies {
+            replies.push(reply.to_response(conn)?);
+        }
+        Ok(NoteThreadResponse { note: self.note.to_response(conn)?, replies })
+    }
+}
+
+/// Note with its replies, oldest reply first
+#[derive(Debug, Serialize, ToSchema)]
+pub struct NoteThreadResponse {
+    pub note: NoteResponse,
+    pub replies: Vec<NoteThreadResponse>,
+}
+
+/// Request structure for arranging a book's notes
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct ReorderNotesRequest {
+    /// Note IDs in their new order; notes of the book that are not listed follow them
+    #[schema(example = json!([3, 1, 2]))]
+    pub note_ids: Vec<i64>,
 }
 
 /// Request structure for creating a new reading note
@@ -98,14 +383,19 @@ pub struct CreateNoteRequest {
     #[schema(example = "This chapter introduces the main concepts...")]
     pub content: String,
     
+    /// Key of a note type, see `/api/note-types`
     #[schema(example = "summary")]
-    pub note_type: Option<NoteType>,
+    pub note_type: Option<String>,
     
     #[schema(example = "Pages 1-15")]
     pub page_reference: Option<String>,
     
     #[schema(example = false)]
     pub is_favorite: Option<bool>,
+
+    /// Note of the same book this note replies to
+    #[schema(example = 1)]
+    pub parent_id: Option<i64>,
     
     #[schema(example = json!(["important", "chapter1"]))]
     pub tags: Option<Vec<String>>,
@@ -125,18 +415,64 @@ pub struct NoteResponse {
     
     #[schema(example = "This chapter introduces the main concepts...")]
     pub content: String,
+
+    /// Sanitized HTML rendering of the content, only with `?render=html`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = "<p>This chapter introduces the main concepts...</p>")]
+    pub content_html: Option<String>,
+
+    /// Plain-text excerpt of the content without Markdown markup
+    #[schema(example = "This chapter introduces the main concepts...")]
+    pub excerpt: String,
     
+    /// Key of a note type, see `/api/note-types`
     #[schema(example = "summary")]
-    pub note_type: Option<NoteType>,
+    pub note_type: Option<String>,
     
     #[schema(example = "Pages 1-15")]
     pub page_reference: Option<String>,
+
+    /// Position parsed from the page reference, absent when it could not be parsed
+    pub location: Option<NoteLocation>,
     
     #[schema(example = false)]
     pub is_favorite: bool,
+
+    /// Pinned notes are listed first among a book's notes
+    #[schema(example = false)]
+    pub is_pinned: bool,
+
+    /// Position in the book's manual order, absent until the notes are arranged
+    #[schema(example = 0)]
+    pub sort_order: Option<i32>,
+
+    /// Note this note replies to, which may be in the trash
+    #[schema(example = json!(null))]
+    pub parent_id: Option<i64>,
+
+    /// Number of active direct replies
+    #[schema(example = 2)]
+    pub reply_count: i64,
+
+    /// Characters in the content other than whitespace and Markdown markup
+    #[schema(example = 152)]
+    pub char_count: i32,
+
+    /// Words in the content, counting each Chinese or Japanese character as a word
+    #[schema(example = 28)]
+    pub word_count: i32,
+
+    /// Estimated time to read the content
+    #[schema(example = 8)]
+    pub reading_time_seconds: i32,
     
     #[schema(example = json!(["important", "chapter1"]))]
     pub tags: Vec<String>,
+
+    /// Outgoing `[[...]]` links in the content
+    pub links: Vec<NoteLinkResponse>,
+
+    pub attachments: Vec<AttachmentResponse>,
     
     #[schema(example = "2024-01-01T12:00:00Z")]
     pub created_at: Option<DateTime<Utc>>,
@@ -169,35 +505,140 @@ impl From<CreateNoteRequest> for NewReadingNote {
             book_id: req.book_id,
             title: req.title,
             content: req.content,
-            note_type: req.note_type.map(|nt| nt.into()),
+            note_type: req.note_type,
             page_reference: req.page_reference,
             is_favorite: req.is_favorite,
+            parent_id: req.parent_id,
+            ..Default::default()
         }
     }
 }
 
 impl ReadingNote {
     /// Creates a new reading note
-    pub fn create(conn: &mut PgConnection, new_note: NewReadingNote) -> Result<ReadingNote> {
+    /// 
+    /// The note type must be a defined note type. The page reference is
+    /// parsed into a structured location, which must not point beyond the
+    /// book's page count.
+    pub fn create(conn: &mut PgConnection, mut new_note: NewReadingNote) -> Result<ReadingNote> {
         use crate::db::schema::books;
         
         // Verify book exists and is not deleted
-        let book_exists = books::table
+        let page_count = books::table
             .filter(books::id.eq(new_note.book_id))
             .filter(books::deleted_at.is_null())
-            .select(books::id)
-            .first::<i64>(conn)
-            .optional()?;
-            
-        if book_exists.is_none() {
-            return Err(AppError::NotFound(format!("Book with id {} not found", new_note.book_id)));
+            .select(books::page_count)
+            .first::<Option<i32>>(conn)
+            .optional()?
+            .ok_or_else(|| AppError::NotFound(format!("Book with id {} not found", new_note.book_id)))?;
+
+        if let Some(ref note_type) = new_note.note_type {
+            validate_note_type(conn, note_type)?;
         }
+        if let Some(parent_id) = new_note.parent_id {
+            Self::validate_parent(conn, None, new_note.book_id, parent_id)?;
+        }
+
+        let location = new_note.page_reference.as_deref().map(NoteLocation::parse).unwrap_or_default();
+        location.validate(page_count)?;
+        new_note.page_start = location.page_start;
+        new_note.page_end = location.page_end;
+        new_note.chapter = location.chapter;
+        new_note.kindle_location = location.kindle_location;
+        new_note.percentage = location.percentage;
+
+        let stats = text_stats::measure(&markdown::plain_text(&new_note.content));
+        new_note.char_count = stats.char_count;
+        new_note.word_count = stats.word_count;
+        new_note.reading_time_seconds = stats.reading_time_seconds;
         
-        diesel::insert_into(reading_notes::table)
-            .values(&new_note)
-            .returning(ReadingNote::as_returning())
-            .get_result(conn)
-            .map_err(AppError::from)
+        conn.transaction(|conn| {
+            let note = diesel::insert_into(reading_notes::table)
+                .values(&new_note)
+                .returning(ReadingNote::as_returning())
+                .get_result(conn)?;
+
+            NoteLink::sync(conn, &note)?;
+            NoteLink::link_note(conn, &note)?;
+
+            Ok(note)
+        })
+    }
+
+    /// Checks that a note can reply to `parent_id`
+    ///
+    /// The parent must be an active note of the same book, and must not be
+    /// the note itself or one of its replies, including replies in the trash.
+    fn validate_parent(conn: &mut PgConnection, note_id: Option<i64>, book_id: i64, parent_id: i64) -> Result<()> {
+        let parent = Self::find_by_id(conn, parent_id)?;
+        if parent.book_id != book_id {
+            return Err(AppError::ValidationError(format!(
+                "Note {} belongs to another book and cannot be replied to",
+                parent_id
+            )));
+        }
+
+        let Some(note_id) = note_id else {
+            return Ok(());
+        };
+        let mut ancestor = Some(parent_id);
+        let mut seen = HashSet::new();
+        while let Some(id) = ancestor.filter(|id| seen.insert(*id)) {
+            if id == note_id {
+                return Err(AppError::ValidationError(format!(
+                    "Note {} cannot reply to itself or to one of its replies",
+                    note_id
+                )));
+            }
+            ancestor = reading_notes::table
+                .find(id)
+                .select(reading_notes::parent_id)
+                .first::<Option<i64>>(conn)
+                .optional()?
+                .flatten();
+        }
+        Ok(())
+    }
+
+    /// Returns the whole thread a note belongs to
+    ///
+    /// The thread starts at the note's topmost active ancestor. A reply
+    /// whose parent is in the trash starts a thread of its own until the
+    /// parent is restored.
+    pub fn thread(conn: &mut PgConnection, note_id: i64) -> Result<NoteThread> {
+        let mut root = Self::find_by_id(conn, note_id)?;
+        let mut seen = HashSet::from([root.id]);
+        while let Some(parent_id) = root.parent_id.filter(|id| seen.insert(*id)) {
+            match reading_notes::table
+                .find(parent_id)
+                .filter(reading_notes::deleted_at.is_null())
+                .select(ReadingNote::as_select())
+                .first(conn)
+                .optional()?
+            {
+                Some(parent) => root = parent,
+                None => break,
+            }
+        }
+
+        let mut replies: HashMap<i64, Vec<ReadingNote>> = HashMap::new();
+        let mut frontier = vec![root.id];
+        while !frontier.is_empty() {
+            let children = reading_notes::table
+                .filter(reading_notes::parent_id.eq_any(&frontier))
+                .filter(reading_notes::deleted_at.is_null())
+                .order(reading_notes::created_at.asc())
+                .select(ReadingNote::as_select())
+                .load::<ReadingNote>(conn)?;
+            frontier = children.iter().map(|c| c.id).collect();
+            for child in children {
+                if let Some(parent_id) = child.parent_id {
+                    replies.entry(parent_id).or_default().push(child);
+                }
+            }
+        }
+
+        Ok(NoteThread::assemble(root, &mut replies))
     }
 
     /// Finds a note by ID (excluding soft deleted)
@@ -209,150 +650,168 @@ impl ReadingNote {
             .map_err(|_| AppError::NotFound(format!("Note with id {} not found", note_id)))
     }
 
-    /// Finds all notes for a specific book
+    /// Finds all notes for a specific book, pinned notes first
+    /// 
+    /// In position order, notes without a parsed location come last.
     pub fn find_by_book_id(
         conn: &mut PgConnection,
         book_id: i64,
+        sort: NoteSort,
+        collapse_replies: bool,
         page: u32,
         per_page: u32,
     ) -> Result<(Vec<ReadingNote>, i64)> {
-        let offset = ((page.saturating_sub(1)) * per_page) as i64;
-        
-        let notes = reading_notes::table
-            .filter(reading_notes::book_id.eq(book_id))
-            .filter(reading_notes::deleted_at.is_null())
-            .order(reading_notes::created_at.desc())
-            .limit(per_page as i64)
-            .offset(offset)
-            .load::<ReadingNote>(conn)?;
+        let filter = NoteFilter { book_id: Some(book_id), collapse_replies, ..Default::default() };
+        Self::list_with_filters(conn, &filter, sort, page, per_page)
+    }
 
-        let total = reading_notes::table
-            .filter(reading_notes::book_id.eq(book_id))
-            .filter(reading_notes::deleted_at.is_null())
-            .count()
-            .get_result::<i64>(conn)?;
+    /// Arranges a book's notes in the given order
+    ///
+    /// Listed notes get consecutive positions; the book's other notes lose
+    /// theirs and follow in manual order, newest first. Returns all of the
+    /// book's notes in their new order.
+    pub fn reorder(conn: &mut PgConnection, book_id: i64, note_ids: &[i64]) -> Result<Vec<ReadingNote>> {
+        let mut seen = HashSet::new();
+        if let Some(duplicate) = note_ids.iter().find(|id| !seen.insert(**id)) {
+            return Err(AppError::ValidationError(format!("Note {} is listed more than once", duplicate)));
+        }
 
-        Ok((notes, total))
-    }
+        conn.transaction(|conn| {
+            let book_notes: HashSet<i64> = reading_notes::table
+                .filter(reading_notes::book_id.eq(book_id))
+                .filter(reading_notes::deleted_at.is_null())
+                .select(reading_notes::id)
+                .for_update()
+                .load::<i64>(conn)?
+                .into_iter()
+                .collect();
+            if let Some(foreign) = note_ids.iter().find(|id| !book_notes.contains(id)) {
+                return Err(AppError::ValidationError(format!(
+                    "Note {} is not a note of book {}",
+                    foreign, book_id
+                )));
+            }
 
-    /// Lists all notes with pagination
-    pub fn list_paginated(
-        conn: &mut PgConnection,
-        page: u32,
-        per_page: u32,
-    ) -> Result<(Vec<ReadingNote>, i64)> {
-        let offset = ((page.saturating_sub(1)) * per_page) as i64;
-        
-        let notes = reading_notes::table
-            .filter(reading_notes::deleted_at.is_null())
-            .order(reading_notes::created_at.desc())
-            .limit(per_page as i64)
-            .offset(offset)
-            .load::<ReadingNote>(conn)?;
+            // Only touch rows whose position changes, so that their updated_at stays put
+            diesel::update(
+                reading_notes::table
+                    .filter(reading_notes::book_id.eq(book_id))
+                    .filter(reading_notes::deleted_at.is_null())
+                    .filter(reading_notes::id.ne_all(note_ids))
+                    .filter(reading_notes::sort_order.is_not_null()),
+            )
+            .set(reading_notes::sort_order.eq(None::<i32>))
+            .execute(conn)?;
+            for (position, note_id) in note_ids.iter().enumerate() {
+                diesel::update(
+                    reading_notes::table
+                        .find(note_id)
+                        .filter(reading_notes::sort_order.is_distinct_from(position as i32)),
+                )
+                .set(reading_notes::sort_order.eq(position as i32))
+                .execute(conn)?;
+            }
 
-        let total = reading_notes::table
-            .filter(reading_notes::deleted_at.is_null())
-            .count()
-            .get_result::<i64>(conn)?;
+            Self::in_manual_order(conn, book_id)
+        })
+    }
 
-        Ok((notes, total))
+    /// Loads all of a book's active notes, pinned first, then in manual order
+    pub fn in_manual_order(conn: &mut PgConnection, book_id: i64) -> Result<Vec<ReadingNote>> {
+        let filter = NoteFilter { book_id: Some(book_id), ..Default::default() };
+        ordered(Self::filtered(&filter), NoteSort::Manual, true)
+            .select(ReadingNote::as_select())
+            .load::<ReadingNote>(conn)
+            .map_err(AppError::from)
     }
 
-    /// Searches notes by title or content
-    pub fn search(
+    /// Lists notes matching all set filters in the given order
+    pub fn list_with_filters(
         conn: &mut PgConnection,
-        query: &str,
+        filter: &NoteFilter,
+        sort: NoteSort,
         page: u32,
         per_page: u32,
     ) -> Result<(Vec<ReadingNote>, i64)> {
-        let search_pattern = format!("%{}%", query);
+        filter.validate()?;
         let offset = ((page.saturating_sub(1)) * per_page) as i64;
-        
-        let notes = reading_notes::table
-            .filter(reading_notes::deleted_at.is_null())
-            .filter(
-                reading_notes::title.ilike(&search_pattern)
-                    .or(reading_notes::content.ilike(&search_pattern))
-            )
-            .order(reading_notes::created_at.desc())
+
+        let notes = ordered(Self::filtered(filter), sort, filter.book_id.is_some())
+            .select(ReadingNote::as_select())
             .limit(per_page as i64)
             .offset(offset)
             .load::<ReadingNote>(conn)?;
 
-        let total = reading_notes::table
-            .filter(reading_notes::deleted_at.is_null())
-            .filter(
-                reading_notes::title.ilike(&search_pattern)
-                    .or(reading_notes::content.ilike(&search_pattern))
-            )
-            .count()
-            .get_result::<i64>(conn)?;
+        let total = Self::filtered(filter).count().get_result::<i64>(conn)?;
 
         Ok((notes, total))
     }
 
-    /// Lists notes with optional search and note type filter
-    pub fn list_with_filters(
-        conn: &mut PgConnection,
-        search_query: Option<&str>,
-        note_type_filter: Option<&str>,
-        page: u32,
-        per_page: u32,
-    ) -> Result<(Vec<ReadingNote>, i64)> {
-        let offset = ((page.saturating_sub(1)) * per_page) as i64;
-        
-        // Prepare search pattern if needed
-        let search_pattern = search_query
-            .filter(|s| !s.trim().is_empty())
-            .map(|s| format!("%{}%", s.trim()));
-        
-        // Prepare note type filter if needed
-        let note_type = note_type_filter
-            .filter(|s| !s.trim().is_empty())
-            .map(|s| s.trim().to_string());
-        
+    /// Builds the query for active notes matching the filter
+    fn filtered(filter: &NoteFilter) -> NoteQuery<'static> {
         let mut query = reading_notes::table
+            .inner_join(books::table)
             .filter(reading_notes::deleted_at.is_null())
             .into_boxed();
 
-        // Apply search filter if provided
-        if let Some(ref pattern) = search_pattern {
+        if let Some(pattern) = filter
+            .search
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|s| format!("%{}%", s))
+        {
             query = query.filter(
-                reading_notes::title.ilike(pattern)
+                reading_notes::title.ilike(pattern.clone())
                     .or(reading_notes::content.ilike(pattern))
             );
         }
-
-        // Apply note type filter if provided
-        if let Some(ref note_type_val) = note_type {
-            query = query.filter(reading_notes::note_type.eq(note_type_val));
+        if let Some(note_type) = filter.note_type.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+            query = query.filter(reading_notes::note_type.eq(note_type.to_string()));
         }
-
-        let notes = query
-            .order(reading_notes::created_at.desc())
-            .limit(per_page as i64)
-            .offset(offset)
-            .load::<ReadingNote>(conn)?;
-
-        // Get total count with the same filters
-        let mut count_query = reading_notes::table
-            .filter(reading_notes::deleted_at.is_null())
-            .into_boxed();
-
-        if let Some(ref pattern) = search_pattern {
-            count_query = count_query.filter(
-                reading_notes::title.ilike(pattern)
-                    .or(reading_notes::content.ilike(pattern))
-            );
+        if let Some(book_id) = filter.book_id {
+            query = query.filter(reading_notes::book_id.eq(book_id));
         }
-
-        if let Some(ref note_type_val) = note_type {
-            count_query = count_query.filter(reading_notes::note_type.eq(note_type_val));
+        for tag in &filter.tags {
+            let tagged = note_tags::table
+                .inner_join(tags::table)
+                .filter(tags::slug.eq(slugify(tag)))
+                .filter(tags::deleted_at.is_null())
+                .filter(note_tags::deleted_at.is_null())
+                .select(note_tags::note_id);
+            query = query.filter(reading_notes::id.eq_any(tagged));
+        }
+        // Notes never marked as favorites have no value and count as not favorite
+        match filter.is_favorite {
+            Some(true) => query = query.filter(reading_notes::is_favorite.eq(true)),
+            Some(false) => query = query.filter(reading_notes::is_favorite.is_distinct_from(true)),
+            None => {}
+        }
+        if let Some(from) = filter.created_from {
+            query = query.filter(reading_notes::created_at.ge(day_start(from)));
+        }
+        if let Some(to) = filter.created_to {
+            query = query.filter(reading_notes::created_at.lt(day_end(to)));
+        }
+        if let Some(from) = filter.updated_from {
+            query = query.filter(reading_notes::updated_at.ge(day_start(from)));
+        }
+        if let Some(to) = filter.updated_to {
+            query = query.filter(reading_notes::updated_at.lt(day_end(to)));
+        }
+        if filter.collapse_replies {
+            // Replies whose parent is in the trash are listed as top-level notes
+            let parents = diesel::alias!(reading_notes as parents);
+            let active = parents
+                .filter(parents.field(reading_notes::deleted_at).is_null())
+                .select(parents.field(reading_notes::id));
+            query = query.filter(
+                reading_notes::parent_id.is_null()
+                    .or(diesel::dsl::not(reading_notes::parent_id.assume_not_null().eq_any(active)))
+            );
         }
 
-        let total = count_query.count().get_result::<i64>(conn)?;
-
-        Ok((notes, total))
+        query
     }
 
     /// Updates a note
@@ -361,37 +820,193 @@ impl ReadingNote {
         note_id: i64,
         update_data: UpdateReadingNote,
     ) -> Result<ReadingNote> {
-        diesel::update(reading_notes::table.find(note_id))
-            .filter(reading_notes::deleted_at.is_null())
-            .set((
-                &update_data,
-                reading_notes::updated_at.eq(Some(Utc::now())),
-            ))
-            .returning(ReadingNote::as_returning())
-            .get_result(conn)
-            .map_err(|e| match e {
-                diesel::result::Error::NotFound => {
-                    AppError::NotFound(format!("Note with id {} not found", note_id))
-                }
-                _ => AppError::from(e),
-            })
+        Self::patch(conn, note_id, update_data.into())
+    }
+
+    /// Applies changes to a note, clearing nullable columns set to `Some(None)`
+    ///
+    /// When the title, content, type or page reference change, the
+    /// previous version is kept as a revision. Links are re-parsed when
+    /// the content changes.
+    pub fn patch(
+        conn: &mut PgConnection,
+        note_id: i64,
+        mut changes: NoteChangeset,
+    ) -> Result<ReadingNote> {
+        conn.transaction(|conn| {
+            let current: ReadingNote = reading_notes::table
+                .find(note_id)
+                .filter(reading_notes::deleted_at.is_null())
+                .select(ReadingNote::as_select())
+                .for_update()
+                .first(conn)
+                .map_err(|e| match e {
+                    diesel::result::Error::NotFound => {
+                        AppError::NotFound(format!("Note with id {} not found", note_id))
+                    }
+                    _ => AppError::from(e),
+                })?;
+
+            if let Some(Some(note_type)) = &changes.note_type {
+                validate_note_type(conn, note_type)?;
+            }
+            if let Some(Some(parent_id)) = changes.parent_id {
+                Self::validate_parent(conn, Some(note_id), current.book_id, parent_id)?;
+            }
+
+            if let Some(page_reference) = &changes.page_reference {
+                use crate::db::schema::books;
+
+                let location = page_reference.as_deref().map(NoteLocation::parse).unwrap_or_default();
+                let page_count = books::table
+                    .find(current.book_id)
+                    .select(books::page_count)
+                    .first::<Option<i32>>(conn)?;
+                location.validate(page_count)?;
+                changes.set_location(location);
+            }
+
+            if let Some(content) = &changes.content {
+                changes.set_stats(text_stats::measure(&markdown::plain_text(content)));
+            }
+
+            if changes.revises(&current) {
+                NoteRevision::record(conn, &current)?;
+            }
+
+            let note: ReadingNote = diesel::update(reading_notes::table.find(note_id))
+                .set((
+                    &changes,
+                    reading_notes::updated_at.eq(Some(Utc::now())),
+                ))
+                .returning(ReadingNote::as_returning())
+                .get_result(conn)?;
+
+            if note.content != current.content {
+                NoteLink::sync(conn, &note)?;
+            }
+
+            Ok(note)
+        })
     }
 
     /// Soft deletes a note
+    /// 
+    /// Tag associations are soft deleted in the same batch so that
+    /// restoring the note brings its tags back.
     pub fn soft_delete(conn: &mut PgConnection, note_id: i64) -> Result<()> {
-        let affected = diesel::update(reading_notes::table.find(note_id))
-            .filter(reading_notes::deleted_at.is_null())
-            .set(reading_notes::deleted_at.eq(Some(Utc::now())))
-            .execute(conn)?;
+        conn.transaction(|conn| {
+            let batch_id = Uuid::now_v7();
+            let now = Utc::now();
+
+            let affected = diesel::update(reading_notes::table.find(note_id))
+                .filter(reading_notes::deleted_at.is_null())
+                .set((
+                    reading_notes::deleted_at.eq(Some(now)),
+                    reading_notes::deletion_batch_id.eq(Some(batch_id)),
+                ))
+                .execute(conn)?;
+
+            if affected == 0 {
+                return Err(AppError::NotFound(format!("Note with id {} not found", note_id)));
+            }
+
+            let tag_ids = diesel::update(note_tags::table)
+                .filter(note_tags::note_id.eq(note_id))
+                .filter(note_tags::deleted_at.is_null())
+                .set((note_tags::deleted_at.eq(Some(now)), note_tags::deletion_batch_id.eq(Some(batch_id))))
+                .returning(note_tags::tag_id)
+                .get_results::<i64>(conn)?;
+            Tag::refresh_usage_counts(conn, &tag_ids)?;
+
+            Ok(())
+        })
+    }
+
+    /// Lists soft deleted notes, most recently deleted first
+    pub fn list_deleted(conn: &mut PgConnection) -> Result<Vec<ReadingNote>> {
+        reading_notes::table
+            .filter(reading_notes::deleted_at.is_not_null())
+            .order(reading_notes::deleted_at.desc())
+            .load::<ReadingNote>(conn)
+            .map_err(AppError::from)
+    }
 
-        if affected == 0 {
-            return Err(AppError::NotFound(format!("Note with id {} not found", note_id)));
+    /// Restores a soft deleted note
+    /// 
+    /// The note's book must not be in the trash, otherwise the book
+    /// has to be restored first.
+    pub fn restore(conn: &mut PgConnection, note_id: i64) -> Result<ReadingNote> {
+        use crate::db::schema::books;
+
+        let note = reading_notes::table
+            .filter(reading_notes::id.eq(note_id))
+            .filter(reading_notes::deleted_at.is_not_null())
+            .first::<ReadingNote>(conn)
+            .optional()?
+            .ok_or_else(|| AppError::NotFound(format!("Deleted note with id {} not found", note_id)))?;
+
+        let book_active = books::table
+            .filter(books::id.eq(note.book_id))
+            .filter(books::deleted_at.is_null())
+            .select(books::id)
+            .first::<i64>(conn)
+            .optional()?;
+
+        if book_active.is_none() {
+            return Err(AppError::Conflict(format!(
+                "Cannot restore note {}: book {} is deleted, restore the book first",
+                note_id, note.book_id
+            )));
         }
 
-        // Also remove tag associations
-        diesel::delete(note_tags::table.filter(note_tags::note_id.eq(note_id)))
+        conn.transaction(|conn| {
+            let restored = diesel::update(reading_notes::table.find(note_id))
+                .set((
+                    reading_notes::deleted_at.eq(None::<DateTime<Utc>>),
+                    reading_notes::deletion_batch_id.eq(None::<Uuid>),
+                ))
+                .returning(ReadingNote::as_returning())
+                .get_result(conn)?;
+
+            if let Some(batch_id) = note.deletion_batch_id {
+                let tag_ids = diesel::update(note_tags::table)
+                    .filter(note_tags::note_id.eq(note_id))
+                    .filter(note_tags::deletion_batch_id.eq(batch_id))
+                    .set((
+                        note_tags::deleted_at.eq(None::<DateTime<Utc>>),
+                        note_tags::deletion_batch_id.eq(None::<Uuid>),
+                    ))
+                    .returning(note_tags::tag_id)
+                    .get_results::<i64>(conn)?;
+                Tag::refresh_usage_counts(conn, &tag_ids)?;
+            }
+
+            Ok(restored)
+        })
+    }
+
+    /// Permanently deletes a soft deleted note
+    pub fn purge(conn: &mut PgConnection, note_id: i64) -> Result<()> {
+        let storage_keys = conn.transaction(|conn| {
+            let storage_keys = NoteAttachment::storage_keys(conn, &[note_id])?;
+            let affected = diesel::delete(
+                reading_notes::table
+                    .filter(reading_notes::id.eq(note_id))
+                    .filter(reading_notes::deleted_at.is_not_null()),
+            )
             .execute(conn)?;
 
+            if affected == 0 {
+                return Err(AppError::NotFound(format!("Deleted note with id {} not found", note_id)));
+            }
+
+            Ok(storage_keys)
+        })?;
+
+        // Attachment rows go with the note; their files are removed once it is gone
+        attachment::remove_files(&storage_keys);
+
         Ok(())
     }
 
@@ -402,6 +1017,7 @@ impl ReadingNote {
         let tag_names = note_tags::table
             .inner_join(tags::table)
             .filter(note_tags::note_id.eq(self.id))
+            .filter(note_tags::deleted_at.is_null())
             .filter(tags::deleted_at.is_null())
             .select(tags::name)
             .load::<String>(conn)?;
@@ -411,8 +1027,6 @@ impl ReadingNote {
 
     /// Associates tags with this note
     pub fn set_tags(&self, conn: &mut PgConnection, tag_names: Vec<String>) -> Result<()> {
-        use crate::models::tag::Tag;
-        
         // Start a transaction
         conn.transaction(|conn| {
             // Remove existing associations
@@ -445,8 +1059,19 @@ impl ReadingNote {
     }
 }
 
-/// Converts ReadingNote to NoteResponse with tags
 impl ReadingNote {
+    /// Structured location parsed from the page reference
+    pub fn location(&self) -> NoteLocation {
+        NoteLocation {
+            page_start: self.page_start,
+            page_end: self.page_end,
+            chapter: self.chapter,
+            kindle_location: self.kindle_location,
+            percentage: self.percentage,
+        }
+    }
+
+    /// Converts ReadingNote to NoteResponse with tags
     pub fn to_response(&self, conn: &mut PgConnection) -> Result<NoteResponse> {
         let tags = self.get_tags(conn).unwrap_or_default();
         
@@ -455,28 +1080,40 @@ impl ReadingNote {
             book_id: self.book_id,
             title: self.title.clone(),
             content: self.content.clone(),
-            note_type: self.note_type.as_ref().map(|t| NoteType::from(t.clone())),
+            content_html: None,
+            excerpt: markdown::render(&self.content).excerpt.clone(),
+            note_type: self.note_type.clone(),
             page_reference: self.page_reference.clone(),
+            location: Some(self.location()).filter(|location| !location.is_empty()),
             is_favorite: self.is_favorite.unwrap_or(false),
+            is_pinned: self.is_pinned,
+            sort_order: self.sort_order,
+            parent_id: self.parent_id,
+            reply_count: reading_notes::table
+                .filter(reading_notes::parent_id.eq(self.id))
+                .filter(reading_notes::deleted_at.is_null())
+                .count()
+                .get_result(conn)?,
+            char_count: self.char_count,
+            word_count: self.word_count,
+            reading_time_seconds: self.reading_time_seconds,
             tags,
+            links: NoteLink::outgoing(conn, self.id)?,
+            attachments: NoteAttachment::list_for_note(conn, self.id)?
+                .into_iter()
+                .map(AttachmentResponse::from)
+                .collect(),
             created_at: self.created_at,
             updated_at: self.updated_at,
         })
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
 
-    #[test]
-    fn test_note_type_conversion() {
-        assert_eq!(String::from(NoteType::Quote), "quote");
-        assert_eq!(String::from(NoteType::Summary), "summary");
-        assert_eq!(String::from(NoteType::Thought), "thought");
-        assert_eq!(String::from(NoteType::General), "general");
-        
-        assert_eq!(NoteType::from("quote".to_string()), NoteType::Quote);
-        assert_eq!(NoteType::from("invalid".to_string()), NoteType::General);
+    /// Converts to a response, optionally with the content rendered as HTML
+    pub fn to_rendered_response(&self, conn: &mut PgConnection, render_html: bool) -> Result<NoteResponse> {
+        let mut response = self.to_response(conn)?;
+        if render_html {
+            response.content_html = Some(markdown::render(&self.content).html.clone());
+        }
+        Ok(response)
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,292 @@
+use std::collections::HashSet;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use crate::db::schema::{books, note_tags, reading_notes};
+use crate::errors::{AppError, ErrorResponse, Result};
+use crate::models::book::Book;
+use crate::models::note::{CreateNoteRequest, NoteChangeset, ReadingNote};
+use crate::models::note_location::NoteLocation;
+use crate::models::note_type::validate_note_type;
+use crate::models::tag::{slugify, Tag};
+
+/// Largest number of notes a single bulk request may touch
+pub const MAX_BULK_ITEMS: usize = 500;
+
+/// Bulk operation over many notes, selected by `action`
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum BulkNoteRequest {
+    /// Creates notes, with their tags
+    Create { notes: Vec<CreateNoteRequest> },
+    /// Moves notes to the trash
+    Delete { note_ids: Vec<i64> },
+    SetFavorite { note_ids: Vec<i64>, is_favorite: bool },
+    ChangeType { note_ids: Vec<i64>, note_type: String },
+    /// Moves notes to another book; replies whose parent ends up in another book are detached
+    MoveToBook { note_ids: Vec<i64>, book_id: i64 },
+    AddTags { note_ids: Vec<i64>, tags: Vec<String> },
+    RemoveTags { note_ids: Vec<i64>, tags: Vec<String> },
+}
+
+/// Outcome of one item of a bulk operation
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BulkNoteResult {
+    /// Position of the item in the request
+    #[schema(example = 0)]
+    pub index: usize,
+
+    /// The note the item applied to or created, absent when it failed
+    #[schema(example = 1)]
+    pub note_id: Option<i64>,
+
+    /// Why the item failed, absent on success
+    pub error: Option<ErrorResponse>,
+}
+
+/// Results of a bulk operation, in request order
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BulkNoteResponse {
+    #[schema(example = 2)]
+    pub succeeded: usize,
+
+    #[schema(example = 0)]
+    pub failed: usize,
+
+    pub results: Vec<BulkNoteResult>,
+}
+
+impl BulkNoteRequest {
+    /// Number of items in the request
+    fn len(&self) -> usize {
+        match self {
+            BulkNoteRequest::Create { notes } => notes.len(),
+            BulkNoteRequest::Delete { note_ids }
+            | BulkNoteRequest::SetFavorite { note_ids, .. }
+            | BulkNoteRequest::ChangeType { note_ids, .. }
+            | BulkNoteRequest::MoveToBook { note_ids, .. }
+            | BulkNoteRequest::AddTags { note_ids, .. }
+            | BulkNoteRequest::RemoveTags { note_ids, .. } => note_ids.len(),
+        }
+    }
+
+    /// Checks the parts of the request shared by all items
+    fn validate(&self, conn: &mut PgConnection) -> Result<()> {
+        if self.len() == 0 {
+            return Err(AppError::ValidationError("At least one item is required".to_string()));
+        }
+        if self.len() > MAX_BULK_ITEMS {
+            return Err(AppError::ValidationError(format!(
+                "At most {} items can be processed at once",
+                MAX_BULK_ITEMS
+            )));
+        }
+
+        match self {
+            BulkNoteRequest::ChangeType { note_type, .. } => validate_note_type(conn, note_type),
+            BulkNoteRequest::MoveToBook { book_id, .. } => Book::find_by_id(conn, *book_id).map(|_| ()),
+            BulkNoteRequest::AddTags { tags, .. } | BulkNoteRequest::RemoveTags { tags, .. }
+                if tags.iter().all(|t| t.trim().is_empty()) =>
+            {
+                Err(AppError::ValidationError("At least one tag is required".to_string()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Runs the operation in one transaction
+    ///
+    /// Each item runs in its own savepoint, so a failing item is reported
+    /// and rolled back while the other items are still applied.
+    pub fn execute(self, conn: &mut PgConnection) -> Result<BulkNoteResponse> {
+        self.validate(conn)?;
+
+        conn.transaction(|conn| {
+            let results = match self {
+                BulkNoteRequest::Create { notes } => each(conn, notes, |conn, request| {
+                    create_note(conn, request).map(|note| note.id)
+                }),
+                BulkNoteRequest::Delete { note_ids } => each(conn, note_ids, |conn, note_id| {
+                    ReadingNote::soft_delete(conn, note_id).map(|_| note_id)
+                }),
+                BulkNoteRequest::SetFavorite { note_ids, is_favorite } => each(conn, note_ids, |conn, note_id| {
+                    let changes = NoteChangeset { is_favorite: Some(Some(is_favorite)), ..Default::default() };
+                    ReadingNote::patch(conn, note_id, changes).map(|note| note.id)
+                }),
+                BulkNoteRequest::ChangeType { note_ids, note_type } => each(conn, note_ids, |conn, note_id| {
+                    let changes = NoteChangeset { note_type: Some(Some(note_type.clone())), ..Default::default() };
+                    ReadingNote::patch(conn, note_id, changes).map(|note| note.id)
+                }),
+                BulkNoteRequest::MoveToBook { note_ids, book_id } => {
+                    let results = each(conn, note_ids, |conn, note_id| move_note(conn, note_id, book_id))?;
+                    let moved: Vec<i64> = results.iter().filter(|r| r.error.is_none()).filter_map(|r| r.note_id).collect();
+                    detach_cross_book_replies(conn, &moved)?;
+                    Ok(results)
+                }
+                BulkNoteRequest::AddTags { note_ids, tags } => {
+                    let mut tag_ids = Vec::new();
+                    for name in tags.into_iter().filter(|t| !t.trim().is_empty()) {
+                        tag_ids.push(Tag::find_or_create(conn, name)?.id);
+                    }
+                    each(conn, note_ids, |conn, note_id| add_tags(conn, note_id, &tag_ids).map(|_| note_id))
+                }
+                BulkNoteRequest::RemoveTags { note_ids, tags } => {
+                    let mut tag_ids = Vec::new();
+                    for name in &tags {
+                        if let Some(tag) = Tag::find_by_slug(conn, &slugify(name))? {
+                            tag_ids.push(tag.id);
+                        }
+                    }
+                    each(conn, note_ids, |conn, note_id| remove_tags(conn, note_id, &tag_ids).map(|_| note_id))
+                }
+            }?;
+
+            let failed = results.iter().filter(|r| r.error.is_some()).count();
+            Ok(BulkNoteResponse { succeeded: results.len() - failed, failed, results })
+        })
+    }
+}
+
+/// Applies `op` to each item in a savepoint, collecting per-item results
+fn each<T>(
+    conn: &mut PgConnection,
+    items: Vec<T>,
+    mut op: impl FnMut(&mut PgConnection, T) -> Result<i64>,
+) -> Result<Vec<BulkNoteResult>> {
+    let mut results = Vec::with_capacity(items.len());
+    for (index, item) in items.into_iter().enumerate() {
+        let result = match conn.transaction(|conn| op(conn, item)) {
+            Ok(note_id) => BulkNoteResult { index, note_id: Some(note_id), error: None },
+            Err(error) => BulkNoteResult { index, note_id: None, error: Some(ErrorResponse::from(&error)) },
+        };
+        results.push(result);
+    }
+    Ok(results)
+}
+
+/// Creates a note with its tags
+fn create_note(conn: &mut PgConnection, request: CreateNoteRequest) -> Result<ReadingNote> {
+    if request.content.trim().is_empty() {
+        return Err(AppError::ValidationError("Content is required".to_string()));
+    }
+
+    let tags = request.tags.clone();
+    let note = ReadingNote::create(conn, request.into())?;
+    if let Some(tag_names) = tags {
+        note.set_tags(conn, tag_names)?;
+    }
+    Ok(note)
+}
+
+/// Moves a note to another book
+///
+/// The note leaves the old book's manual order, and its page reference
+/// must fit within the new book.
+fn move_note(conn: &mut PgConnection, note_id: i64, book_id: i64) -> Result<i64> {
+    let note = ReadingNote::find_by_id(conn, note_id)?;
+    if note.book_id == book_id {
+        return Ok(note_id);
+    }
+
+    let page_count = books::table
+        .find(book_id)
+        .select(books::page_count)
+        .first::<Option<i32>>(conn)?;
+    note.page_reference
+        .as_deref()
+        .map(NoteLocation::parse)
+        .unwrap_or_default()
+        .validate(page_count)?;
+
+    diesel::update(reading_notes::table.find(note_id))
+        .set((
+            reading_notes::book_id.eq(book_id),
+            reading_notes::sort_order.eq(None::<i32>),
+        ))
+        .execute(conn)?;
+    Ok(note_id)
+}
+
+/// Detaches moved notes from parents left in another book, and replies
+/// left behind from moved parents
+fn detach_cross_book_replies(conn: &mut PgConnection, moved: &[i64]) -> Result<()> {
+    if moved.is_empty() {
+        return Ok(());
+    }
+
+    let parents = diesel::alias!(reading_notes as parents);
+    let detached: Vec<i64> = reading_notes::table
+        .inner_join(parents.on(reading_notes::parent_id.eq(parents.field(reading_notes::id).nullable())))
+        .filter(reading_notes::id.eq_any(moved).or(parents.field(reading_notes::id).eq_any(moved)))
+        .filter(reading_notes::book_id.ne(parents.field(reading_notes::book_id)))
+        .select(reading_notes::id)
+        .load(conn)?;
+
+    diesel::update(reading_notes::table.filter(reading_notes::id.eq_any(&detached)))
+        .set(reading_notes::parent_id.eq(None::<i64>))
+        .execute(conn)?;
+    Ok(())
+}
+
+/// Adds tags to a note, keeping the tags it already has
+fn add_tags(conn: &mut PgConnection, note_id: i64, tag_ids: &[i64]) -> Result<()> {
+    ReadingNote::find_by_id(conn, note_id)?;
+
+    let current: HashSet<i64> = note_tags::table
+        .filter(note_tags::note_id.eq(note_id))
+        .filter(note_tags::deleted_at.is_null())
+        .select(note_tags::tag_id)
+        .load::<i64>(conn)?
+        .into_iter()
+        .collect();
+    let new_associations: Vec<_> = tag_ids
+        .iter()
+        .filter(|id| !current.contains(id))
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .map(|&tag_id| (note_tags::note_id.eq(note_id), note_tags::tag_id.eq(tag_id)))
+        .collect();
+
+    // Drop trashed associations that would otherwise block the insert
+    diesel::delete(
+        note_tags::table
+            .filter(note_tags::note_id.eq(note_id))
+            .filter(note_tags::tag_id.eq_any(tag_ids))
+            .filter(note_tags::deleted_at.is_not_null()),
+    )
+    .execute(conn)?;
+    diesel::insert_into(note_tags::table)
+        .values(&new_associations)
+        .execute(conn)?;
+    Ok(())
+}
+
+/// Removes tags from a note
+fn remove_tags(conn: &mut PgConnection, note_id: i64, tag_ids: &[i64]) -> Result<()> {
+    ReadingNote::find_by_id(conn, note_id)?;
+
+    diesel::delete(
+        note_tags::table
+            .filter(note_tags::note_id.eq(note_id))
+            .filter(note_tags::tag_id.eq_any(tag_ids)),
+    )
+    .execute(conn)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_is_tagged_by_action() {
+        let request: BulkNoteRequest =
+            serde_json::from_value(serde_json::json!({ "action": "set_favorite", "note_ids": [1, 2], "is_favorite": true }))
+                .unwrap();
+        assert!(matches!(request, BulkNoteRequest::SetFavorite { is_favorite: true, .. }));
+        assert_eq!(request.len(), 2);
+
+        let unknown = serde_json::from_value::<BulkNoteRequest>(serde_json::json!({ "action": "archive", "note_ids": [1] }));
+        assert!(unknown.is_err());
+    }
+}
@@ -0,0 +1,284 @@
+use std::collections::HashMap;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use pulldown_cmark::{Event, Parser, Tag, TagEnd};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use crate::db::schema::{books, note_links, reading_notes};
+use crate::errors::{AppError, Result};
+use crate::models::book::Book;
+use crate::models::note::ReadingNote;
+
+/// Prefix of links that point at a note by id, as in `[[note:123]]`
+const NOTE_LINK_PREFIX: &str = "note:";
+/// Longest link text that is recognized, matching the book title limit
+const MAX_LINK_TEXT_LENGTH: usize = 200;
+
+diesel::define_sql_function!(fn lower(x: diesel::sql_types::Text) -> diesel::sql_types::Text);
+
+/// Wiki-style link from a note to another note or a book
+#[derive(Debug, Serialize, Deserialize, Queryable, Selectable, Identifiable)]
+#[diesel(table_name = note_links)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct NoteLink {
+    pub id: i64,
+    pub source_note_id: i64,
+    pub link_text: String,
+    pub target_note_id: Option<i64>,
+    pub target_book_id: Option<i64>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// New note link for insertion
+#[derive(Debug, Insertable)]
+#[diesel(table_name = note_links)]
+pub struct NewNoteLink {
+    pub source_note_id: i64,
+    pub link_text: String,
+    pub target_note_id: Option<i64>,
+    pub target_book_id: Option<i64>,
+}
+
+/// Kind of record a link points at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkTargetType {
+    Note,
+    Book,
+}
+
+/// Outgoing link of a note
+#[derive(Debug, Serialize, ToSchema)]
+pub struct NoteLinkResponse {
+    /// Text between the brackets
+    #[schema(example = "note:42")]
+    pub text: String,
+
+    #[schema(example = "note")]
+    pub target_type: LinkTargetType,
+
+    /// Resolved note or book id, absent when nothing matched
+    #[schema(example = 42)]
+    pub target_id: Option<i64>,
+
+    #[schema(example = "Chapter 3 Summary")]
+    pub target_title: Option<String>,
+
+    /// Whether the target does not exist or is in the trash
+    #[schema(example = false)]
+    pub broken: bool,
+}
+
+/// Extracts the distinct link texts of `[[...]]` links in Markdown content
+///
+/// Links inside code spans and code blocks are ignored.
+pub fn parse_links(content: &str) -> Vec<String> {
+    let mut links = Vec::new();
+    let mut text = String::new();
+
+    let mut in_code_block = false;
+
+    // Brackets split text into several events, so scan runs of adjacent text
+    for event in Parser::new(content) {
+        match event {
+            Event::Text(t) if !in_code_block => text.push_str(&t),
+            Event::Start(Tag::CodeBlock(_)) => in_code_block = true,
+            Event::End(TagEnd::CodeBlock) => in_code_block = false,
+            _ => {
+                collect_links(&text, &mut links);
+                text.clear();
+            }
+        }
+    }
+    collect_links(&text, &mut links);
+
+    links
+}
+
+fn collect_links(text: &str, links: &mut Vec<String>) {
+    let mut rest = text;
+    while let Some(start) = rest.find("[[") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("]]") else {
+            break;
+        };
+        let inner = after[..end].trim();
+        if !inner.is_empty() && inner.chars().count() <= MAX_LINK_TEXT_LENGTH && !links.iter().any(|l| l == inner) {
+            links.push(inner.to_string());
+        }
+        rest = &after[end + 2..];
+    }
+}
+
+/// Target type of a link text
+fn target_type(text: &str) -> LinkTargetType {
+    if text.starts_with(NOTE_LINK_PREFIX) {
+        LinkTargetType::Note
+    } else {
+        LinkTargetType::Book
+    }
+}
+
+impl NoteLink {
+    /// Replaces the outgoing links of a note with those in its content
+    ///
+    /// `[[note:123]]` resolves to the note with that id and any other text
+    /// to the book with that title (case-insensitive), preferring books
+    /// that are not in the trash. Unmatched links are kept as broken.
+    pub fn sync(conn: &mut PgConnection, note: &ReadingNote) -> Result<()> {
+        diesel::delete(note_links::table.filter(note_links::source_note_id.eq(note.id)))
+            .execute(conn)?;
+
+        let mut new_links = Vec::new();
+        for text in parse_links(&note.content) {
+            let (target_note_id, target_book_id) = match text.strip_prefix(NOTE_LINK_PREFIX) {
+                Some(id) => {
+                    let target = match id.trim().parse::<i64>() {
+                        Ok(id) => reading_notes::table
+                            .find(id)
+                            .select(reading_notes::id)
+                            .first::<i64>(conn)
+                            .optional()?,
+                        Err(_) => None,
+                    };
+                    (target, None)
+                }
+                None => {
+                    let target = books::table
+                        .filter(lower(books::title).eq(lower(&text)))
+                        .order((books::deleted_at.is_not_null(), books::id))
+                        .select(books::id)
+                        .first::<i64>(conn)
+                        .optional()?;
+                    (None, target)
+                }
+            };
+
+            new_links.push(NewNoteLink {
+                source_note_id: note.id,
+                link_text: text,
+                target_note_id,
+                target_book_id,
+            });
+        }
+
+        diesel::insert_into(note_links::table)
+            .values(&new_links)
+            .execute(conn)?;
+
+        Ok(())
+    }
+
+    /// Resolves pending links to a book that was just created, renamed or restored
+    ///
+    /// Links to its title that matched nothing, or only books in the trash,
+    /// now point at it.
+    pub fn link_book(conn: &mut PgConnection, book: &Book) -> Result<()> {
+        let trashed_books = books::table
+            .filter(books::deleted_at.is_not_null())
+            .select(books::id.nullable());
+
+        diesel::update(note_links::table)
+            .filter(lower(note_links::link_text).eq(lower(&book.title)))
+            .filter(note_links::link_text.not_like(format!("{}%", NOTE_LINK_PREFIX)))
+            .filter(note_links::target_book_id.is_null().or(note_links::target_book_id.eq_any(trashed_books)))
+            .set(note_links::target_book_id.eq(book.id))
+            .execute(conn)?;
+
+        Ok(())
+    }
+
+    /// Resolves pending `[[note:id]]` links to a note that was just created
+    pub fn link_note(conn: &mut PgConnection, note: &ReadingNote) -> Result<()> {
+        diesel::update(note_links::table)
+            .filter(note_links::link_text.eq(format!("{}{}", NOTE_LINK_PREFIX, note.id)))
+            .filter(note_links::target_note_id.is_null())
+            .set(note_links::target_note_id.eq(note.id))
+            .execute(conn)?;
+
+        Ok(())
+    }
+
+    /// Lists the outgoing links of a note with their resolved targets
+    pub fn outgoing(conn: &mut PgConnection, note_id: i64) -> Result<Vec<NoteLinkResponse>> {
+        let links = note_links::table
+            .filter(note_links::source_note_id.eq(note_id))
+            .order(note_links::id)
+            .select(NoteLink::as_select())
+            .load(conn)?;
+        if links.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let note_ids: Vec<i64> = links.iter().filter_map(|l| l.target_note_id).collect();
+        let notes: HashMap<i64, (Option<String>, bool)> = reading_notes::table
+            .filter(reading_notes::id.eq_any(&note_ids))
+            .select((reading_notes::id, reading_notes::title, reading_notes::deleted_at))
+            .load::<(i64, Option<String>, Option<DateTime<Utc>>)>(conn)?
+            .into_iter()
+            .map(|(id, title, deleted_at)| (id, (title, deleted_at.is_some())))
+            .collect();
+
+        let book_ids: Vec<i64> = links.iter().filter_map(|l| l.target_book_id).collect();
+        let books: HashMap<i64, (Option<String>, bool)> = books::table
+            .filter(books::id.eq_any(&book_ids))
+            .select((books::id, books::title, books::deleted_at))
+            .load::<(i64, String, Option<DateTime<Utc>>)>(conn)?
+            .into_iter()
+            .map(|(id, title, deleted_at)| (id, (Some(title), deleted_at.is_some())))
+            .collect();
+
+        Ok(links
+            .into_iter()
+            .map(|link| {
+                let target_type = target_type(&link.link_text);
+                let (target_id, target) = match target_type {
+                    LinkTargetType::Note => (link.target_note_id, link.target_note_id.and_then(|id| notes.get(&id))),
+                    LinkTargetType::Book => (link.target_book_id, link.target_book_id.and_then(|id| books.get(&id))),
+                };
+
+                NoteLinkResponse {
+                    text: link.link_text,
+                    target_type,
+                    target_id,
+                    target_title: target.and_then(|(title, _)| title.clone()),
+                    broken: target.is_none_or(|(_, deleted)| *deleted),
+                }
+            })
+            .collect())
+    }
+
+    /// Lists active notes that link to the given note, newest first
+    pub fn backlinks(conn: &mut PgConnection, note_id: i64) -> Result<Vec<ReadingNote>> {
+        ReadingNote::find_by_id(conn, note_id)?;
+
+        let sources = note_links::table
+            .filter(note_links::target_note_id.eq(note_id))
+            .select(note_links::source_note_id);
+
+        reading_notes::table
+            .filter(reading_notes::id.eq_any(sources))
+            .filter(reading_notes::deleted_at.is_null())
+            .order(reading_notes::created_at.desc())
+            .select(ReadingNote::as_select())
+            .load(conn)
+            .map_err(AppError::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_links() {
+        let content = "See [[note:12]] and [[ Dune ]], again [[Dune]].\n\n`[[not a link]]`\n\n```\n[[nor this]]\n```\n[[]] [[unclosed";
+        assert_eq!(parse_links(content), vec!["note:12".to_string(), "Dune".to_string()]);
+    }
+
+    #[test]
+    fn test_target_type() {
+        assert_eq!(target_type("note:12"), LinkTargetType::Note);
+        assert_eq!(target_type("Notes from Underground"), LinkTargetType::Book);
+    }
+}
@@ -0,0 +1,173 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use crate::errors::{AppError, Result};
+
+/// Structured position of a note in its book, parsed from the page reference
+///
+/// Understands references such as "p. 5", "Pages 10-20", "Loc 1234",
+/// "45%", "Chapter 3, p. 45" and "第3章".
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct NoteLocation {
+    #[schema(example = 10)]
+    pub page_start: Option<i32>,
+
+    #[schema(example = 20)]
+    pub page_end: Option<i32>,
+
+    #[schema(example = 3)]
+    pub chapter: Option<i32>,
+
+    /// Kindle location
+    #[schema(example = 1234)]
+    pub kindle_location: Option<i32>,
+
+    #[schema(example = 45)]
+    pub percentage: Option<i32>,
+}
+
+impl NoteLocation {
+    /// Parses a free-text page reference
+    ///
+    /// Parts are separated by commas or semicolons; parts that are not
+    /// understood are ignored, so unknown formats yield an empty location.
+    pub fn parse(reference: &str) -> NoteLocation {
+        let mut location = NoteLocation::default();
+
+        for part in reference.split([',', ';']) {
+            let part = part.trim().to_lowercase();
+            if part.is_empty() {
+                continue;
+            }
+
+            // 第3章 (chapter 3), 第45页 (page 45)
+            if let Some(rest) = part.strip_prefix('第') {
+                if let Some(n) = rest.strip_suffix('章') {
+                    location.chapter = n.trim().parse().ok().or(location.chapter);
+                } else if let Some(n) = rest.strip_suffix('页') {
+                    if let Some((start, end)) = parse_range(n) {
+                        location.page_start = Some(start);
+                        location.page_end = end;
+                    }
+                }
+                continue;
+            }
+
+            if let Some(n) = part.strip_suffix('%') {
+                location.percentage = n
+                    .trim()
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|p| p.is_finite())
+                    .map(|p| p.round() as i32)
+                    .or(location.percentage);
+                continue;
+            }
+
+            let keyword_end = part
+                .find(|c: char| !(c.is_alphabetic() || c == '.' || c.is_whitespace()))
+                .unwrap_or(part.len());
+            let keyword = part[..keyword_end].trim().trim_end_matches('.');
+            let Some((start, end)) = parse_range(&part[keyword_end..]) else {
+                continue;
+            };
+
+            match keyword {
+                "" | "p" | "pp" | "pg" | "pgs" | "page" | "pages" | "页" => {
+                    location.page_start = Some(start);
+                    location.page_end = end;
+                }
+                "loc" | "location" | "kindle location" => location.kindle_location = Some(start),
+                "ch" | "chap" | "chapter" => location.chapter = Some(start),
+                _ => {}
+            }
+        }
+
+        location
+    }
+
+    /// Whether nothing could be parsed
+    pub fn is_empty(&self) -> bool {
+        *self == NoteLocation::default()
+    }
+
+    /// Checks that the location is plausible for a book with `page_count` pages
+    pub fn validate(&self, page_count: Option<i32>) -> Result<()> {
+        if self.page_start.is_some_and(|p| p < 1) {
+            return Err(AppError::ValidationError("Page numbers start at 1".to_string()));
+        }
+        if let (Some(start), Some(end)) = (self.page_start, self.page_end) {
+            if end < start {
+                return Err(AppError::ValidationError(format!(
+                    "Page range {}-{} ends before it starts",
+                    start, end
+                )));
+            }
+        }
+        if let (Some(last), Some(page_count)) = (self.page_end.or(self.page_start), page_count) {
+            if last > page_count {
+                return Err(AppError::ValidationError(format!(
+                    "Page {} is beyond the book's {} pages",
+                    last, page_count
+                )));
+            }
+        }
+        if self.percentage.is_some_and(|p| !(0..=100).contains(&p)) {
+            return Err(AppError::ValidationError("Percentage must be between 0 and 100".to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// Parses "12" or "12-15" (also with en or em dashes) into a start and optional end
+fn parse_range(text: &str) -> Option<(i32, Option<i32>)> {
+    let mut bounds = text.split(['-', '–', '—']).map(str::trim);
+    let start = bounds.next()?.parse().ok()?;
+    let end = match bounds.next() {
+        Some(end) => Some(end.parse().ok()?),
+        None => None,
+    };
+    if bounds.next().is_some() {
+        return None;
+    }
+    Some((start, end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_references() {
+        let pages = |start, end| NoteLocation { page_start: Some(start), page_end: end, ..Default::default() };
+
+        assert_eq!(NoteLocation::parse("p.5"), pages(5, None));
+        assert_eq!(NoteLocation::parse("Pages 10-20"), pages(10, Some(20)));
+        assert_eq!(NoteLocation::parse("pp. 10 – 12"), pages(10, Some(12)));
+        assert_eq!(NoteLocation::parse("1-20"), pages(1, Some(20)));
+        assert_eq!(NoteLocation::parse("Loc 1234").kindle_location, Some(1234));
+        assert_eq!(NoteLocation::parse("45.5%").percentage, Some(46));
+        assert_eq!(NoteLocation::parse("nan%").percentage, None);
+        assert_eq!(NoteLocation::parse("inf%").percentage, None);
+        assert_eq!(
+            NoteLocation::parse("Chapter 3, p. 45"),
+            NoteLocation { chapter: Some(3), page_start: Some(45), ..Default::default() }
+        );
+        assert_eq!(
+            NoteLocation::parse("第3章, 第45页"),
+            NoteLocation { chapter: Some(3), page_start: Some(45), ..Default::default() }
+        );
+        assert!(NoteLocation::parse("Introduction").is_empty());
+        assert!(NoteLocation::parse("p. 5-6-7").is_empty());
+    }
+
+    #[test]
+    fn test_validate() {
+        let location = NoteLocation::parse("pages 250-310");
+        assert!(location.validate(None).is_ok());
+        assert!(location.validate(Some(320)).is_ok());
+        assert!(location.validate(Some(300)).is_err());
+        assert!(NoteLocation::parse("pages 20-10").validate(None).is_err());
+        assert!(NoteLocation::parse("p. 0").validate(None).is_err());
+        assert!(NoteLocation::parse("120%").validate(None).is_err());
+    }
+}
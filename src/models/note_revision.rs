@@ -0,0 +1,246 @@
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use similar::{ChangeTag, TextDiff};
+use utoipa::ToSchema;
+use crate::db::schema::note_revisions;
+use crate::errors::{AppError, Result};
+use crate::models::note::{NoteChangeset, ReadingNote};
+
+/// Prior version of a note's content
+#[derive(Debug, Serialize, Deserialize, Queryable, Selectable, Identifiable)]
+#[diesel(table_name = note_revisions)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct NoteRevision {
+    pub id: i64,
+    pub note_id: i64,
+    pub revision: i32,
+    pub title: Option<String>,
+    pub content: String,
+    pub note_type: Option<String>,
+    pub page_reference: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// New note revision for insertion
+#[derive(Debug, Insertable)]
+#[diesel(table_name = note_revisions)]
+pub struct NewNoteRevision {
+    pub note_id: i64,
+    pub revision: i32,
+    pub title: Option<String>,
+    pub content: String,
+    pub note_type: Option<String>,
+    pub page_reference: Option<String>,
+}
+
+/// Note revision response
+#[derive(Debug, Serialize, ToSchema)]
+pub struct NoteRevisionResponse {
+    /// Revision number, starting at 1 for the original version
+    #[schema(example = 1)]
+    pub revision: i32,
+
+    #[schema(example = 1)]
+    pub note_id: i64,
+
+    #[schema(example = "Chapter 1 Summary")]
+    pub title: Option<String>,
+
+    #[schema(example = "This chapter introduces the main concepts...")]
+    pub content: String,
+
+    #[schema(example = "summary")]
+    pub note_type: Option<String>,
+
+    #[schema(example = "Pages 1-15")]
+    pub page_reference: Option<String>,
+
+    /// When this version was replaced
+    #[schema(example = "2024-01-01T12:00:00Z")]
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// Kind of change in a diff segment
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffOp {
+    Equal,
+    Insert,
+    Delete,
+}
+
+/// Run of words with the same kind of change
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct DiffSegment {
+    #[schema(example = "insert")]
+    pub op: DiffOp,
+
+    #[schema(example = "carefully ")]
+    pub text: String,
+}
+
+/// Word-level diff between two versions of a note's content
+#[derive(Debug, Serialize, ToSchema)]
+pub struct NoteDiffResponse {
+    #[schema(example = 1)]
+    pub note_id: i64,
+
+    #[schema(example = 1)]
+    pub from: i32,
+
+    /// Target revision, absent when comparing against the current content
+    #[schema(example = 2)]
+    pub to: Option<i32>,
+
+    pub segments: Vec<DiffSegment>,
+}
+
+impl From<NoteRevision> for NoteRevisionResponse {
+    fn from(revision: NoteRevision) -> Self {
+        NoteRevisionResponse {
+            revision: revision.revision,
+            note_id: revision.note_id,
+            title: revision.title,
+            content: revision.content,
+            note_type: revision.note_type,
+            page_reference: revision.page_reference,
+            created_at: revision.created_at,
+        }
+    }
+}
+
+/// Computes a word-level diff, merging adjacent words with the same change
+pub fn diff_words(old: &str, new: &str) -> Vec<DiffSegment> {
+    let mut segments: Vec<DiffSegment> = Vec::new();
+
+    for change in TextDiff::from_words(old, new).iter_all_changes() {
+        let op = match change.tag() {
+            ChangeTag::Equal => DiffOp::Equal,
+            ChangeTag::Insert => DiffOp::Insert,
+            ChangeTag::Delete => DiffOp::Delete,
+        };
+        match segments.last_mut() {
+            Some(last) if last.op == op => last.text.push_str(change.value()),
+            _ => segments.push(DiffSegment { op, text: change.value().to_string() }),
+        }
+    }
+
+    segments
+}
+
+impl NoteRevision {
+    /// Stores the current state of a note as its next revision
+    pub fn record(conn: &mut PgConnection, note: &ReadingNote) -> Result<NoteRevision> {
+        let latest: Option<i32> = note_revisions::table
+            .filter(note_revisions::note_id.eq(note.id))
+            .select(diesel::dsl::max(note_revisions::revision))
+            .first(conn)?;
+
+        let new_revision = NewNoteRevision {
+            note_id: note.id,
+            revision: latest.unwrap_or(0) + 1,
+            title: note.title.clone(),
+            content: note.content.clone(),
+            note_type: note.note_type.clone(),
+            page_reference: note.page_reference.clone(),
+        };
+
+        diesel::insert_into(note_revisions::table)
+            .values(&new_revision)
+            .returning(NoteRevision::as_returning())
+            .get_result(conn)
+            .map_err(AppError::from)
+    }
+
+    /// Lists all revisions of a note, newest first
+    pub fn list_for_note(conn: &mut PgConnection, note_id: i64) -> Result<Vec<NoteRevision>> {
+        ReadingNote::find_by_id(conn, note_id)?;
+
+        note_revisions::table
+            .filter(note_revisions::note_id.eq(note_id))
+            .order(note_revisions::revision.desc())
+            .select(NoteRevision::as_select())
+            .load(conn)
+            .map_err(AppError::from)
+    }
+
+    /// Finds a revision of a note by its number
+    pub fn find(conn: &mut PgConnection, note_id: i64, revision: i32) -> Result<NoteRevision> {
+        note_revisions::table
+            .filter(note_revisions::note_id.eq(note_id))
+            .filter(note_revisions::revision.eq(revision))
+            .select(NoteRevision::as_select())
+            .first(conn)
+            .map_err(|e| match e {
+                diesel::result::Error::NotFound => AppError::NotFound(format!(
+                    "Revision {} of note with id {} not found",
+                    revision, note_id
+                )),
+                _ => AppError::from(e),
+            })
+    }
+
+    /// Diffs two revisions, or a revision against the current content when `to` is `None`
+    pub fn diff(conn: &mut PgConnection, note_id: i64, from: i32, to: Option<i32>) -> Result<NoteDiffResponse> {
+        let note = ReadingNote::find_by_id(conn, note_id)?;
+        let old = Self::find(conn, note_id, from)?.content;
+        let new = match to {
+            Some(to) => Self::find(conn, note_id, to)?.content,
+            None => note.content,
+        };
+
+        Ok(NoteDiffResponse {
+            note_id,
+            from,
+            to,
+            segments: diff_words(&old, &new),
+        })
+    }
+
+    /// Restores a note to a prior revision
+    ///
+    /// The content being replaced is kept as a new revision, so a restore
+    /// can itself be undone.
+    pub fn restore(conn: &mut PgConnection, note_id: i64, revision: i32) -> Result<ReadingNote> {
+        conn.transaction(|conn| {
+            ReadingNote::find_by_id(conn, note_id)?;
+            let revision = Self::find(conn, note_id, revision)?;
+
+            ReadingNote::patch(
+                conn,
+                note_id,
+                NoteChangeset {
+                    title: Some(revision.title),
+                    content: Some(revision.content),
+                    note_type: Some(revision.note_type),
+                    page_reference: Some(revision.page_reference),
+                    ..Default::default()
+                },
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_words() {
+        let segments = diff_words("the quick fox", "the quick brown fox");
+        assert_eq!(
+            segments,
+            vec![
+                DiffSegment { op: DiffOp::Equal, text: "the quick ".to_string() },
+                DiffSegment { op: DiffOp::Insert, text: "brown ".to_string() },
+                DiffSegment { op: DiffOp::Equal, text: "fox".to_string() },
+            ]
+        );
+
+        let segments = diff_words("old text", "new text");
+        assert_eq!(segments[0], DiffSegment { op: DiffOp::Delete, text: "old".to_string() });
+        assert_eq!(segments[1], DiffSegment { op: DiffOp::Insert, text: "new".to_string() });
+        assert!(diff_words("same", "same").iter().all(|s| s.op == DiffOp::Equal));
+    }
+}
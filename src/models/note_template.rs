@@ -0,0 +1,418 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+use crate::db::schema::note_templates;
+use crate::errors::{AppError, Result};
+use crate::models::book::Book;
+use crate::models::note::{NewReadingNote, ReadingNote};
+use crate::models::note_type::validate_note_type;
+use crate::utils::validation::{optional_text, required_text};
+
+/// Placeholders that can be used in template titles and content
+pub const PLACEHOLDERS: [&str; 6] = [
+    "book.title",
+    "book.author",
+    "book.isbn",
+    "book.publisher",
+    "book.page_count",
+    "today",
+];
+
+/// Longest template name, matching its column
+const MAX_NAME_LENGTH: usize = 100;
+/// Longest title, of the template and of notes created from it
+const MAX_TITLE_LENGTH: usize = 200;
+/// Longest tag name, matching the tags table
+const MAX_TAG_LENGTH: usize = 30;
+/// Longest page reference of a note
+const MAX_PAGE_REFERENCE_LENGTH: usize = 50;
+
+/// Note template database model
+#[derive(Debug, Serialize, Deserialize, Queryable, Selectable, Identifiable)]
+#[diesel(table_name = note_templates)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct NoteTemplate {
+    pub id: i64,
+    pub name: String,
+    pub title: Option<String>,
+    pub content: String,
+    pub note_type: Option<String>,
+    pub tags: Value,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// New note template for insertion
+#[derive(Debug, Insertable)]
+#[diesel(table_name = note_templates)]
+pub struct NewNoteTemplate {
+    pub name: String,
+    pub title: Option<String>,
+    pub content: String,
+    pub note_type: Option<String>,
+    pub tags: Value,
+}
+
+/// Note template changes
+#[derive(Debug, Default, AsChangeset)]
+#[diesel(table_name = note_templates)]
+pub struct UpdateNoteTemplate {
+    pub name: Option<String>,
+    pub title: Option<String>,
+    pub content: Option<String>,
+    pub note_type: Option<String>,
+    pub tags: Option<Value>,
+}
+
+/// Request structure for creating a note template
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct CreateNoteTemplateRequest {
+    #[schema(example = "Book summary")]
+    pub name: String,
+
+    /// Title of created notes, may contain placeholders
+    #[schema(example = "Summary of {{book.title}}")]
+    pub title: Option<String>,
+
+    /// Markdown content with placeholders such as `{{book.title}}`,
+    /// `{{book.author}}` and `{{today}}`
+    #[schema(example = "# {{book.title}} by {{book.author}}\n\n## Key ideas\n\n## Quotes\n\n## Actions\n")]
+    pub content: String,
+
+    /// Note type of created notes
+    #[schema(example = "summary")]
+    pub note_type: Option<String>,
+
+    /// Tags of created notes
+    #[schema(example = json!(["summary"]))]
+    pub tags: Option<Vec<String>>,
+}
+
+/// Request structure for updating a note template
+#[derive(Debug, Default, Deserialize, Serialize, ToSchema)]
+pub struct UpdateNoteTemplateRequest {
+    #[schema(example = "Book summary")]
+    pub name: Option<String>,
+
+    #[schema(example = "Summary of {{book.title}}")]
+    pub title: Option<String>,
+
+    #[schema(example = "# {{book.title}}\n\n## Key ideas\n")]
+    pub content: Option<String>,
+
+    #[schema(example = "summary")]
+    pub note_type: Option<String>,
+
+    #[schema(example = json!(["summary"]))]
+    pub tags: Option<Vec<String>>,
+}
+
+/// Response structure for a note template
+#[derive(Debug, Serialize, ToSchema)]
+pub struct NoteTemplateResponse {
+    #[schema(example = 1)]
+    pub id: i64,
+
+    #[schema(example = "Book summary")]
+    pub name: String,
+
+    #[schema(example = "Summary of {{book.title}}")]
+    pub title: Option<String>,
+
+    #[schema(example = "# {{book.title}} by {{book.author}}\n\n## Key ideas\n\n## Quotes\n\n## Actions\n")]
+    pub content: String,
+
+    #[schema(example = "summary")]
+    pub note_type: Option<String>,
+
+    #[schema(example = json!(["summary"]))]
+    pub tags: Vec<String>,
+
+    #[schema(example = "2024-01-01T12:00:00Z")]
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// Request structure for creating a note from a template
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct CreateNoteFromTemplateRequest {
+    #[schema(example = 1)]
+    pub template_id: i64,
+
+    #[schema(example = 1)]
+    pub book_id: i64,
+
+    #[schema(example = "Pages 1-15")]
+    pub page_reference: Option<String>,
+}
+
+impl From<NoteTemplate> for NoteTemplateResponse {
+    fn from(template: NoteTemplate) -> Self {
+        let tags = template.tags();
+        Self {
+            id: template.id,
+            name: template.name,
+            title: template.title,
+            content: template.content,
+            note_type: template.note_type,
+            tags,
+            created_at: template.created_at,
+        }
+    }
+}
+
+impl NoteTemplate {
+    /// Default tags of notes created from this template
+    pub fn tags(&self) -> Vec<String> {
+        self.tags
+            .as_array()
+            .map(|tags| tags.iter().filter_map(|t| t.as_str().map(String::from)).collect())
+            .unwrap_or_default()
+    }
+
+    /// Creates a new note template
+    pub fn create(conn: &mut PgConnection, request: CreateNoteTemplateRequest) -> Result<NoteTemplate> {
+        let name = required_text("Name", &request.name, MAX_NAME_LENGTH)?;
+        if Self::find_by_name(conn, &name)?.is_some() {
+            return Err(AppError::Conflict(format!("Note template '{}' already exists", name)));
+        }
+        let title = optional_text("Title", request.title, MAX_TITLE_LENGTH)?;
+        if let Some(ref title) = title {
+            validate_placeholders(title)?;
+        }
+        if request.content.trim().is_empty() {
+            return Err(AppError::ValidationError("Content cannot be empty".to_string()));
+        }
+        validate_placeholders(&request.content)?;
+        if let Some(ref note_type) = request.note_type {
+            validate_note_type(conn, note_type)?;
+        }
+        let tags = tags_value(request.tags.unwrap_or_default())?;
+
+        diesel::insert_into(note_templates::table)
+            .values(&NewNoteTemplate {
+                name,
+                title,
+                content: request.content,
+                note_type: request.note_type,
+                tags,
+            })
+            .returning(NoteTemplate::as_returning())
+            .get_result(conn)
+            .map_err(AppError::from)
+    }
+
+    /// Finds a template by ID
+    pub fn find_by_id(conn: &mut PgConnection, template_id: i64) -> Result<NoteTemplate> {
+        note_templates::table
+            .find(template_id)
+            .first(conn)
+            .map_err(|_| AppError::NotFound(format!("Note template with id {} not found", template_id)))
+    }
+
+    /// Finds a template by name
+    pub fn find_by_name(conn: &mut PgConnection, name: &str) -> Result<Option<NoteTemplate>> {
+        note_templates::table
+            .filter(note_templates::name.eq(name))
+            .first(conn)
+            .optional()
+            .map_err(AppError::from)
+    }
+
+    /// Lists all templates ordered by name
+    pub fn list_all(conn: &mut PgConnection) -> Result<Vec<NoteTemplate>> {
+        note_templates::table
+            .order(note_templates::name.asc())
+            .load::<NoteTemplate>(conn)
+            .map_err(AppError::from)
+    }
+
+    /// Updates a template
+    pub fn update(
+        conn: &mut PgConnection,
+        template_id: i64,
+        request: UpdateNoteTemplateRequest,
+    ) -> Result<NoteTemplate> {
+        let template = Self::find_by_id(conn, template_id)?;
+
+        let name = optional_text("Name", request.name, MAX_NAME_LENGTH)?;
+        if let Some(ref name) = name {
+            if Self::find_by_name(conn, name)?.is_some_and(|other| other.id != template.id) {
+                return Err(AppError::Conflict(format!("Note template '{}' already exists", name)));
+            }
+        }
+        let title = optional_text("Title", request.title, MAX_TITLE_LENGTH)?;
+        if let Some(ref title) = title {
+            validate_placeholders(title)?;
+        }
+        if let Some(ref content) = request.content {
+            if content.trim().is_empty() {
+                return Err(AppError::ValidationError("Content cannot be empty".to_string()));
+            }
+            validate_placeholders(content)?;
+        }
+        if let Some(ref note_type) = request.note_type {
+            validate_note_type(conn, note_type)?;
+        }
+        let tags = request.tags.map(tags_value).transpose()?;
+
+        diesel::update(note_templates::table.find(template_id))
+            .set((
+                &UpdateNoteTemplate {
+                    name,
+                    title,
+                    content: request.content,
+                    note_type: request.note_type,
+                    tags,
+                },
+                note_templates::updated_at.eq(Some(Utc::now())),
+            ))
+            .returning(NoteTemplate::as_returning())
+            .get_result(conn)
+            .map_err(AppError::from)
+    }
+
+    /// Deletes a template; notes created from it are kept
+    pub fn delete(conn: &mut PgConnection, template_id: i64) -> Result<()> {
+        Self::find_by_id(conn, template_id)?;
+        diesel::delete(note_templates::table.find(template_id)).execute(conn)?;
+        Ok(())
+    }
+
+    /// Renders the template for a book into a new note
+    pub fn instantiate(
+        &self,
+        book: &Book,
+        today: NaiveDate,
+        page_reference: Option<String>,
+    ) -> NewReadingNote {
+        NewReadingNote {
+            book_id: book.id,
+            title: self.title.as_deref().map(|title| render(title, book, today)),
+            content: render(&self.content, book, today),
+            note_type: self.note_type.clone(),
+            page_reference,
+            ..Default::default()
+        }
+    }
+
+    /// Creates a note for a book from a template, with the template's tags
+    pub fn create_note(
+        conn: &mut PgConnection,
+        request: CreateNoteFromTemplateRequest,
+        today: NaiveDate,
+    ) -> Result<ReadingNote> {
+        let template = Self::find_by_id(conn, request.template_id)?;
+        let book = Book::find_by_id(conn, request.book_id)?;
+        let page_reference = optional_text("Page reference", request.page_reference, MAX_PAGE_REFERENCE_LENGTH)?;
+
+        let new_note = template.instantiate(&book, today, page_reference);
+        // Placeholders such as a long book title can push the title over the limit
+        if new_note.title.as_ref().is_some_and(|title| title.chars().count() > MAX_TITLE_LENGTH) {
+            return Err(AppError::ValidationError(format!(
+                "Title rendered from the template exceeds {} characters",
+                MAX_TITLE_LENGTH
+            )));
+        }
+
+        conn.transaction(|conn| {
+            let note = ReadingNote::create(conn, new_note)?;
+            let tags = template.tags();
+            if !tags.is_empty() {
+                note.set_tags(conn, tags)?;
+            }
+            Ok(note)
+        })
+    }
+}
+
+/// Stores tags as a JSON array of trimmed, non-empty names
+fn tags_value(tags: Vec<String>) -> Result<Value> {
+    let tags = tags
+        .into_iter()
+        .filter(|t| !t.trim().is_empty())
+        .map(|t| required_text("Tag", &t, MAX_TAG_LENGTH))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(Value::from(tags))
+}
+
+/// Calls `f` with the trimmed name of every `{{ placeholder }}` and
+/// appends its replacement; text outside placeholders is kept as-is
+fn substitute(template: &str, mut f: impl FnMut(&str) -> Option<String>) -> String {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let raw = &rest[start..start + 2 + len + 2];
+        output.push_str(&rest[..start]);
+        match f(rest[start + 2..start + 2 + len].trim()) {
+            Some(value) => output.push_str(&value),
+            None => output.push_str(raw),
+        }
+        rest = &rest[start + raw.len()..];
+    }
+
+    output.push_str(rest);
+    output
+}
+
+/// Checks that a template only uses known placeholders
+fn validate_placeholders(template: &str) -> Result<()> {
+    let mut unknown = None;
+    substitute(template, |name| {
+        if unknown.is_none() && !PLACEHOLDERS.contains(&name) {
+            unknown = Some(name.to_string());
+        }
+        None
+    });
+
+    match unknown {
+        Some(name) => Err(AppError::ValidationError(format!(
+            "Unknown placeholder '{{{{{}}}}}', expected one of: {}",
+            name,
+            PLACEHOLDERS.join(", ")
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Replaces placeholders with the book's details; missing details become empty
+fn render(template: &str, book: &Book, today: NaiveDate) -> String {
+    substitute(template, |name| {
+        let value = match name {
+            "book.title" => book.title.clone(),
+            "book.author" => book.author.clone(),
+            "book.isbn" => book.isbn.clone().unwrap_or_default(),
+            "book.publisher" => book.publisher.clone().unwrap_or_default(),
+            "book.page_count" => book.page_count.map(|p| p.to_string()).unwrap_or_default(),
+            "today" => today.to_string(),
+            _ => return None,
+        };
+        Some(value)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_substitute() {
+        let upper = |name: &str| (name != "skip").then(|| name.to_uppercase());
+
+        assert_eq!(substitute("Notes on {{ book.title }}!", upper), "Notes on BOOK.TITLE!");
+        assert_eq!(substitute("{{a}}{{b}}", upper), "AB");
+        assert_eq!(substitute("keep {{skip}} and {{unclosed", upper), "keep {{skip}} and {{unclosed");
+        assert_eq!(substitute("no placeholders", upper), "no placeholders");
+    }
+
+    #[test]
+    fn test_validate_placeholders() {
+        assert!(validate_placeholders("# {{book.title}} by {{ book.author }} ({{today}})").is_ok());
+        assert!(validate_placeholders("{{book.name}}").is_err());
+    }
+}
@@ -0,0 +1,296 @@
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use crate::db::schema::{note_templates, note_types, reading_notes};
+use crate::errors::{AppError, Result};
+use crate::utils::validation::{optional_text, required_text};
+
+/// Longest name or icon, matching their columns
+const MAX_NAME_LENGTH: usize = 50;
+
+/// Note type database model
+///
+/// Notes store the type's key in `reading_notes.note_type`.
+#[derive(Debug, Serialize, Deserialize, Queryable, Selectable, Identifiable)]
+#[diesel(table_name = note_types)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct NoteType {
+    pub id: i64,
+    pub key: String,
+    pub name: String,
+    pub icon: Option<String>,
+    pub color: Option<String>,
+    pub is_builtin: bool,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// New note type for insertion
+#[derive(Debug, Insertable)]
+#[diesel(table_name = note_types)]
+pub struct NewNoteType {
+    pub key: String,
+    pub name: String,
+    pub icon: Option<String>,
+    pub color: Option<String>,
+}
+
+/// Note type changes
+#[derive(Debug, Default, AsChangeset)]
+#[diesel(table_name = note_types)]
+pub struct UpdateNoteType {
+    pub name: Option<String>,
+    pub icon: Option<String>,
+    pub color: Option<String>,
+}
+
+/// Request structure for creating a note type
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct CreateNoteTypeRequest {
+    /// Value used in a note's `note_type`: lowercase letters, digits and underscores
+    #[schema(example = "action_item")]
+    pub key: String,
+
+    #[schema(example = "Action item")]
+    pub name: String,
+
+    #[schema(example = "check-square")]
+    pub icon: Option<String>,
+
+    /// Hex color such as `#ef4444`
+    #[schema(example = "#ef4444")]
+    pub color: Option<String>,
+}
+
+/// Request structure for updating a note type
+///
+/// The key cannot change once notes may use it.
+#[derive(Debug, Default, Deserialize, Serialize, ToSchema)]
+pub struct UpdateNoteTypeRequest {
+    #[schema(example = "Action item")]
+    pub name: Option<String>,
+
+    #[schema(example = "check-square")]
+    pub icon: Option<String>,
+
+    #[schema(example = "#ef4444")]
+    pub color: Option<String>,
+}
+
+/// Response structure for a note type
+#[derive(Debug, Serialize, ToSchema)]
+pub struct NoteTypeResponse {
+    #[schema(example = 5)]
+    pub id: i64,
+
+    #[schema(example = "action_item")]
+    pub key: String,
+
+    #[schema(example = "Action item")]
+    pub name: String,
+
+    #[schema(example = "check-square")]
+    pub icon: Option<String>,
+
+    #[schema(example = "#ef4444")]
+    pub color: Option<String>,
+
+    /// Built-in types cannot be deleted
+    #[schema(example = false)]
+    pub is_builtin: bool,
+
+    #[schema(example = "2024-01-01T12:00:00Z")]
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl From<NoteType> for NoteTypeResponse {
+    fn from(note_type: NoteType) -> Self {
+        Self {
+            id: note_type.id,
+            key: note_type.key,
+            name: note_type.name,
+            icon: note_type.icon,
+            color: note_type.color,
+            is_builtin: note_type.is_builtin,
+            created_at: note_type.created_at,
+        }
+    }
+}
+
+impl NoteType {
+    /// Creates a new note type
+    pub fn create(conn: &mut PgConnection, request: CreateNoteTypeRequest) -> Result<NoteType> {
+        let key = request.key.trim().to_string();
+        validate_key(&key)?;
+        let name = required_text("Name", &request.name, MAX_NAME_LENGTH)?;
+        let icon = optional_text("Icon", request.icon, MAX_NAME_LENGTH)?;
+        if let Some(ref color) = request.color {
+            validate_color(color)?;
+        }
+
+        if Self::find_by_key(conn, &key)?.is_some() {
+            return Err(AppError::Conflict(format!("Note type '{}' already exists", key)));
+        }
+
+        diesel::insert_into(note_types::table)
+            .values(&NewNoteType {
+                key,
+                name,
+                icon,
+                color: request.color,
+            })
+            .returning(NoteType::as_returning())
+            .get_result(conn)
+            .map_err(AppError::from)
+    }
+
+    /// Finds a note type by ID
+    pub fn find_by_id(conn: &mut PgConnection, type_id: i64) -> Result<NoteType> {
+        note_types::table
+            .find(type_id)
+            .first(conn)
+            .optional()?
+            .ok_or_else(|| AppError::NotFound(format!("Note type with id {} not found", type_id)))
+    }
+
+    /// Finds a note type by key
+    pub fn find_by_key(conn: &mut PgConnection, key: &str) -> Result<Option<NoteType>> {
+        note_types::table
+            .filter(note_types::key.eq(key))
+            .first(conn)
+            .optional()
+            .map_err(AppError::from)
+    }
+
+    /// Lists all note types, built-in types first
+    pub fn list_all(conn: &mut PgConnection) -> Result<Vec<NoteType>> {
+        note_types::table
+            .order((note_types::is_builtin.desc(), note_types::id.asc()))
+            .load::<NoteType>(conn)
+            .map_err(AppError::from)
+    }
+
+    /// Updates a note type
+    pub fn update(conn: &mut PgConnection, type_id: i64, request: UpdateNoteTypeRequest) -> Result<NoteType> {
+        Self::find_by_id(conn, type_id)?;
+        let name = optional_text("Name", request.name, MAX_NAME_LENGTH)?;
+        let icon = optional_text("Icon", request.icon, MAX_NAME_LENGTH)?;
+        if let Some(ref color) = request.color {
+            validate_color(color)?;
+        }
+
+        diesel::update(note_types::table.find(type_id))
+            .set((
+                &UpdateNoteType {
+                    name,
+                    icon,
+                    color: request.color,
+                },
+                note_types::updated_at.eq(Some(Utc::now())),
+            ))
+            .returning(NoteType::as_returning())
+            .get_result(conn)
+            .map_err(AppError::from)
+    }
+
+    /// Deletes a note type
+    ///
+    /// Built-in types and types still used by a note, including notes in
+    /// the trash, or by a note template cannot be deleted.
+    pub fn delete(conn: &mut PgConnection, type_id: i64) -> Result<()> {
+        let note_type = Self::find_by_id(conn, type_id)?;
+        if note_type.is_builtin {
+            return Err(AppError::Conflict(format!(
+                "Built-in note type '{}' cannot be deleted",
+                note_type.key
+            )));
+        }
+
+        let in_use = reading_notes::table
+            .filter(reading_notes::note_type.eq(&note_type.key))
+            .count()
+            .get_result::<i64>(conn)?;
+        if in_use > 0 {
+            return Err(AppError::Conflict(format!(
+                "Note type '{}' is used by {} notes",
+                note_type.key, in_use
+            )));
+        }
+
+        let templates = note_templates::table
+            .filter(note_templates::note_type.eq(&note_type.key))
+            .count()
+            .get_result::<i64>(conn)?;
+        if templates > 0 {
+            return Err(AppError::Conflict(format!(
+                "Note type '{}' is used by {} note templates",
+                note_type.key, templates
+            )));
+        }
+
+        diesel::delete(note_types::table.find(type_id)).execute(conn)?;
+        Ok(())
+    }
+}
+
+/// Checks that a note's type refers to a defined note type
+pub fn validate_note_type(conn: &mut PgConnection, key: &str) -> Result<()> {
+    if NoteType::find_by_key(conn, key)?.is_some() {
+        return Ok(());
+    }
+
+    let keys: Vec<String> = NoteType::list_all(conn)?.into_iter().map(|t| t.key).collect();
+    Err(AppError::ValidationError(format!(
+        "Invalid note type '{}', expected one of: {}",
+        key,
+        keys.join(", ")
+    )))
+}
+
+/// Checks that a key is 1-20 lowercase letters, digits and underscores
+fn validate_key(key: &str) -> Result<()> {
+    if key.is_empty()
+        || key.len() > 20
+        || !key.starts_with(|c: char| c.is_ascii_lowercase())
+        || !key.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+    {
+        return Err(AppError::ValidationError(format!(
+            "Invalid key '{}', use up to 20 lowercase letters, digits and underscores",
+            key
+        )));
+    }
+    Ok(())
+}
+
+/// Checks that a color is a `#rrggbb` hex color
+fn validate_color(color: &str) -> Result<()> {
+    let valid = color.len() == 7
+        && color.starts_with('#')
+        && color[1..].chars().all(|c| c.is_ascii_hexdigit());
+    if !valid {
+        return Err(AppError::ValidationError(format!(
+            "Invalid color '{}', expected a hex color such as #ef4444",
+            color
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_key_and_color() {
+        assert!(validate_key("vocabulary").is_ok());
+        assert!(validate_key("action_item").is_ok());
+        assert!(validate_key("Action Item").is_err());
+        assert!(validate_key("1st").is_err());
+        assert!(validate_key("a_key_that_is_far_too_long").is_err());
+
+        assert!(validate_color("#EF4444").is_ok());
+        assert!(validate_color("red").is_err());
+        assert!(validate_color("#ef44").is_err());
+    }
+}
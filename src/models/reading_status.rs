@@ -1,8 +1,15 @@
 use chrono::{NaiveDate, DateTime, Utc};
 use diesel::prelude::*;
 use serde::{Deserialize, Serialize};
-use bigdecimal::BigDecimal;
+use bigdecimal::{BigDecimal, FromPrimitive, ToPrimitive};
+use utoipa::ToSchema;
+use uuid::Uuid;
 use crate::db::schema::reading_status;
+use crate::errors::{AppError, Result};
+use crate::models::book::{Book, BookFormat};
+
+/// Allowed values for `reading_status.status`
+pub const READING_STATUSES: [&str; 3] = ["to_read", "reading", "completed"];
 
 #[derive(Debug, Serialize, Deserialize, Queryable, Selectable)]
 #[diesel(table_name = reading_status)]
@@ -19,6 +26,9 @@ pub struct ReadingStatus {
     pub deleted_at: Option<DateTime<Utc>>,
     pub created_at: Option<DateTime<Utc>>,
     pub updated_at: Option<DateTime<Utc>>,
+    pub listened_minutes: Option<i32>,
+    pub current_location: Option<i32>,
+    pub deletion_batch_id: Option<Uuid>,
 }
 
 #[derive(Debug, Deserialize, Insertable)]
@@ -31,7 +41,7 @@ pub struct NewReadingStatus {
     pub finish_date: Option<NaiveDate>,
 }
 
-#[derive(Debug, Deserialize, AsChangeset)]
+#[derive(Debug, Default, Deserialize, AsChangeset)]
 #[diesel(table_name = reading_status)]
 pub struct UpdateReadingStatus {
     pub status: Option<String>,
@@ -40,4 +50,299 @@ pub struct UpdateReadingStatus {
     pub finish_date: Option<NaiveDate>,
     pub current_page: Option<i32>,
     pub reading_progress: Option<BigDecimal>,
-}
\ No newline at end of file
+    pub listened_minutes: Option<i32>,
+    pub current_location: Option<i32>,
+}
+
+/// Request structure for updating a book's reading status and progress
+///
+/// Which progress marker applies depends on the book's format:
+/// `current_page` for paper editions, `listened_minutes` for audiobooks and
+/// `current_location` (or an explicit `reading_progress`) for ebooks.
+#[derive(Debug, Default, Deserialize, Serialize, ToSchema)]
+pub struct UpdateReadingStatusRequest {
+    /// Reading status: to_read, reading or completed
+    #[schema(example = "reading")]
+    pub status: Option<String>,
+
+    /// Rating from 1 to 5
+    #[schema(example = 4)]
+    pub rating: Option<i32>,
+
+    #[schema(example = "2024-01-01")]
+    pub start_date: Option<NaiveDate>,
+
+    #[schema(example = "2024-02-01")]
+    pub finish_date: Option<NaiveDate>,
+
+    /// Current page (hardcover and paperback)
+    #[schema(example = 120)]
+    pub current_page: Option<i32>,
+
+    /// Minutes listened (audiobook)
+    #[schema(example = 300)]
+    pub listened_minutes: Option<i32>,
+
+    /// Current location (ebook)
+    #[schema(example = 1800)]
+    pub current_location: Option<i32>,
+
+    /// Explicit progress percentage, used when it cannot be derived
+    /// from the format-specific marker (e.g. ebook readers that only show %)
+    #[schema(example = 42.5)]
+    pub reading_progress: Option<f64>,
+}
+
+/// Response structure for reading status
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReadingStatusResponse {
+    #[schema(example = 1)]
+    pub id: i64,
+
+    #[schema(example = 1)]
+    pub book_id: i64,
+
+    #[schema(example = "reading")]
+    pub status: String,
+
+    /// Format of the book the progress refers to
+    #[schema(example = "audiobook")]
+    pub format: BookFormat,
+
+    #[schema(example = 4)]
+    pub rating: Option<i32>,
+
+    #[schema(example = "2024-01-01")]
+    pub start_date: Option<NaiveDate>,
+
+    #[schema(example = "2024-02-01")]
+    pub finish_date: Option<NaiveDate>,
+
+    #[schema(example = 120)]
+    pub current_page: Option<i32>,
+
+    #[schema(example = 416)]
+    pub page_count: Option<i32>,
+
+    #[schema(example = 300)]
+    pub listened_minutes: Option<i32>,
+
+    #[schema(example = 720)]
+    pub duration_minutes: Option<i32>,
+
+    #[schema(example = 1800)]
+    pub current_location: Option<i32>,
+
+    #[schema(example = 5400)]
+    pub total_locations: Option<i32>,
+
+    /// Progress percentage (0-100) computed from the format-specific marker
+    #[schema(example = 41.67)]
+    pub reading_progress: Option<f64>,
+
+    #[schema(example = "2024-01-01T12:00:00Z")]
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Computes the progress percentage from a format-specific marker and total
+///
+/// Falls back to `explicit` when the marker or total is unknown, and a
+/// completed book is always 100%.
+pub fn compute_progress(
+    status: &str,
+    marker: Option<i32>,
+    total: Option<i32>,
+    explicit: Option<f64>,
+) -> Option<f64> {
+    if status == "completed" {
+        return Some(100.0);
+    }
+
+    let derived = match (marker, total) {
+        (Some(marker), Some(total)) if total > 0 => Some(marker as f64 / total as f64 * 100.0),
+        _ => None,
+    };
+
+    derived
+        .or(explicit)
+        .map(|p| (p.clamp(0.0, 100.0) * 100.0).round() / 100.0)
+}
+
+/// Returns the (marker, total) pair used for progress in the book's format:
+/// pages for paper editions, minutes for audiobooks and locations for ebooks
+fn progress_inputs(book: &Book, status: &ReadingStatus) -> (Option<i32>, Option<i32>) {
+    match book.format() {
+        BookFormat::Audiobook => (status.listened_minutes, book.duration_minutes),
+        BookFormat::Ebook => (status.current_location, book.total_locations),
+        _ => (status.current_page, book.page_count),
+    }
+}
+
+impl ReadingStatus {
+    /// Finds the active reading status of a book
+    pub fn find_by_book_id(conn: &mut PgConnection, book_id: i64) -> Result<ReadingStatus> {
+        reading_status::table
+            .filter(reading_status::book_id.eq(book_id))
+            .filter(reading_status::deleted_at.is_null())
+            .first(conn)
+            .map_err(|_| AppError::NotFound(format!("Reading status for book {} not found", book_id)))
+    }
+
+    /// Creates or updates the reading status of a book and recomputes its progress
+    pub fn upsert(
+        conn: &mut PgConnection,
+        book: &Book,
+        request: UpdateReadingStatusRequest,
+    ) -> Result<ReadingStatus> {
+        validate_request(book, &request)?;
+
+        conn.transaction(|conn| {
+            let existing = reading_status::table
+                .filter(reading_status::book_id.eq(book.id))
+                .filter(reading_status::deleted_at.is_null())
+                .first::<ReadingStatus>(conn)
+                .optional()?;
+
+            let current = match existing {
+                Some(status) => status,
+                None => diesel::insert_into(reading_status::table)
+                    .values(&NewReadingStatus {
+                        book_id: book.id,
+                        status: "to_read".to_string(),
+                        rating: None,
+                        start_date: None,
+                        finish_date: None,
+                    })
+                    .returning(ReadingStatus::as_returning())
+                    .get_result(conn)?,
+            };
+
+            let changes = UpdateReadingStatus {
+                status: request.status,
+                rating: request.rating,
+                start_date: request.start_date,
+                finish_date: request.finish_date,
+                current_page: request.current_page,
+                reading_progress: None,
+                listened_minutes: request.listened_minutes,
+                current_location: request.current_location,
+            };
+
+            let mut updated = diesel::update(reading_status::table.find(current.id))
+                .set((&changes, reading_status::updated_at.eq(Some(Utc::now()))))
+                .returning(ReadingStatus::as_returning())
+                .get_result::<ReadingStatus>(conn)?;
+
+            let explicit = request
+                .reading_progress
+                .or_else(|| updated.reading_progress.as_ref().and_then(|p| p.to_f64()));
+            let (marker, total) = progress_inputs(book, &updated);
+            let progress = compute_progress(&updated.status, marker, total, explicit)
+                .and_then(BigDecimal::from_f64)
+                .map(|p| p.with_scale(2));
+
+            if progress != updated.reading_progress {
+                updated = diesel::update(reading_status::table.find(updated.id))
+                    .set(reading_status::reading_progress.eq(progress))
+                    .returning(ReadingStatus::as_returning())
+                    .get_result(conn)?;
+            }
+
+            Ok(updated)
+        })
+    }
+
+    /// Converts to a response enriched with the book's format-specific totals
+    pub fn to_response(&self, book: &Book) -> ReadingStatusResponse {
+        ReadingStatusResponse {
+            id: self.id,
+            book_id: self.book_id,
+            status: self.status.clone(),
+            format: book.format(),
+            rating: self.rating,
+            start_date: self.start_date,
+            finish_date: self.finish_date,
+            current_page: self.current_page,
+            page_count: book.page_count,
+            listened_minutes: self.listened_minutes,
+            duration_minutes: book.duration_minutes,
+            current_location: self.current_location,
+            total_locations: book.total_locations,
+            reading_progress: self.reading_progress.as_ref().and_then(|p| p.to_f64()),
+            updated_at: self.updated_at,
+        }
+    }
+}
+
+/// Validates a status update against the book's format and totals
+fn validate_request(book: &Book, request: &UpdateReadingStatusRequest) -> Result<()> {
+    if let Some(ref status) = request.status {
+        if !READING_STATUSES.contains(&status.as_str()) {
+            return Err(AppError::ValidationError(format!(
+                "Invalid status '{}', expected one of: {}",
+                status,
+                READING_STATUSES.join(", ")
+            )));
+        }
+    }
+    if request.rating.is_some_and(|r| !(1..=5).contains(&r)) {
+        return Err(AppError::ValidationError("Rating must be between 1 and 5".to_string()));
+    }
+    if request.reading_progress.is_some_and(|p| !(0.0..=100.0).contains(&p)) {
+        return Err(AppError::ValidationError("Reading progress must be between 0 and 100".to_string()));
+    }
+
+    let checks = [
+        ("Current page", request.current_page, book.page_count, "page count"),
+        ("Listened minutes", request.listened_minutes, book.duration_minutes, "duration"),
+        ("Current location", request.current_location, book.total_locations, "total locations"),
+    ];
+    for (label, value, total, total_label) in checks {
+        if let Some(value) = value {
+            if value < 0 {
+                return Err(AppError::ValidationError(format!("{} cannot be negative", label)));
+            }
+            if let Some(total) = total {
+                if value > total {
+                    return Err(AppError::ValidationError(format!(
+                        "{} {} exceeds the book's {} of {}",
+                        label, value, total_label, total
+                    )));
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_progress_from_marker() {
+        assert_eq!(compute_progress("reading", Some(104), Some(416), None), Some(25.0));
+        assert_eq!(compute_progress("reading", Some(300), Some(720), None), Some(41.67));
+        // Derived progress takes precedence over an explicit value
+        assert_eq!(compute_progress("reading", Some(2700), Some(5400), Some(10.0)), Some(50.0));
+    }
+
+    #[test]
+    fn test_compute_progress_fallbacks() {
+        // Missing totals use the explicit percentage
+        assert_eq!(compute_progress("reading", None, None, Some(33.3)), Some(33.3));
+        // Unknown totals and no explicit value yield no progress
+        assert_eq!(compute_progress("reading", Some(10), None, None), None);
+        // Completed is always 100%
+        assert_eq!(compute_progress("completed", Some(10), Some(720), None), Some(100.0));
+    }
+
+    #[test]
+    fn test_book_format_parsing() {
+        assert_eq!("audiobook".parse::<BookFormat>().unwrap(), BookFormat::Audiobook);
+        assert_eq!(BookFormat::Ebook.as_str(), "ebook");
+        assert!(BookFormat::Hardcover.is_paper());
+        assert!("cassette".parse::<BookFormat>().is_err());
+    }
+}
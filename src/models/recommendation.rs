@@ -0,0 +1,300 @@
+use std::collections::{HashMap, HashSet};
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::Serialize;
+use utoipa::ToSchema;
+use crate::db::schema::{book_categories, book_tags, books, categories, note_tags, reading_notes, reading_status, tags};
+use crate::errors::{AppError, Result};
+
+/// Weight of tag overlap (Jaccard index of book and note tags)
+const TAG_WEIGHT: f64 = 3.0;
+/// Weight of category overlap (Jaccard index)
+const CATEGORY_WEIGHT: f64 = 2.0;
+/// Bonus for books by the same author
+const AUTHOR_WEIGHT: f64 = 1.5;
+/// Weight of rating closeness when both books are rated
+const RATING_WEIGHT: f64 = 1.0;
+/// Finished books rated at least this high seed "what to read next"
+const SEED_MIN_RATING: i32 = 4;
+
+/// A book related to another one
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SimilarBook {
+    #[schema(example = 2)]
+    pub book_id: i64,
+
+    #[schema(example = "Java Concurrency in Practice")]
+    pub title: String,
+
+    #[schema(example = "Brian Goetz")]
+    pub author: String,
+
+    /// Similarity score, higher is more similar
+    #[schema(example = 2.75)]
+    pub score: f64,
+
+    /// Tags both books carry, directly or on their notes
+    #[schema(example = json!(["java", "concurrency"]))]
+    pub shared_tags: Vec<String>,
+
+    #[schema(example = json!(["Programming"]))]
+    pub shared_categories: Vec<String>,
+
+    #[schema(example = false)]
+    pub same_author: bool,
+}
+
+/// A suggestion from the to-read pile
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Recommendation {
+    #[schema(example = 5)]
+    pub book_id: i64,
+
+    #[schema(example = "Effective Java")]
+    pub title: String,
+
+    #[schema(example = "Joshua Bloch")]
+    pub author: String,
+
+    /// Sum of similarities to highly rated finished books, weighted by rating
+    #[schema(example = 4.5)]
+    pub score: f64,
+
+    /// Titles of the finished books that contributed most to the score
+    #[schema(example = json!(["Java Concurrency in Practice"]))]
+    pub because_you_liked: Vec<String>,
+}
+
+/// Features of a book used for scoring
+#[derive(Debug, Default, Clone)]
+pub struct BookFeatures {
+    pub id: i64,
+    pub title: String,
+    pub author: String,
+    pub tags: HashSet<i64>,
+    pub categories: HashSet<i64>,
+    pub rating: Option<i32>,
+    pub status: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// Features of all active books plus tag and category names
+struct Library {
+    books: Vec<BookFeatures>,
+    tag_names: HashMap<i64, String>,
+    category_names: HashMap<i64, String>,
+}
+
+/// Jaccard index of two sets, 0 when both are empty
+fn jaccard(a: &HashSet<i64>, b: &HashSet<i64>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f64 / union as f64
+}
+
+/// Scores how similar two books are
+///
+/// Combines tag and category overlap, a shared author and, when both
+/// books are rated, how close their ratings are.
+pub fn similarity(a: &BookFeatures, b: &BookFeatures) -> f64 {
+    let mut score = TAG_WEIGHT * jaccard(&a.tags, &b.tags) + CATEGORY_WEIGHT * jaccard(&a.categories, &b.categories);
+
+    if normalize_author(&a.author) == normalize_author(&b.author) {
+        score += AUTHOR_WEIGHT;
+    }
+    if let (Some(ra), Some(rb)) = (a.rating, b.rating) {
+        // Only rewards closeness when there is some other overlap
+        if score > 0.0 {
+            score += RATING_WEIGHT * (1.0 - (ra - rb).abs() as f64 / 4.0);
+        }
+    }
+
+    (score * 100.0).round() / 100.0
+}
+
+fn normalize_author(author: &str) -> String {
+    author.trim().to_lowercase()
+}
+
+/// Loads scoring features for every active book
+fn load_library(conn: &mut PgConnection) -> Result<Library> {
+    let mut books: HashMap<i64, BookFeatures> = books::table
+        .filter(books::deleted_at.is_null())
+        .select((books::id, books::title, books::author, books::created_at))
+        .load::<(i64, String, String, Option<DateTime<Utc>>)>(conn)?
+        .into_iter()
+        .map(|(id, title, author, created_at)| {
+            (id, BookFeatures { id, title, author, created_at, ..Default::default() })
+        })
+        .collect();
+
+    let mut tag_names = HashMap::new();
+    let direct_tags = book_tags::table
+        .inner_join(tags::table)
+        .filter(book_tags::deleted_at.is_null())
+        .filter(tags::deleted_at.is_null())
+        .select((book_tags::book_id, tags::id, tags::name))
+        .load::<(i64, i64, String)>(conn)?;
+    let note_tag_rows = note_tags::table
+        .inner_join(reading_notes::table)
+        .inner_join(tags::table)
+        .filter(note_tags::deleted_at.is_null())
+        .filter(reading_notes::deleted_at.is_null())
+        .filter(tags::deleted_at.is_null())
+        .select((reading_notes::book_id, tags::id, tags::name))
+        .load::<(i64, i64, String)>(conn)?;
+    for (book_id, tag_id, name) in direct_tags.into_iter().chain(note_tag_rows) {
+        if let Some(book) = books.get_mut(&book_id) {
+            book.tags.insert(tag_id);
+        }
+        tag_names.insert(tag_id, name);
+    }
+
+    let mut category_names = HashMap::new();
+    let category_rows = book_categories::table
+        .inner_join(categories::table)
+        .filter(book_categories::deleted_at.is_null())
+        .filter(categories::deleted_at.is_null())
+        .select((book_categories::book_id, categories::id, categories::name))
+        .load::<(i64, i64, String)>(conn)?;
+    for (book_id, category_id, name) in category_rows {
+        if let Some(book) = books.get_mut(&book_id) {
+            book.categories.insert(category_id);
+        }
+        category_names.insert(category_id, name);
+    }
+
+    let statuses = reading_status::table
+        .filter(reading_status::deleted_at.is_null())
+        .select((reading_status::book_id, reading_status::status, reading_status::rating))
+        .load::<(i64, String, Option<i32>)>(conn)?;
+    for (book_id, status, rating) in statuses {
+        if let Some(book) = books.get_mut(&book_id) {
+            book.status = Some(status);
+            book.rating = rating;
+        }
+    }
+
+    Ok(Library {
+        books: books.into_values().collect(),
+        tag_names,
+        category_names,
+    })
+}
+
+/// Names for a set of ids, sorted
+fn names(ids: impl Iterator<Item = i64>, lookup: &HashMap<i64, String>) -> Vec<String> {
+    let mut names: Vec<String> = ids.filter_map(|id| lookup.get(&id).cloned()).collect();
+    names.sort();
+    names
+}
+
+/// Ranks other active books by similarity to the given book
+pub fn similar_books(conn: &mut PgConnection, book_id: i64, limit: usize) -> Result<Vec<SimilarBook>> {
+    let library = load_library(conn)?;
+    let target = library
+        .books
+        .iter()
+        .find(|b| b.id == book_id)
+        .ok_or_else(|| AppError::NotFound(format!("Book with id {} not found", book_id)))?;
+
+    let mut similar: Vec<SimilarBook> = library
+        .books
+        .iter()
+        .filter(|b| b.id != book_id)
+        .filter_map(|other| {
+            let score = similarity(target, other);
+            (score > 0.0).then(|| SimilarBook {
+                book_id: other.id,
+                title: other.title.clone(),
+                author: other.author.clone(),
+                score,
+                shared_tags: names(target.tags.intersection(&other.tags).copied(), &library.tag_names),
+                shared_categories: names(
+                    target.categories.intersection(&other.categories).copied(),
+                    &library.category_names,
+                ),
+                same_author: normalize_author(&target.author) == normalize_author(&other.author),
+            })
+        })
+        .collect();
+
+    similar.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.book_id.cmp(&b.book_id)));
+    similar.truncate(limit);
+    Ok(similar)
+}
+
+/// Suggests books from the to-read pile based on highly rated finished books
+///
+/// Without any highly rated books, the oldest entries in the pile come first.
+pub fn next_reads(conn: &mut PgConnection, limit: usize) -> Result<Vec<Recommendation>> {
+    let library = load_library(conn)?;
+    let seeds: Vec<&BookFeatures> = library
+        .books
+        .iter()
+        .filter(|b| b.status.as_deref() == Some("completed"))
+        .filter(|b| b.rating.is_some_and(|r| r >= SEED_MIN_RATING))
+        .collect();
+
+    let mut candidates: Vec<(Recommendation, Option<DateTime<Utc>>)> = library
+        .books
+        .iter()
+        .filter(|b| b.status.as_deref() == Some("to_read"))
+        .map(|candidate| {
+            let mut contributions: Vec<(f64, &str)> = seeds
+                .iter()
+                .map(|seed| {
+                    // A 5-star book counts twice as much as a 4-star one
+                    let weight = (seed.rating.unwrap_or(SEED_MIN_RATING) - SEED_MIN_RATING + 1) as f64;
+                    (weight * similarity(seed, candidate), seed.title.as_str())
+                })
+                .filter(|(score, _)| *score > 0.0)
+                .collect();
+            contributions.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+            let score = contributions.iter().map(|(s, _)| s).sum::<f64>();
+            let recommendation = Recommendation {
+                book_id: candidate.id,
+                title: candidate.title.clone(),
+                author: candidate.author.clone(),
+                score: (score * 100.0).round() / 100.0,
+                because_you_liked: contributions.iter().take(3).map(|(_, t)| t.to_string()).collect(),
+            };
+            (recommendation, candidate.created_at)
+        })
+        .collect();
+
+    candidates.sort_by(|(a, a_created), (b, b_created)| {
+        b.score.total_cmp(&a.score).then(a_created.cmp(b_created))
+    });
+    Ok(candidates.into_iter().take(limit).map(|(r, _)| r).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn book(author: &str, tags: &[i64], categories: &[i64], rating: Option<i32>) -> BookFeatures {
+        BookFeatures {
+            author: author.to_string(),
+            tags: tags.iter().copied().collect(),
+            categories: categories.iter().copied().collect(),
+            rating,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_similarity_components() {
+        let a = book("Joshua Bloch", &[1, 2], &[10], Some(5));
+
+        // Half the tags and all categories shared, same rating
+        assert_eq!(similarity(&a, &book("Brian Goetz", &[1, 3], &[10], Some(5))), 1.0 + 2.0 + 1.0);
+        // Same author only, ratings far apart
+        assert_eq!(similarity(&a, &book(" joshua bloch ", &[], &[], Some(1))), 1.5);
+        // Nothing in common, a matching rating alone does not count
+        assert_eq!(similarity(&a, &book("Someone Else", &[4], &[11], Some(5))), 0.0);
+    }
+}
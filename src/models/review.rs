@@ -0,0 +1,276 @@
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use crate::db::schema::{note_reviews, reading_notes};
+use crate::errors::{AppError, Result};
+use crate::models::note::{NoteResponse, ReadingNote};
+
+/// Ease factor of a note that has never been reviewed
+pub const INITIAL_EASE: f64 = 2.5;
+/// Lowest ease factor, so that difficult notes still come back less often over time
+pub const MIN_EASE: f64 = 1.3;
+
+/// How well a note was remembered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewGrade {
+    /// Forgotten, start over
+    Again,
+    Hard,
+    Good,
+    Easy,
+}
+
+impl ReviewGrade {
+    /// Returns the value stored in the `note_reviews.last_grade` column
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReviewGrade::Again => "again",
+            ReviewGrade::Hard => "hard",
+            ReviewGrade::Good => "good",
+            ReviewGrade::Easy => "easy",
+        }
+    }
+
+    /// SM-2 response quality from 0 to 5
+    fn quality(&self) -> f64 {
+        match self {
+            ReviewGrade::Again => 1.0,
+            ReviewGrade::Hard => 3.0,
+            ReviewGrade::Good => 4.0,
+            ReviewGrade::Easy => 5.0,
+        }
+    }
+}
+
+/// Review schedule database model
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Selectable, Identifiable, Insertable, AsChangeset)]
+#[diesel(table_name = note_reviews)]
+#[diesel(primary_key(note_id))]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct NoteReview {
+    pub note_id: i64,
+    pub ease_factor: f64,
+    pub interval_days: i32,
+    pub repetitions: i32,
+    pub due_date: NaiveDate,
+    pub last_grade: Option<String>,
+    pub last_reviewed_at: Option<DateTime<Utc>>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Request structure for grading a review
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct ReviewRequest {
+    #[schema(example = "good")]
+    pub grade: ReviewGrade,
+}
+
+/// Review schedule of a note
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReviewStateResponse {
+    #[schema(example = 1)]
+    pub note_id: i64,
+
+    #[schema(example = 2.5)]
+    pub ease_factor: f64,
+
+    /// Days until the next review
+    #[schema(example = 6)]
+    pub interval_days: i32,
+
+    /// Successful reviews in a row
+    #[schema(example = 2)]
+    pub repetitions: i32,
+
+    #[schema(value_type = String, format = Date, example = "2024-01-07")]
+    pub due_date: NaiveDate,
+
+    #[schema(example = "good")]
+    pub last_grade: Option<String>,
+
+    #[schema(example = "2024-01-01T12:00:00Z")]
+    pub last_reviewed_at: Option<DateTime<Utc>>,
+}
+
+impl From<NoteReview> for ReviewStateResponse {
+    fn from(review: NoteReview) -> Self {
+        Self {
+            note_id: review.note_id,
+            ease_factor: review.ease_factor,
+            interval_days: review.interval_days,
+            repetitions: review.repetitions,
+            due_date: review.due_date,
+            last_grade: review.last_grade,
+            last_reviewed_at: review.last_reviewed_at,
+        }
+    }
+}
+
+/// Note due for review with its schedule, which is absent for new notes
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DueNoteResponse {
+    pub note: NoteResponse,
+    pub review: Option<ReviewStateResponse>,
+}
+
+/// Notes due for review
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DueReviewResponse {
+    pub notes: Vec<DueNoteResponse>,
+
+    /// Number of due notes, including those beyond the limit
+    #[schema(example = 42)]
+    pub total: i64,
+}
+
+/// Note with its review schedule, `None` for notes never reviewed
+pub type DueNote = (ReadingNote, Option<NoteReview>);
+
+/// Which notes take part in reviews
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ReviewScope {
+    pub quotes_only: bool,
+    pub favorites_only: bool,
+}
+
+impl NoteReview {
+    /// Schedule of a note that has not been reviewed yet
+    pub fn new(note_id: i64, today: NaiveDate) -> NoteReview {
+        NoteReview {
+            note_id,
+            ease_factor: INITIAL_EASE,
+            interval_days: 0,
+            repetitions: 0,
+            due_date: today,
+            last_grade: None,
+            last_reviewed_at: None,
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    /// Applies a grade following SM-2
+    ///
+    /// Failed reviews restart the repetitions with a one day interval.
+    /// Successful ones wait 1 day, then 6 days, then the previous interval
+    /// times the ease factor. The ease factor moves with the grade but
+    /// never drops below 1.3.
+    pub fn graded(mut self, grade: ReviewGrade, today: NaiveDate) -> NoteReview {
+        let quality = grade.quality();
+
+        if quality < 3.0 {
+            self.repetitions = 0;
+            self.interval_days = 1;
+        } else {
+            self.repetitions += 1;
+            self.interval_days = match self.repetitions {
+                1 => 1,
+                2 => 6,
+                _ => (self.interval_days as f64 * self.ease_factor).round() as i32,
+            };
+        }
+
+        let ease = self.ease_factor + 0.1 - (5.0 - quality) * (0.08 + (5.0 - quality) * 0.02);
+        self.ease_factor = (ease.max(MIN_EASE) * 100.0).round() / 100.0;
+        self.due_date = today
+            .checked_add_days(Days::new(self.interval_days as u64))
+            .unwrap_or(NaiveDate::MAX);
+        self.last_grade = Some(grade.as_str().to_string());
+        self.last_reviewed_at = Some(Utc::now());
+        self
+    }
+
+    /// Lists notes due on `today`, overdue notes first, then new notes
+    pub fn due(
+        conn: &mut PgConnection,
+        scope: ReviewScope,
+        today: NaiveDate,
+        limit: i64,
+    ) -> Result<(Vec<DueNote>, i64)> {
+        let due_query = || {
+            let mut query = reading_notes::table
+                .left_join(note_reviews::table)
+                .filter(reading_notes::deleted_at.is_null())
+                .filter(note_reviews::due_date.is_null().or(note_reviews::due_date.le(today)))
+                .into_boxed();
+            if scope.quotes_only {
+                query = query.filter(reading_notes::note_type.eq("quote"));
+            }
+            if scope.favorites_only {
+                query = query.filter(reading_notes::is_favorite.eq(true));
+            }
+            query
+        };
+
+        let notes = due_query()
+            .order((note_reviews::due_date.asc().nulls_last(), reading_notes::created_at.asc()))
+            .select((ReadingNote::as_select(), Option::<NoteReview>::as_select()))
+            .limit(limit)
+            .load::<DueNote>(conn)?;
+
+        let total = due_query().count().get_result::<i64>(conn)?;
+
+        Ok((notes, total))
+    }
+
+    /// Records a review of a note and schedules the next one
+    pub fn record(conn: &mut PgConnection, note_id: i64, grade: ReviewGrade, today: NaiveDate) -> Result<NoteReview> {
+        ReadingNote::find_by_id(conn, note_id)?;
+
+        conn.transaction(|conn| {
+            let current = note_reviews::table
+                .find(note_id)
+                .select(NoteReview::as_select())
+                .for_update()
+                .first(conn)
+                .optional()?
+                .unwrap_or_else(|| NoteReview::new(note_id, today));
+            let next = current.graded(grade, today);
+
+            diesel::insert_into(note_reviews::table)
+                .values(&next)
+                .on_conflict(note_reviews::note_id)
+                .do_update()
+                .set(&next)
+                .returning(NoteReview::as_returning())
+                .get_result(conn)
+                .map_err(AppError::from)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sm2_schedule() {
+        let today = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+
+        let review = NoteReview::new(1, today).graded(ReviewGrade::Good, today);
+        assert_eq!((review.repetitions, review.interval_days), (1, 1));
+        assert_eq!(review.ease_factor, 2.5);
+
+        let review = review.graded(ReviewGrade::Good, today);
+        assert_eq!(review.interval_days, 6);
+        assert_eq!(review.due_date, NaiveDate::from_ymd_opt(2024, 1, 7).unwrap());
+
+        let review = review.graded(ReviewGrade::Easy, today);
+        assert_eq!(review.ease_factor, 2.6);
+        assert_eq!(review.interval_days, 15);
+
+        let review = review.graded(ReviewGrade::Again, today);
+        assert_eq!((review.repetitions, review.interval_days), (0, 1));
+        assert_eq!(review.ease_factor, 2.06);
+        assert_eq!(review.last_grade.as_deref(), Some("again"));
+
+        let mut review = review;
+        for _ in 0..10 {
+            review = review.graded(ReviewGrade::Hard, today);
+        }
+        assert_eq!(review.ease_factor, MIN_EASE);
+    }
+}
@@ -0,0 +1,348 @@
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use crate::db::schema::share_links;
+use crate::errors::{AppError, Result};
+use crate::models::attachment::NoteAttachment;
+use crate::models::book::Book;
+use crate::models::note::ReadingNote;
+use crate::utils::markdown;
+
+/// Read-only link to a note or to all notes of a book
+#[derive(Debug, Queryable, Selectable, Identifiable)]
+#[diesel(table_name = share_links)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ShareLink {
+    pub id: i64,
+    pub token: String,
+    pub note_id: Option<i64>,
+    pub book_id: Option<i64>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// New share link for insertion
+#[derive(Debug, Insertable)]
+#[diesel(table_name = share_links)]
+pub struct NewShareLink {
+    pub token: String,
+    pub note_id: Option<i64>,
+    pub book_id: Option<i64>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Request structure for sharing a note or book
+#[derive(Debug, Default, Deserialize, Serialize, ToSchema)]
+pub struct CreateShareLinkRequest {
+    /// When the link stops working (default: never)
+    #[schema(example = "2024-02-01T00:00:00Z")]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Response structure for a share link
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ShareLinkResponse {
+    #[schema(example = 1)]
+    pub id: i64,
+
+    #[schema(example = "3f2a9c0d4b8e4f6a9d1c2b3a4e5f60718293a4b5c6d7e8f90a1b2c3d4e5f6a7b")]
+    pub token: String,
+
+    /// Public path of the shared page
+    #[schema(example = "/s/3f2a9c0d4b8e4f6a9d1c2b3a4e5f60718293a4b5c6d7e8f90a1b2c3d4e5f6a7b")]
+    pub url: String,
+
+    /// The shared note, for a single-note link
+    #[schema(example = 1)]
+    pub note_id: Option<i64>,
+
+    /// The shared book, for a link to all of its notes
+    #[schema(example = json!(null))]
+    pub book_id: Option<i64>,
+
+    #[schema(example = "2024-02-01T00:00:00Z")]
+    pub expires_at: Option<DateTime<Utc>>,
+
+    #[schema(example = false)]
+    pub is_expired: bool,
+
+    #[schema(example = "2024-01-01T12:00:00Z")]
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// Book of shared notes
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SharedBook {
+    #[schema(example = "The Great Gatsby")]
+    pub title: String,
+
+    #[schema(example = "F. Scott Fitzgerald")]
+    pub author: String,
+}
+
+/// Note as seen through a share link
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SharedNote {
+    #[schema(example = "Chapter 1 Summary")]
+    pub title: Option<String>,
+
+    #[schema(example = "Nick Carraway introduces himself...")]
+    pub content: String,
+
+    /// Sanitized HTML rendering of the content, with images served through the link
+    #[schema(example = "<p>Nick Carraway introduces himself...</p>")]
+    pub content_html: String,
+
+    #[schema(example = "summary")]
+    pub note_type: Option<String>,
+
+    #[schema(example = "p. 1-15")]
+    pub page_reference: Option<String>,
+
+    #[schema(example = json!(["important", "chapter1"]))]
+    pub tags: Vec<String>,
+
+    #[schema(example = "2024-01-01T12:00:00Z")]
+    pub created_at: Option<DateTime<Utc>>,
+
+    #[schema(example = "2024-01-01T12:00:00Z")]
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Public, read-only content of a share link
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SharedContentResponse {
+    pub book: SharedBook,
+
+    /// The shared note, or all active notes of the shared book
+    pub notes: Vec<SharedNote>,
+
+    #[schema(example = "2024-02-01T00:00:00Z")]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl ShareLink {
+    /// Creates a link to a single note
+    pub fn create_for_note(conn: &mut PgConnection, note_id: i64, request: CreateShareLinkRequest) -> Result<ShareLink> {
+        ReadingNote::find_by_id(conn, note_id)?;
+        Self::create(conn, Some(note_id), None, request)
+    }
+
+    /// Creates a link to all notes of a book
+    pub fn create_for_book(conn: &mut PgConnection, book_id: i64, request: CreateShareLinkRequest) -> Result<ShareLink> {
+        Book::find_by_id(conn, book_id)?;
+        Self::create(conn, None, Some(book_id), request)
+    }
+
+    fn create(
+        conn: &mut PgConnection,
+        note_id: Option<i64>,
+        book_id: Option<i64>,
+        request: CreateShareLinkRequest,
+    ) -> Result<ShareLink> {
+        if request.expires_at.is_some_and(|expires_at| expires_at <= Utc::now()) {
+            return Err(AppError::ValidationError("Expiry must be in the future".to_string()));
+        }
+
+        let new_link = NewShareLink {
+            token: generate_token(),
+            note_id,
+            book_id,
+            expires_at: request.expires_at,
+        };
+        diesel::insert_into(share_links::table)
+            .values(&new_link)
+            .returning(ShareLink::as_returning())
+            .get_result(conn)
+            .map_err(AppError::from)
+    }
+
+    /// Lists share links, newest first, optionally only those of a note or book
+    pub fn list(conn: &mut PgConnection, note_id: Option<i64>, book_id: Option<i64>) -> Result<Vec<ShareLink>> {
+        let mut query = share_links::table.into_boxed();
+        if let Some(note_id) = note_id {
+            query = query.filter(share_links::note_id.eq(note_id));
+        }
+        if let Some(book_id) = book_id {
+            query = query.filter(share_links::book_id.eq(book_id));
+        }
+
+        query
+            .order((share_links::created_at.desc(), share_links::id.desc()))
+            .select(ShareLink::as_select())
+            .load(conn)
+            .map_err(AppError::from)
+    }
+
+    /// Revokes a share link; its token stops working immediately
+    pub fn revoke(conn: &mut PgConnection, link_id: i64) -> Result<()> {
+        let deleted = diesel::delete(share_links::table.find(link_id)).execute(conn)?;
+        if deleted == 0 {
+            return Err(AppError::NotFound(format!("Share link with id {} not found", link_id)));
+        }
+        Ok(())
+    }
+
+    /// Finds the link of a token that has not expired
+    ///
+    /// Unknown and expired tokens are reported alike.
+    pub fn find_by_token(conn: &mut PgConnection, token: &str, now: DateTime<Utc>) -> Result<ShareLink> {
+        share_links::table
+            .filter(share_links::token.eq(token))
+            .select(ShareLink::as_select())
+            .first(conn)
+            .optional()?
+            .filter(|link| !link.is_expired_at(now))
+            .ok_or_else(|| AppError::NotFound("Share link not found".to_string()))
+    }
+
+    /// Whether the link has expired at the given time
+    pub fn is_expired_at(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+
+    /// Loads the shared note or book
+    ///
+    /// A trashed note or book is not shown, without revealing what the
+    /// link pointed at.
+    pub fn shared_content(&self, conn: &mut PgConnection) -> Result<SharedContentResponse> {
+        let not_found = |_| AppError::NotFound("Share link not found".to_string());
+
+        let (book, notes) = match (self.note_id, self.book_id) {
+            (Some(note_id), _) => {
+                let note = ReadingNote::find_by_id(conn, note_id).map_err(not_found)?;
+                let book = Book::find_by_id(conn, note.book_id).map_err(not_found)?;
+                (book, vec![note])
+            }
+            (None, Some(book_id)) => {
+                let book = Book::find_by_id(conn, book_id).map_err(not_found)?;
+                let notes = ReadingNote::in_manual_order(conn, book_id)?;
+                (book, notes)
+            }
+            (None, None) => return Err(AppError::InternalError),
+        };
+
+        let attachment_base = format!("{}/attachments", self.url());
+        let notes = notes
+            .into_iter()
+            .map(|note| {
+                Ok(SharedNote {
+                    tags: note.get_tags(conn)?,
+                    content_html: markdown::render_html_with_attachment_base(&note.content, &attachment_base),
+                    title: note.title,
+                    content: note.content,
+                    note_type: note.note_type,
+                    page_reference: note.page_reference,
+                    created_at: note.created_at,
+                    updated_at: note.updated_at,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(SharedContentResponse {
+            book: SharedBook { title: book.title, author: book.author },
+            notes,
+            expires_at: self.expires_at,
+        })
+    }
+
+    /// Finds an attachment of a note the link gives access to
+    ///
+    /// Attachments of other notes are reported as missing.
+    pub fn attachment(&self, conn: &mut PgConnection, attachment_id: i64) -> Result<NoteAttachment> {
+        let not_found = || AppError::NotFound(format!("Attachment with id {} not found", attachment_id));
+
+        let attachment = NoteAttachment::find_by_id(conn, attachment_id)?;
+        let note = ReadingNote::find_by_id(conn, attachment.note_id)?;
+        let shared = match (self.note_id, self.book_id) {
+            (Some(note_id), _) => note.id == note_id,
+            (None, Some(book_id)) => note.book_id == book_id,
+            (None, None) => false,
+        };
+        if !shared {
+            return Err(not_found());
+        }
+
+        Book::find_by_id(conn, note.book_id).map_err(|_| not_found())?;
+        Ok(attachment)
+    }
+
+    /// Public path of the shared page
+    pub fn url(&self) -> String {
+        format!("/s/{}", self.token)
+    }
+
+    pub fn to_response(&self, now: DateTime<Utc>) -> ShareLinkResponse {
+        ShareLinkResponse {
+            id: self.id,
+            token: self.token.clone(),
+            url: self.url(),
+            note_id: self.note_id,
+            book_id: self.book_id,
+            expires_at: self.expires_at,
+            is_expired: self.is_expired_at(now),
+            created_at: self.created_at,
+        }
+    }
+}
+
+impl SharedContentResponse {
+    /// Renders the shared notes as a standalone HTML page
+    pub fn to_html(&self) -> String {
+        let title = match self.notes.as_slice() {
+            [note] => note.title.as_deref().unwrap_or(&self.book.title),
+            _ => &self.book.title,
+        };
+
+        let mut html = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+             <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+             <meta name=\"robots\" content=\"noindex\">\n<title>{}</title>\n</head>\n<body>\n\
+             <header>\n<h1>{}</h1>\n<p>{}</p>\n</header>\n",
+            ammonia::clean_text(title),
+            ammonia::clean_text(&self.book.title),
+            ammonia::clean_text(&self.book.author),
+        );
+        for note in &self.notes {
+            html.push_str("<article>\n");
+            if let Some(title) = &note.title {
+                html.push_str(&format!("<h2>{}</h2>\n", ammonia::clean_text(title)));
+            }
+            if let Some(page_reference) = &note.page_reference {
+                html.push_str(&format!("<p class=\"location\">{}</p>\n", ammonia::clean_text(page_reference)));
+            }
+            html.push_str(&note.content_html);
+            html.push_str("\n</article>\n");
+        }
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+}
+
+/// Generates an unguessable token from two random UUIDs
+fn generate_token() -> String {
+    format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_html_escapes_text() {
+        let content = SharedContentResponse {
+            book: SharedBook { title: "<script>alert(1)</script>".to_string(), author: "A & B".to_string() },
+            notes: vec![],
+            expires_at: None,
+        };
+        let html = content.to_html();
+        assert!(!html.contains("<script>"));
+        assert!(html.contains("&amp;"));
+
+        let token = generate_token();
+        assert_eq!(token.len(), 64);
+        assert_ne!(token, generate_token());
+    }
+}
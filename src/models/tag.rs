@@ -2,8 +2,10 @@ use chrono::{DateTime, Utc};
 use diesel::prelude::*;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
+use uuid::Uuid;
 use crate::db::schema::{tags, book_tags, note_tags};
 use crate::errors::{AppError, Result};
+use crate::utils::patch::{nullable, required};
 
 /// Tag database model
 #[derive(Debug, Serialize, Deserialize, Queryable, Selectable, Identifiable)]
@@ -16,6 +18,7 @@ pub struct Tag {
     pub usage_count: Option<i32>,
     pub deleted_at: Option<DateTime<Utc>>,
     pub created_at: Option<DateTime<Utc>>,
+    pub deletion_batch_id: Option<Uuid>,
 }
 
 /// New tag for insertion
@@ -36,6 +39,26 @@ pub struct UpdateTag {
     pub slug: Option<String>,
 }
 
+/// Request structure for patching a tag (JSON Merge Patch)
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct PatchTagRequest {
+    /// Cannot be null; the slug follows the name
+    #[serde(default, deserialize_with = "nullable")]
+    #[schema(value_type = Option<String>, example = "Updated Tag")]
+    pub name: Option<Option<String>>,
+}
+
+impl TryFrom<PatchTagRequest> for UpdateTag {
+    type Error = AppError;
+
+    fn try_from(patch: PatchTagRequest) -> Result<Self> {
+        Ok(Self {
+            name: required("name", patch.name)?,
+            slug: None,
+        })
+    }
+}
+
 /// Request structure for creating a new tag
 #[derive(Debug, Deserialize, Serialize, ToSchema)]
 pub struct CreateTagRequest {
@@ -43,6 +66,14 @@ pub struct CreateTagRequest {
     pub name: String,
 }
 
+/// Request structure for merging a tag into another
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct MergeTagRequest {
+    /// Tag that takes over the notes and books of the merged tag
+    #[schema(example = 2)]
+    pub target_id: i64,
+}
+
 /// Response structure for tag
 #[derive(Debug, Serialize, ToSchema)]
 pub struct TagResponse {
@@ -111,7 +142,7 @@ impl From<CreateTagRequest> for NewTag {
 }
 
 /// Convert a string to a URL-safe slug
-fn slugify(s: &str) -> String {
+pub(crate) fn slugify(s: &str) -> String {
     s.to_lowercase()
         .chars()
         .map(|c| if c.is_alphanumeric() { c } else { '-' })
@@ -301,15 +332,136 @@ impl Tag {
             })
     }
 
-    /// Soft deletes a tag
+    /// Soft deletes a tag together with its note and book associations
+    ///
+    /// The associations share the tag's deletion batch id, so that
+    /// `restore` brings back exactly what was deleted with the tag.
     pub fn soft_delete(conn: &mut PgConnection, tag_id: i64) -> Result<()> {
-        let affected = diesel::update(tags::table.find(tag_id))
-            .filter(tags::deleted_at.is_null())
-            .set(tags::deleted_at.eq(Some(Utc::now())))
-            .execute(conn)?;
+        conn.transaction(|conn| {
+            let batch_id = Uuid::now_v7();
+            let now = Utc::now();
+
+            let affected = diesel::update(tags::table.find(tag_id))
+                .filter(tags::deleted_at.is_null())
+                .set((tags::deleted_at.eq(Some(now)), tags::deletion_batch_id.eq(Some(batch_id))))
+                .execute(conn)?;
+
+            if affected == 0 {
+                return Err(AppError::NotFound(format!("Tag with id {} not found", tag_id)));
+            }
+
+            diesel::update(note_tags::table)
+                .filter(note_tags::tag_id.eq(tag_id))
+                .filter(note_tags::deleted_at.is_null())
+                .set((note_tags::deleted_at.eq(Some(now)), note_tags::deletion_batch_id.eq(Some(batch_id))))
+                .execute(conn)?;
+
+            diesel::update(book_tags::table)
+                .filter(book_tags::tag_id.eq(tag_id))
+                .filter(book_tags::deleted_at.is_null())
+                .set((book_tags::deleted_at.eq(Some(now)), book_tags::deletion_batch_id.eq(Some(batch_id))))
+                .execute(conn)?;
+
+            Self::refresh_usage_counts(conn, &[tag_id])
+        })
+    }
+
+    /// Merges a tag into another and soft deletes it
+    ///
+    /// All of the tag's note and book associations, including those of
+    /// trashed notes and books, move to the target. Where an item already
+    /// has the target tag the duplicate association is dropped, keeping an
+    /// active one over a trashed one. Returns the target tag.
+    pub fn merge(conn: &mut PgConnection, source_id: i64, target_id: i64) -> Result<Tag> {
+        if source_id == target_id {
+            return Err(AppError::ValidationError("Cannot merge a tag into itself".to_string()));
+        }
+
+        conn.transaction(|conn| {
+            let source = Self::find_by_id(conn, source_id)?;
+            let target = Self::find_by_id(conn, target_id)?;
+
+            merge_note_tags(conn, source.id, target.id)?;
+            merge_book_tags(conn, source.id, target.id)?;
+
+            Self::soft_delete(conn, source.id)?;
+            target.update_usage_count(conn)?;
+
+            Self::find_by_id(conn, target.id)
+        })
+    }
+
+    /// Lists soft deleted tags, most recently deleted first
+    pub fn list_deleted(conn: &mut PgConnection) -> Result<Vec<Tag>> {
+        tags::table
+            .filter(tags::deleted_at.is_not_null())
+            .order(tags::deleted_at.desc())
+            .load::<Tag>(conn)
+            .map_err(AppError::from)
+    }
+
+    /// Restores a soft deleted tag with the associations deleted with it
+    /// 
+    /// Rejected when an active tag already uses the same slug, since
+    /// slugs are unique among non-deleted tags.
+    pub fn restore(conn: &mut PgConnection, tag_id: i64) -> Result<Tag> {
+        let tag = tags::table
+            .filter(tags::id.eq(tag_id))
+            .filter(tags::deleted_at.is_not_null())
+            .first::<Tag>(conn)
+            .optional()?
+            .ok_or_else(|| AppError::NotFound(format!("Deleted tag with id {} not found", tag_id)))?;
+
+        if let Some(existing) = Self::find_by_slug(conn, &tag.slug)? {
+            return Err(AppError::Conflict(format!(
+                "Cannot restore tag '{}': tag '{}' (id {}) already uses slug '{}'",
+                tag.name, existing.name, existing.id, tag.slug
+            )));
+        }
+
+        conn.transaction(|conn| {
+            let restored = diesel::update(tags::table.find(tag_id))
+                .set((
+                    tags::deleted_at.eq(None::<DateTime<Utc>>),
+                    tags::deletion_batch_id.eq(None::<Uuid>),
+                ))
+                .returning(Tag::as_returning())
+                .get_result(conn)?;
+
+            if let Some(batch_id) = tag.deletion_batch_id {
+                diesel::update(note_tags::table)
+                    .filter(note_tags::deletion_batch_id.eq(batch_id))
+                    .set((
+                        note_tags::deleted_at.eq(None::<DateTime<Utc>>),
+                        note_tags::deletion_batch_id.eq(None::<Uuid>),
+                    ))
+                    .execute(conn)?;
+
+                diesel::update(book_tags::table)
+                    .filter(book_tags::deletion_batch_id.eq(batch_id))
+                    .set((
+                        book_tags::deleted_at.eq(None::<DateTime<Utc>>),
+                        book_tags::deletion_batch_id.eq(None::<Uuid>),
+                    ))
+                    .execute(conn)?;
+            }
+
+            restored.update_usage_count(conn)?;
+            Self::find_by_id(conn, tag_id)
+        })
+    }
+
+    /// Permanently deletes a soft deleted tag and its associations
+    pub fn purge(conn: &mut PgConnection, tag_id: i64) -> Result<()> {
+        let affected = diesel::delete(
+            tags::table
+                .filter(tags::id.eq(tag_id))
+                .filter(tags::deleted_at.is_not_null()),
+        )
+        .execute(conn)?;
 
         if affected == 0 {
-            return Err(AppError::NotFound(format!("Tag with id {} not found", tag_id)));
+            return Err(AppError::NotFound(format!("Deleted tag with id {} not found", tag_id)));
         }
 
         Ok(())
@@ -322,6 +474,7 @@ impl Tag {
         book_tags::table
             .inner_join(books::table)
             .filter(book_tags::tag_id.eq(self.id))
+            .filter(book_tags::deleted_at.is_null())
             .filter(books::deleted_at.is_null())
             .count()
             .get_result(conn)
@@ -335,12 +488,29 @@ impl Tag {
         note_tags::table
             .inner_join(reading_notes::table)
             .filter(note_tags::tag_id.eq(self.id))
+            .filter(note_tags::deleted_at.is_null())
             .filter(reading_notes::deleted_at.is_null())
             .count()
             .get_result(conn)
             .map_err(AppError::from)
     }
 
+    /// Recomputes the usage counts of the given tags
+    ///
+    /// Used after associations are trashed or restored in bulk, which the
+    /// usage count trigger does not see.
+    pub fn refresh_usage_counts(conn: &mut PgConnection, tag_ids: &[i64]) -> Result<()> {
+        let tags = tags::table
+            .filter(tags::id.eq_any(tag_ids))
+            .load::<Tag>(conn)?;
+
+        for tag in tags {
+            tag.update_usage_count(conn)?;
+        }
+
+        Ok(())
+    }
+
     /// Updates the usage count for this tag
     pub fn update_usage_count(&self, conn: &mut PgConnection) -> Result<()> {
         let book_count = self.get_book_count(conn)?;
@@ -355,6 +525,72 @@ impl Tag {
     }
 }
 
+/// Moves a tag's note associations to the target tag
+fn merge_note_tags(conn: &mut PgConnection, source_id: i64, target_id: i64) -> Result<()> {
+    // A trashed duplicate gives way to an active association
+    let active_source_notes = note_tags::table
+        .filter(note_tags::tag_id.eq(source_id))
+        .filter(note_tags::deleted_at.is_null())
+        .select(note_tags::note_id)
+        .load::<i64>(conn)?;
+    diesel::delete(
+        note_tags::table
+            .filter(note_tags::tag_id.eq(target_id))
+            .filter(note_tags::deleted_at.is_not_null())
+            .filter(note_tags::note_id.eq_any(&active_source_notes)),
+    )
+    .execute(conn)?;
+
+    let target_notes = note_tags::table
+        .filter(note_tags::tag_id.eq(target_id))
+        .select(note_tags::note_id)
+        .load::<i64>(conn)?;
+    diesel::delete(
+        note_tags::table
+            .filter(note_tags::tag_id.eq(source_id))
+            .filter(note_tags::note_id.eq_any(&target_notes)),
+    )
+    .execute(conn)?;
+
+    diesel::update(note_tags::table.filter(note_tags::tag_id.eq(source_id)))
+        .set(note_tags::tag_id.eq(target_id))
+        .execute(conn)?;
+    Ok(())
+}
+
+/// Moves a tag's book associations to the target tag
+fn merge_book_tags(conn: &mut PgConnection, source_id: i64, target_id: i64) -> Result<()> {
+    // A trashed duplicate gives way to an active association
+    let active_source_books = book_tags::table
+        .filter(book_tags::tag_id.eq(source_id))
+        .filter(book_tags::deleted_at.is_null())
+        .select(book_tags::book_id)
+        .load::<i64>(conn)?;
+    diesel::delete(
+        book_tags::table
+            .filter(book_tags::tag_id.eq(target_id))
+            .filter(book_tags::deleted_at.is_not_null())
+            .filter(book_tags::book_id.eq_any(&active_source_books)),
+    )
+    .execute(conn)?;
+
+    let target_books = book_tags::table
+        .filter(book_tags::tag_id.eq(target_id))
+        .select(book_tags::book_id)
+        .load::<i64>(conn)?;
+    diesel::delete(
+        book_tags::table
+            .filter(book_tags::tag_id.eq(source_id))
+            .filter(book_tags::book_id.eq_any(&target_books)),
+    )
+    .execute(conn)?;
+
+    diesel::update(book_tags::table.filter(book_tags::tag_id.eq(source_id)))
+        .set(book_tags::tag_id.eq(target_id))
+        .execute(conn)?;
+    Ok(())
+}
+
 /// Converts Tag to TagResponse with usage counts
 impl Tag {
     pub fn to_response(&self, conn: &mut PgConnection) -> Result<TagResponse> {
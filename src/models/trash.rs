@@ -0,0 +1,111 @@
+use std::str::FromStr;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use crate::errors::{AppError, Result};
+use crate::models::book::Book;
+use crate::models::note::ReadingNote;
+use crate::models::tag::Tag;
+
+/// Maximum characters of note content shown as its trash label
+const NOTE_LABEL_LENGTH: usize = 50;
+
+/// Kind of record that can be restored from or purged in the trash
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrashEntity {
+    Books,
+    Notes,
+    Tags,
+}
+
+impl FromStr for TrashEntity {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "books" => Ok(TrashEntity::Books),
+            "notes" => Ok(TrashEntity::Notes),
+            "tags" => Ok(TrashEntity::Tags),
+            _ => Err(AppError::NotFound(format!("Unknown trash entity '{}'", s))),
+        }
+    }
+}
+
+/// A soft deleted record in the trash
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TrashItem {
+    #[schema(example = 1)]
+    pub id: i64,
+
+    /// Display label: book title, note title (or content excerpt) or tag name
+    #[schema(example = "Effective Java")]
+    pub label: String,
+
+    /// Book the item belongs to (notes only)
+    #[schema(example = 1)]
+    pub book_id: Option<i64>,
+
+    #[schema(example = "2024-01-01T12:00:00Z")]
+    pub deleted_at: Option<DateTime<Utc>>,
+
+    /// Deletion batch; records sharing it are restored together with their book
+    #[schema(example = "01890a5d-ac96-774b-bcce-b302099a8057")]
+    pub deletion_batch_id: Option<Uuid>,
+}
+
+/// Trash listing grouped by entity
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TrashResponse {
+    pub books: Vec<TrashItem>,
+    pub notes: Vec<TrashItem>,
+    pub tags: Vec<TrashItem>,
+}
+
+/// Lists everything currently in the trash
+pub fn list(conn: &mut PgConnection) -> Result<TrashResponse> {
+    let books = Book::list_deleted(conn)?
+        .into_iter()
+        .map(|book| TrashItem {
+            id: book.id,
+            label: book.title,
+            book_id: None,
+            deleted_at: book.deleted_at,
+            deletion_batch_id: book.deletion_batch_id,
+        })
+        .collect();
+
+    let notes = ReadingNote::list_deleted(conn)?
+        .into_iter()
+        .map(|note| TrashItem {
+            id: note.id,
+            label: note.title.unwrap_or_else(|| note.content.chars().take(NOTE_LABEL_LENGTH).collect()),
+            book_id: Some(note.book_id),
+            deleted_at: note.deleted_at,
+            deletion_batch_id: note.deletion_batch_id,
+        })
+        .collect();
+
+    let tags = Tag::list_deleted(conn)?
+        .into_iter()
+        .map(|tag| TrashItem {
+            id: tag.id,
+            label: tag.name,
+            book_id: None,
+            deleted_at: tag.deleted_at,
+            deletion_batch_id: None,
+        })
+        .collect();
+
+    Ok(TrashResponse { books, notes, tags })
+}
+
+/// Permanently deletes a record that is in the trash
+pub fn purge(conn: &mut PgConnection, entity: TrashEntity, id: i64) -> Result<()> {
+    match entity {
+        TrashEntity::Books => Book::purge(conn, id),
+        TrashEntity::Notes => ReadingNote::purge(conn, id),
+        TrashEntity::Tags => Tag::purge(conn, id),
+    }
+}
@@ -0,0 +1,124 @@
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Integer, Nullable, Text, Varchar};
+use serde::Serialize;
+use utoipa::ToSchema;
+use crate::errors::Result;
+
+/// How much was written about one book
+#[derive(Debug, Serialize, QueryableByName, ToSchema)]
+pub struct BookWritingStats {
+    #[diesel(sql_type = BigInt)]
+    #[schema(example = 1)]
+    pub book_id: i64,
+
+    #[diesel(sql_type = Varchar)]
+    #[schema(example = "The Great Gatsby")]
+    pub title: String,
+
+    #[diesel(sql_type = BigInt)]
+    #[schema(example = 12)]
+    pub note_count: i64,
+
+    #[diesel(sql_type = BigInt)]
+    #[schema(example = 1840)]
+    pub word_count: i64,
+
+    #[diesel(sql_type = BigInt)]
+    #[schema(example = 9650)]
+    pub char_count: i64,
+
+    #[diesel(sql_type = BigInt)]
+    #[schema(example = 480)]
+    pub reading_time_seconds: i64,
+}
+
+/// How much was written in one month
+#[derive(Debug, Serialize, QueryableByName, ToSchema)]
+pub struct MonthWritingStats {
+    /// Month the notes were created, e.g. "2024-03"
+    #[diesel(sql_type = Text)]
+    #[schema(example = "2024-03")]
+    pub month: String,
+
+    #[diesel(sql_type = BigInt)]
+    #[schema(example = 5)]
+    pub note_count: i64,
+
+    #[diesel(sql_type = BigInt)]
+    #[schema(example = 730)]
+    pub word_count: i64,
+
+    #[diesel(sql_type = BigInt)]
+    #[schema(example = 3900)]
+    pub char_count: i64,
+}
+
+/// Totals over all notes in the report
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct WritingTotals {
+    #[schema(example = 42)]
+    pub note_count: i64,
+
+    #[schema(example = 6120)]
+    pub word_count: i64,
+
+    #[schema(example = 31800)]
+    pub char_count: i64,
+
+    #[schema(example = 1600)]
+    pub reading_time_seconds: i64,
+}
+
+/// Writing statistics over active notes
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WritingStatsResponse {
+    pub totals: WritingTotals,
+
+    /// Books with notes, most words first
+    pub by_book: Vec<BookWritingStats>,
+
+    /// Months with notes, newest first
+    pub by_month: Vec<MonthWritingStats>,
+}
+
+/// Sums note lengths per book and per month of creation
+pub fn writing_report(conn: &mut PgConnection, year: Option<i32>) -> Result<WritingStatsResponse> {
+    let by_book = diesel::sql_query(
+        "SELECT b.id AS book_id, b.title, COUNT(*) AS note_count, \
+                SUM(n.word_count)::BIGINT AS word_count, SUM(n.char_count)::BIGINT AS char_count, \
+                SUM(n.reading_time_seconds)::BIGINT AS reading_time_seconds \
+         FROM reading_notes n \
+         JOIN books b ON b.id = n.book_id \
+         WHERE n.deleted_at IS NULL \
+           AND b.deleted_at IS NULL \
+           AND ($1 IS NULL OR EXTRACT(YEAR FROM n.created_at) = $1) \
+         GROUP BY b.id, b.title \
+         ORDER BY 4 DESC, b.title",
+    )
+    .bind::<Nullable<Integer>, _>(year)
+    .load::<BookWritingStats>(conn)?;
+
+    let by_month = diesel::sql_query(
+        "SELECT to_char(n.created_at, 'YYYY-MM') AS month, COUNT(*) AS note_count, \
+                SUM(n.word_count)::BIGINT AS word_count, SUM(n.char_count)::BIGINT AS char_count \
+         FROM reading_notes n \
+         JOIN books b ON b.id = n.book_id \
+         WHERE n.deleted_at IS NULL \
+           AND b.deleted_at IS NULL \
+           AND n.created_at IS NOT NULL \
+           AND ($1 IS NULL OR EXTRACT(YEAR FROM n.created_at) = $1) \
+         GROUP BY 1 \
+         ORDER BY 1 DESC",
+    )
+    .bind::<Nullable<Integer>, _>(year)
+    .load::<MonthWritingStats>(conn)?;
+
+    let totals = by_book.iter().fold(WritingTotals::default(), |totals, book| WritingTotals {
+        note_count: totals.note_count + book.note_count,
+        word_count: totals.word_count + book.word_count,
+        char_count: totals.char_count + book.char_count,
+        reading_time_seconds: totals.reading_time_seconds + book.reading_time_seconds,
+    });
+
+    Ok(WritingStatsResponse { totals, by_book, by_month })
+}
@@ -0,0 +1,308 @@
+//! Markdown rendering for note content
+//!
+//! Notes are written in CommonMark with tables, footnotes, strikethrough
+//! and task lists. Rendered HTML is sanitized before it leaves the server
+//! and cached by content hash, so unchanged notes are rendered only once.
+
+use std::borrow::Cow;
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex, OnceLock};
+use lru::LruCache;
+use pulldown_cmark::{html, CodeBlockKind, CowStr, Event, Options, Parser, Tag, TagEnd};
+use sha2::{Digest, Sha256};
+use syntect::html::{ClassStyle, ClassedHTMLGenerator};
+use syntect::parsing::SyntaxSet;
+use syntect::util::LinesWithEndings;
+
+/// Maximum number of rendered notes kept in memory
+const CACHE_CAPACITY: usize = 1024;
+/// Maximum length of a plain-text excerpt in characters
+pub const EXCERPT_LENGTH: usize = 200;
+/// Prefix of the CSS classes emitted for highlighted code
+const HIGHLIGHT_CLASS_PREFIX: &str = "hl-";
+/// Prefix added to element ids, so notes cannot clobber ids of the page
+const USER_CONTENT_ID_PREFIX: &str = "user-content-";
+/// URL scheme referencing a note attachment by id, as in `![diagram](attachment:12)`
+pub const ATTACHMENT_URL_SCHEME: &str = "attachment:";
+/// Path under which the API serves attachments
+pub const ATTACHMENT_BASE_URL: &str = "/api/attachments";
+
+/// HTML and plain-text forms of a Markdown document
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenderedMarkdown {
+    /// Sanitized HTML
+    pub html: String,
+    /// Plain-text excerpt without any markup
+    pub excerpt: String,
+}
+
+type RenderCache = Mutex<LruCache<[u8; 32], Arc<RenderedMarkdown>>>;
+
+fn cache() -> &'static RenderCache {
+    static CACHE: OnceLock<RenderCache> = OnceLock::new();
+    CACHE.get_or_init(|| {
+        Mutex::new(LruCache::new(NonZeroUsize::new(CACHE_CAPACITY).expect("capacity is non-zero")))
+    })
+}
+
+fn syntax_set() -> &'static SyntaxSet {
+    static SYNTAX_SET: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAX_SET.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+fn sanitizer() -> &'static ammonia::Builder<'static> {
+    static SANITIZER: OnceLock<ammonia::Builder<'static>> = OnceLock::new();
+    SANITIZER.get_or_init(|| {
+        let mut builder = ammonia::Builder::default();
+        builder
+            .add_tags(["input"])
+            .add_tag_attributes("input", ["checked", "disabled"])
+            .add_tag_attribute_values("input", "type", ["checkbox"])
+            .add_tag_attributes("div", ["class", "id"])
+            .add_tag_attributes("sup", ["class"])
+            .add_tag_attributes("pre", ["class"])
+            .add_tag_attributes("code", ["class"])
+            .add_tag_attributes("span", ["class"])
+            .id_prefix(Some(USER_CONTENT_ID_PREFIX))
+            .attribute_filter(|element, attribute, value| match (element, attribute) {
+                (_, "class") => allowed_classes(element, value),
+                // In-page links follow the prefixed ids, as footnote references do
+                ("a", "href") => match value.strip_prefix('#') {
+                    Some(fragment) if !fragment.starts_with(USER_CONTENT_ID_PREFIX) => {
+                        Some(format!("#{}{}", USER_CONTENT_ID_PREFIX, fragment).into())
+                    }
+                    _ => Some(value.into()),
+                },
+                _ => Some(value.into()),
+            });
+        builder
+    })
+}
+
+/// Keeps only the classes the renderer emits for footnotes and highlighted code
+fn allowed_classes<'a>(element: &str, value: &'a str) -> Option<Cow<'a, str>> {
+    let classes: Vec<&str> = value
+        .split_whitespace()
+        .filter(|class| match element {
+            "div" => *class == "footnote-definition",
+            "sup" => matches!(*class, "footnote-reference" | "footnote-definition-label"),
+            "pre" => *class == "highlight",
+            "code" => class.starts_with("language-"),
+            "span" => class.starts_with(HIGHLIGHT_CLASS_PREFIX),
+            _ => false,
+        })
+        .collect();
+    (!classes.is_empty()).then(|| classes.join(" ").into())
+}
+
+fn parser_options() -> Options {
+    Options::ENABLE_TABLES
+        | Options::ENABLE_FOOTNOTES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+}
+
+/// Renders Markdown to sanitized HTML and a plain-text excerpt
+///
+/// Results are cached by the SHA-256 hash of the content.
+pub fn render(content: &str) -> Arc<RenderedMarkdown> {
+    let key: [u8; 32] = Sha256::digest(content.as_bytes()).into();
+
+    if let Some(rendered) = cache().lock().ok().and_then(|mut cache| cache.get(&key).cloned()) {
+        return rendered;
+    }
+
+    let rendered = Arc::new(RenderedMarkdown {
+        html: render_html(content),
+        excerpt: excerpt(content, EXCERPT_LENGTH),
+    });
+    if let Ok(mut cache) = cache().lock() {
+        cache.put(key, rendered.clone());
+    }
+    rendered
+}
+
+/// Renders Markdown to sanitized HTML without caching
+pub fn render_html(content: &str) -> String {
+    render_html_with_attachment_base(content, ATTACHMENT_BASE_URL)
+}
+
+/// Renders Markdown to sanitized HTML without caching, pointing
+/// `attachment:<id>` references at `<attachment_base>/<id>`
+pub fn render_html_with_attachment_base(content: &str, attachment_base: &str) -> String {
+    let mut events = Vec::new();
+    let mut code_block: Option<(String, String)> = None;
+
+    for event in Parser::new_ext(content, parser_options()) {
+        match event {
+            Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(ref lang)))
+                if syntax_set().find_syntax_by_token(lang).is_some() =>
+            {
+                code_block = Some((lang.to_string(), String::new()));
+            }
+            Event::Text(ref text) if code_block.is_some() => {
+                if let Some((_, code)) = code_block.as_mut() {
+                    code.push_str(text);
+                }
+            }
+            Event::End(TagEnd::CodeBlock) if code_block.is_some() => {
+                if let Some((lang, code)) = code_block.take() {
+                    events.push(Event::Html(CowStr::from(highlight(&lang, &code))));
+                }
+            }
+            Event::Start(Tag::Image { link_type, dest_url, title, id }) => {
+                events.push(Event::Start(Tag::Image { link_type, dest_url: attachment_url(dest_url, attachment_base), title, id }));
+            }
+            Event::Start(Tag::Link { link_type, dest_url, title, id }) => {
+                events.push(Event::Start(Tag::Link { link_type, dest_url: attachment_url(dest_url, attachment_base), title, id }));
+            }
+            event => events.push(event),
+        }
+    }
+
+    let mut unsafe_html = String::new();
+    html::push_html(&mut unsafe_html, events.into_iter());
+    sanitizer().clean(&unsafe_html).to_string()
+}
+
+/// Rewrites `attachment:<id>` references to the attachment download URL
+fn attachment_url<'a>(dest_url: CowStr<'a>, attachment_base: &str) -> CowStr<'a> {
+    match dest_url.strip_prefix(ATTACHMENT_URL_SCHEME).map(str::parse::<i64>) {
+        Some(Ok(id)) => CowStr::from(format!("{}/{}", attachment_base, id)),
+        _ => dest_url,
+    }
+}
+
+/// Highlights a fenced code block with CSS classes
+fn highlight(lang: &str, code: &str) -> String {
+    let syntax_set = syntax_set();
+    let Some(syntax) = syntax_set.find_syntax_by_token(lang) else {
+        return String::new();
+    };
+
+    let mut generator = ClassedHTMLGenerator::new_with_class_style(
+        syntax,
+        syntax_set,
+        ClassStyle::SpacedPrefixed { prefix: HIGHLIGHT_CLASS_PREFIX },
+    );
+    for line in LinesWithEndings::from(code) {
+        if generator.parse_html_for_line_which_includes_newline(line).is_err() {
+            return format!("<pre><code>{}</code></pre>\n", ammonia::clean_text(code));
+        }
+    }
+
+    let lang: String = lang.chars().filter(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '#' | '-')).collect();
+    format!("<pre class=\"highlight\"><code class=\"language-{}\">{}</code></pre>\n", lang, generator.finalize())
+}
+
+/// Extracts the text of a Markdown document without any markup
+pub fn plain_text(content: &str) -> String {
+    let mut text = String::new();
+
+    for event in Parser::new_ext(content, parser_options()) {
+        match event {
+            Event::Text(t) | Event::Code(t) => text.push_str(&t),
+            Event::SoftBreak | Event::HardBreak | Event::End(_) => text.push(' '),
+            _ => {}
+        }
+    }
+
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Extracts plain text from Markdown, truncated at a word boundary
+pub fn excerpt(content: &str, max_chars: usize) -> String {
+    let text = plain_text(content);
+    if text.chars().count() <= max_chars {
+        return text;
+    }
+
+    let truncated: String = text.chars().take(max_chars).collect();
+    let cut = truncated.rfind(' ').filter(|&i| i > 0).unwrap_or(truncated.len());
+    format!("{}…", truncated[..cut].trim_end())
+}
+
+/// Escapes text for use inside the brackets of a Markdown link or image
+///
+/// Brackets and backslashes are escaped and line breaks and other control
+/// characters become spaces, so the text cannot end the link early.
+pub fn escape_link_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | '[' | ']' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            c if c.is_control() => escaped.push(' '),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_extensions() {
+        let html = render_html("| a | b |\n|---|---|\n| 1 | 2 |\n\n- [x] done\n\nText[^1]\n\n[^1]: Note");
+        assert!(html.contains("<table>"));
+        assert!(html.contains("<input") && html.contains("type=\"checkbox\""));
+        assert!(html.contains("footnote-definition"));
+
+        let html = render_html("```rust\nfn main() {}\n```");
+        assert!(html.contains("language-rust"));
+        assert!(html.contains("hl-"));
+
+        let html = render_html("![diagram](attachment:12)");
+        assert!(html.contains("src=\"/api/attachments/12\""));
+    }
+
+    #[test]
+    fn test_render_sanitizes() {
+        let html = render_html("<script>alert(1)</script>\n\n[x](javascript:alert(1)) <img src=x onerror=alert(1)>");
+        assert!(!html.contains("<script"));
+        assert!(!html.contains("javascript:"));
+        assert!(!html.contains("onerror"));
+
+        let html = render_html("<div id=\"x\" class=\"admin footnote-definition\">hi</div>");
+        assert!(html.contains("id=\"user-content-x\""));
+        assert!(html.contains("class=\"footnote-definition\""));
+        assert!(!html.contains("admin"));
+
+        let html = render_html("<span class=\"admin\">hi</span>");
+        assert!(!html.contains("class"));
+
+        let html = render_html("Text[^a]\n\n[^a]: Note");
+        assert!(html.contains("href=\"#user-content-a\""));
+        assert!(html.contains("id=\"user-content-a\""));
+    }
+
+    #[test]
+    fn test_excerpt() {
+        assert_eq!(excerpt("# Title\n\nSome **bold** and `code`.", 200), "Title Some bold and code.");
+        assert_eq!(excerpt("one two three four", 12), "one two…");
+        assert_eq!(excerpt("", 10), "");
+    }
+
+    #[test]
+    fn test_escape_link_text() {
+        let alt = escape_link_text("a](javascript:x)\n\n# [b]\\");
+        assert_eq!(alt, "a\\](javascript:x)  # \\[b\\]\\\\");
+
+        let html = render_html(&format!("![{}](attachment:3)", alt));
+        assert_eq!(html.matches("<img").count(), 1);
+        assert!(html.contains("src=\"/api/attachments/3\""));
+        assert!(!html.contains("<h1"));
+    }
+
+    #[test]
+    fn test_render_is_cached() {
+        let first = render("cached *content*");
+        let second = render("cached *content*");
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(first.excerpt, "cached content");
+    }
+}
@@ -1,3 +1,7 @@
+pub mod markdown;
 pub mod pagination;
+pub mod patch;
+pub mod text_stats;
+pub mod validation;
 
 pub use pagination::{PaginationParams, PaginatedResponse};
\ No newline at end of file
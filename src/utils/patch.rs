@@ -0,0 +1,88 @@
+//! Helpers for PATCH requests with JSON Merge Patch (RFC 7396) semantics
+//!
+//! Patch fields are `Option<Option<T>>`: an absent key leaves the column
+//! untouched, `null` clears it and a value sets it.
+
+use actix_web::{http::header, HttpRequest};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer};
+use crate::errors::{AppError, Result};
+
+/// Media type of JSON Merge Patch request bodies
+pub const MERGE_PATCH_CONTENT_TYPE: &str = "application/merge-patch+json";
+
+/// Deserializes a present key into `Some`, keeping `null` as `Some(None)`
+///
+/// Use together with `#[serde(default)]` so that absent keys stay `None`.
+pub fn nullable<'de, T, D>(deserializer: D) -> std::result::Result<Option<Option<T>>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+/// Rejects `null` for a field whose column cannot be cleared
+pub fn required<T>(field: &str, value: Option<Option<T>>) -> Result<Option<T>> {
+    match value {
+        Some(None) => Err(AppError::ValidationError(format!("{} cannot be null", field))),
+        Some(Some(value)) => Ok(Some(value)),
+        None => Ok(None),
+    }
+}
+
+/// Parses a PATCH body sent as `application/merge-patch+json` or `application/json`
+///
+/// The body must be a JSON object; per RFC 7396 any other document would
+/// replace the whole resource, which is not supported.
+pub fn parse_merge_patch<T: DeserializeOwned>(req: &HttpRequest, body: &[u8]) -> Result<T> {
+    let content_type = req
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split(';').next())
+        .map(|v| v.trim().to_ascii_lowercase())
+        .unwrap_or_default();
+    if content_type != MERGE_PATCH_CONTENT_TYPE && content_type != "application/json" {
+        return Err(AppError::BadRequest(format!(
+            "Unsupported content type '{}', expected {} or application/json",
+            content_type, MERGE_PATCH_CONTENT_TYPE
+        )));
+    }
+
+    let value: serde_json::Value = serde_json::from_slice(body)?;
+    if !value.is_object() {
+        return Err(AppError::ValidationError("Patch document must be a JSON object".to_string()));
+    }
+
+    serde_json::from_value(value).map_err(|e| AppError::ValidationError(format!("Invalid patch: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Deserialize)]
+    struct Patch {
+        #[serde(default, deserialize_with = "nullable")]
+        isbn: Option<Option<String>>,
+    }
+
+    #[test]
+    fn test_nullable_distinguishes_absent_null_and_value() {
+        let absent: Patch = serde_json::from_str("{}").unwrap();
+        let null: Patch = serde_json::from_str(r#"{"isbn": null}"#).unwrap();
+        let value: Patch = serde_json::from_str(r#"{"isbn": "978-0134685991"}"#).unwrap();
+
+        assert_eq!(absent.isbn, None);
+        assert_eq!(null.isbn, Some(None));
+        assert_eq!(value.isbn, Some(Some("978-0134685991".to_string())));
+    }
+
+    #[test]
+    fn test_required_rejects_null() {
+        assert!(required::<String>("title", Some(None)).is_err());
+        assert_eq!(required("title", Some(Some(1))).unwrap(), Some(1));
+        assert_eq!(required::<i32>("title", None).unwrap(), None);
+    }
+}
@@ -0,0 +1,99 @@
+//! Length and reading time of note text
+//!
+//! Chinese and Japanese are written without spaces between words, so each
+//! Han, Hiragana or Katakana character counts as one word. Text in other
+//! scripts is split into words at whitespace and punctuation.
+
+/// Reading speed for space-separated words
+pub const WORDS_PER_MINUTE: u32 = 230;
+/// Reading speed for Chinese and Japanese characters
+pub const CJK_CHARS_PER_MINUTE: u32 = 400;
+
+/// Counts describing a text
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TextStats {
+    /// Characters other than whitespace
+    pub char_count: i32,
+    /// Space-separated words plus CJK characters
+    pub word_count: i32,
+    /// Estimated time to read the text, rounded up to whole seconds
+    pub reading_time_seconds: i32,
+}
+
+/// Whether a character belongs to a script written without spaces
+pub fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30FF}'     // Hiragana, Katakana
+        | '\u{3400}'..='\u{4DBF}'   // CJK Extension A
+        | '\u{4E00}'..='\u{9FFF}'   // CJK Unified Ideographs
+        | '\u{F900}'..='\u{FAFF}'   // CJK Compatibility Ideographs
+        | '\u{FF66}'..='\u{FF9F}'   // Halfwidth Katakana
+        | '\u{20000}'..='\u{2FA1F}' // CJK Extensions B-F and supplement
+    )
+}
+
+/// Measures a plain-text string
+pub fn measure(text: &str) -> TextStats {
+    let mut char_count = 0u64;
+    let mut cjk_chars = 0u64;
+    let mut words = 0u64;
+    let mut in_word = false;
+
+    for c in text.chars() {
+        if !c.is_whitespace() {
+            char_count += 1;
+        }
+        if is_cjk(c) {
+            cjk_chars += 1;
+            in_word = false;
+        } else if c.is_alphanumeric() {
+            if !in_word {
+                words += 1;
+                in_word = true;
+            }
+        } else if !(in_word && matches!(c, '\'' | '\u{2019}')) {
+            // Apostrophes keep contractions like "don't" as one word
+            in_word = false;
+        }
+    }
+
+    let seconds = (words * 60).div_ceil(WORDS_PER_MINUTE as u64)
+        + (cjk_chars * 60).div_ceil(CJK_CHARS_PER_MINUTE as u64);
+    TextStats {
+        char_count: clamp(char_count),
+        word_count: clamp(words + cjk_chars),
+        reading_time_seconds: clamp(seconds),
+    }
+}
+
+fn clamp(value: u64) -> i32 {
+    value.min(i32::MAX as u64) as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latin_words() {
+        let stats = measure("Don't panic — it's only 42 pages.");
+        assert_eq!(stats.word_count, 6);
+        assert_eq!(stats.char_count, 27);
+        assert_eq!(stats.reading_time_seconds, 2);
+    }
+
+    #[test]
+    fn test_cjk_characters() {
+        let stats = measure("学而时习之，不亦说乎？");
+        assert_eq!(stats.word_count, 9);
+        assert_eq!(stats.char_count, 11);
+
+        // Mixed text counts both
+        let stats = measure("读《Deep Work》有感");
+        assert_eq!(stats.word_count, 5);
+
+        let stats = measure(&"字".repeat(400));
+        assert_eq!(stats.reading_time_seconds, 60);
+        assert_eq!(measure(""), TextStats::default());
+    }
+}
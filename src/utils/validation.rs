@@ -0,0 +1,42 @@
+//! Checks for free-text request fields
+//!
+//! Values are trimmed before they are stored, so emptiness and length are
+//! checked on the trimmed text. Lengths count characters, as `VARCHAR(n)`
+//! does.
+
+use crate::errors::{AppError, Result};
+
+/// Trims a text field that must not be empty and must fit in `max_length` characters
+pub fn required_text(field: &str, value: &str, max_length: usize) -> Result<String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Err(AppError::ValidationError(format!("{} cannot be empty", field)));
+    }
+    if value.chars().count() > max_length {
+        return Err(AppError::ValidationError(format!(
+            "{} cannot exceed {} characters",
+            field, max_length
+        )));
+    }
+    Ok(value.to_string())
+}
+
+/// Trims an optional text field; when present it is checked like `required_text`
+pub fn optional_text(field: &str, value: Option<String>, max_length: usize) -> Result<Option<String>> {
+    value.map(|v| required_text(field, &v, max_length)).transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_required_text() {
+        assert_eq!(required_text("Name", "  Idea ", 50).unwrap(), "Idea");
+        assert_eq!(required_text("Name", "读书笔记", 4).unwrap(), "读书笔记");
+        assert!(required_text("Name", "   ", 50).is_err());
+        assert!(required_text("Name", "abcdef", 5).is_err());
+        assert_eq!(optional_text("Icon", None, 50).unwrap(), None);
+        assert!(optional_text("Icon", Some(String::new()), 50).is_err());
+    }
+}
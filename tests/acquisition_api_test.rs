@@ -0,0 +1,149 @@
+//! Integration tests for ownership, wishlist and purchase tracking
+
+mod common;
+
+use actix_web::test;
+use reading_notes_backend::create_app;
+use serde_json::{json, Value};
+
+/// Test listing the wishlist and moving a wishlist book to owned
+#[actix_web::test]
+async fn test_wishlist_and_acquire() {
+    let test_db = common::setup_test_db();
+    let app = test::init_service(create_app(test_db.pool.clone())).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(json!({ "title": "The Pragmatic Programmer", "author": "Andy Hunt", "acquisition_status": "wishlist" }))
+        .to_request();
+    let wished: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(wished["acquisition_status"], "wishlist");
+    let wished_id = wished["id"].as_i64().unwrap();
+
+    let req = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(json!({ "title": "Refactoring", "author": "Martin Fowler" }))
+        .to_request();
+    let owned: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(owned["acquisition_status"], "owned");
+    let owned_id = owned["id"].as_i64().unwrap();
+
+    let req = test::TestRequest::get().uri("/api/books/wishlist").to_request();
+    let wishlist: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(wishlist["total"], 1);
+    assert_eq!(wishlist["books"][0]["id"], wished_id);
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/books/{}/acquire", wished_id))
+        .set_json(json!({ "purchase_place": "p".repeat(101) }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 422);
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/books/{}/acquire", wished_id))
+        .set_json(json!({ "purchase_date": "2024-03-15", "purchase_price": 42.5, "currency": "usd", "purchase_place": " Powell's " }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+    let acquired: Value = test::read_body_json(resp).await;
+    assert_eq!(acquired["acquisition_status"], "owned");
+    assert_eq!(acquired["purchase_price"], "42.50");
+    assert_eq!(acquired["currency"], "USD");
+    assert_eq!(acquired["purchase_place"], "Powell's");
+
+    // Already owned books cannot be acquired again
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/books/{}/acquire", owned_id))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 409);
+
+    let req = test::TestRequest::get().uri("/api/books/wishlist").to_request();
+    let wishlist: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(wishlist["total"], 0);
+}
+
+/// Test the spending report groups owned purchases by period and currency
+#[actix_web::test]
+async fn test_spending_report() {
+    let test_db = common::setup_test_db();
+    let app = test::init_service(create_app(test_db.pool.clone())).await;
+
+    let purchases = [
+        ("Book A", "2024-03-01", 10.0, "EUR", "owned"),
+        ("Book B", "2024-03-20", 15.5, "EUR", "owned"),
+        ("Book C", "2024-04-02", 30.0, "USD", "owned"),
+        ("Book D", "2023-12-24", 20.0, "EUR", "owned"),
+        ("Book E", "2024-03-05", 99.0, "EUR", "library"),
+    ];
+    for (title, date, price, currency, status) in purchases {
+        let req = test::TestRequest::post()
+            .uri("/api/books")
+            .set_json(json!({
+                "title": title,
+                "author": "Author",
+                "acquisition_status": status,
+                "purchase_date": date,
+                "purchase_price": price,
+                "currency": currency
+            }))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 201);
+    }
+
+    let req = test::TestRequest::get().uri("/api/books/spending?year=2024").to_request();
+    let report: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    let report = report.as_array().unwrap();
+    assert_eq!(report.len(), 2);
+    assert_eq!(report[0]["period"], "2024-04");
+    assert_eq!(report[0]["currency"], "USD");
+    assert_eq!(report[1]["period"], "2024-03");
+    assert_eq!(report[1]["total"], "25.50");
+    assert_eq!(report[1]["book_count"], 2);
+
+    let req = test::TestRequest::get().uri("/api/books/spending?period=year").to_request();
+    let report: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(report.as_array().unwrap().len(), 3);
+
+    // A price without a currency is rejected
+    let req = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(json!({ "title": "Book F", "author": "Author", "purchase_price": 5.0 }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 422);
+
+    // Purchase places must fit their column
+    let place = "p".repeat(101);
+    let req = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(json!({ "title": "Book F", "author": "Author", "purchase_place": place }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 422);
+
+    let req = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(json!({ "title": "Book F", "author": "Author", "purchase_place": " Market " }))
+        .to_request();
+    let book: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(book["purchase_place"], "Market");
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/books/{}", book["id"]))
+        .set_json(json!({ "purchase_place": place }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 422);
+
+    let req = test::TestRequest::patch()
+        .uri(&format!("/api/books/{}", book["id"]))
+        .insert_header(("content-type", "application/merge-patch+json"))
+        .set_payload(json!({ "purchase_place": place }).to_string())
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 422);
+
+    let req = test::TestRequest::patch()
+        .uri(&format!("/api/books/{}", book["id"]))
+        .insert_header(("content-type", "application/merge-patch+json"))
+        .set_payload(json!({ "purchase_place": null }).to_string())
+        .to_request();
+    let book: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(book["purchase_place"], Value::Null);
+}
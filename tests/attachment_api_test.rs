@@ -0,0 +1,170 @@
+//! Integration tests for image attachments on notes
+
+mod common;
+
+use actix_web::test;
+use reading_notes_backend::create_app;
+use serde_json::{json, Value};
+
+const BOUNDARY: &str = "attachment-test-boundary";
+const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR test image";
+
+/// Test that an uploaded image is listed on the note, downloadable and removed on purge
+#[actix_web::test]
+async fn test_upload_download_and_purge_attachment() {
+    let dir = std::env::temp_dir().join("reading_notes_attachment_tests");
+    std::env::set_var("ATTACHMENTS_DIR", &dir);
+    let test_db = common::setup_test_db();
+    let app = test::init_service(create_app(test_db.pool.clone())).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(json!({ "title": "The Design of Everyday Things", "author": "Don Norman" }))
+        .to_request();
+    let book: Value = test::read_body_json(test::call_service(&app, req).await).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/notes")
+        .set_json(json!({ "book_id": book["id"], "content": "Affordances" }))
+        .to_request();
+    let note: Value = test::read_body_json(test::call_service(&app, req).await).await;
+
+    let mut body = format!(
+        "--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"door.png\"\r\nContent-Type: image/png\r\n\r\n"
+    )
+    .into_bytes();
+    body.extend_from_slice(PNG);
+    body.extend_from_slice(format!("\r\n--{BOUNDARY}--\r\n").as_bytes());
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/notes/{}/attachments", note["id"]))
+        .insert_header(("content-type", format!("multipart/form-data; boundary={BOUNDARY}")))
+        .set_payload(body)
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+    let attachments: Value = test::read_body_json(resp).await;
+    let attachment = &attachments[0];
+    assert_eq!(attachment["filename"], "door.png");
+    assert_eq!(attachment["content_type"], "image/png");
+    assert_eq!(attachment["size_bytes"], PNG.len());
+    assert_eq!(attachment["checksum"].as_str().unwrap().len(), 64);
+
+    // Embedding the attachment by reference renders its download URL
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/notes/{}", note["id"]))
+        .set_json(json!({ "content": format!("Affordances {}", attachment["markdown"].as_str().unwrap()) }))
+        .to_request();
+    test::call_service(&app, req).await;
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/notes/{}?render=html", note["id"]))
+        .to_request();
+    let fetched: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(fetched["attachments"][0]["id"], attachment["id"]);
+    assert!(fetched["content_html"]
+        .as_str()
+        .unwrap()
+        .contains(&format!("src=\"/api/attachments/{}\"", attachment["id"])));
+
+    let req = test::TestRequest::get()
+        .uri(attachment["url"].as_str().unwrap())
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers().get("content-type").unwrap(), "image/png");
+    assert_eq!(test::read_body(resp).await.as_ref(), PNG);
+
+    let stored = std::fs::read_dir(&dir).unwrap().count();
+
+    let req = test::TestRequest::delete()
+        .uri(&format!("/api/notes/{}", note["id"]))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 204);
+
+    let req = test::TestRequest::delete()
+        .uri(&format!("/api/trash/notes/{}", note["id"]))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 204);
+
+    assert!(std::fs::read_dir(&dir).unwrap().count() < stored);
+    let req = test::TestRequest::get()
+        .uri(attachment["url"].as_str().unwrap())
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+}
+
+/// Test that files that are not images are rejected
+#[actix_web::test]
+async fn test_reject_non_image_attachment() {
+    std::env::set_var("ATTACHMENTS_DIR", std::env::temp_dir().join("reading_notes_attachment_tests"));
+    let test_db = common::setup_test_db();
+    let app = test::init_service(create_app(test_db.pool.clone())).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(json!({ "title": "Don't Make Me Think", "author": "Steve Krug" }))
+        .to_request();
+    let book: Value = test::read_body_json(test::call_service(&app, req).await).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/notes")
+        .set_json(json!({ "book_id": book["id"], "content": "Usability" }))
+        .to_request();
+    let note: Value = test::read_body_json(test::call_service(&app, req).await).await;
+
+    let body = format!(
+        "--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"evil.png\"\r\nContent-Type: image/png\r\n\r\n<svg onload=alert(1)>\r\n--{BOUNDARY}--\r\n"
+    );
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/notes/{}/attachments", note["id"]))
+        .insert_header(("content-type", format!("multipart/form-data; boundary={BOUNDARY}")))
+        .set_payload(body)
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 422);
+
+    // Overlong filenames and too many files in one request
+    let mut body = image_part(&format!("{}.png", "a".repeat(300)));
+    body.extend_from_slice(format!("--{BOUNDARY}--\r\n").as_bytes());
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/notes/{}/attachments", note["id"]))
+        .insert_header(("content-type", format!("multipart/form-data; boundary={BOUNDARY}")))
+        .set_payload(body)
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 422);
+
+    let mut body = image_part("door.png").repeat(11);
+    body.extend_from_slice(format!("--{BOUNDARY}--\r\n").as_bytes());
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/notes/{}/attachments", note["id"]))
+        .insert_header(("content-type", format!("multipart/form-data; boundary={BOUNDARY}")))
+        .set_payload(body)
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 422);
+
+    // A form without any file part
+    let body = format!("--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"caption\"\r\n\r\nhello\r\n--{BOUNDARY}--\r\n");
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/notes/{}/attachments", note["id"]))
+        .insert_header(("content-type", format!("multipart/form-data; boundary={BOUNDARY}")))
+        .set_payload(body)
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 400);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/notes/{}", note["id"]))
+        .to_request();
+    let fetched: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(fetched["attachments"], json!([]));
+}
+
+/// Builds one multipart file part holding a PNG image
+fn image_part(filename: &str) -> Vec<u8> {
+    let mut part = format!(
+        "--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{filename}\"\r\nContent-Type: image/png\r\n\r\n"
+    )
+    .into_bytes();
+    part.extend_from_slice(PNG);
+    part.extend_from_slice(b"\r\n");
+    part
+}
@@ -20,6 +20,7 @@ async fn test_create_book_success() {
         page_count: Some(416),
         cover_image: None,
         description: Some("Best practices for the Java platform".to_string()),
+        ..Default::default()
     };
 
     // Act
@@ -54,6 +55,7 @@ async fn test_create_book_validation_error() {
         page_count: None,
         cover_image: None,
         description: None,
+        ..Default::default()
     };
 
     // Act
@@ -86,6 +88,7 @@ async fn test_get_book_success() {
         page_count: Some(416),
         cover_image: None,
         description: Some("Best practices for the Java platform".to_string()),
+        ..Default::default()
     };
 
     let create_req = test::TestRequest::post()
@@ -171,6 +174,7 @@ async fn test_list_books_with_pagination() {
             page_count: None,
             cover_image: None,
             description: None,
+            ..Default::default()
         };
 
         let req = test::TestRequest::post()
@@ -211,18 +215,21 @@ async fn test_search_books() {
             author: "Joshua Bloch".to_string(),
             isbn: None, publisher: None, publication_date: None,
             page_count: None, cover_image: None, description: None,
+            ..Default::default()
         },
         CreateBookRequest {
             title: "Clean Code".to_string(),
             author: "Robert Martin".to_string(),
             isbn: None, publisher: None, publication_date: None,
             page_count: None, cover_image: None, description: None,
+            ..Default::default()
         },
         CreateBookRequest {
             title: "Design Patterns".to_string(),
             author: "Gang of Four".to_string(),
             isbn: None, publisher: None, publication_date: None,
             page_count: None, cover_image: None, description: None,
+            ..Default::default()
         },
     ];
 
@@ -261,6 +268,7 @@ async fn test_update_book_success() {
         author: "Original Author".to_string(),
         isbn: None, publisher: None, publication_date: None,
         page_count: None, cover_image: None, description: None,
+        ..Default::default()
     };
 
     let create_req = test::TestRequest::post()
@@ -306,6 +314,7 @@ async fn test_delete_book_success() {
         author: "Some Author".to_string(),
         isbn: None, publisher: None, publication_date: None,
         page_count: None, cover_image: None, description: None,
+        ..Default::default()
     };
 
     let create_req = test::TestRequest::post()
@@ -0,0 +1,142 @@
+//! Integration tests for user-defined custom fields on books
+
+mod common;
+
+use actix_web::{http::header, test};
+use reading_notes_backend::create_app;
+use serde_json::{json, Value};
+
+/// Test that book values are validated against the field definitions
+#[actix_web::test]
+async fn test_custom_field_validation() {
+    let test_db = common::setup_test_db();
+    let app = test::init_service(create_app(test_db.pool.clone())).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/custom-fields")
+        .set_json(json!({ "key": "condition", "name": "Condition", "field_type": "enum", "enum_values": ["new", "good", "worn"] }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+
+    let req = test::TestRequest::post()
+        .uri("/api/custom-fields")
+        .set_json(json!({ "key": "signed", "name": "Signed copy", "field_type": "bool" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 201);
+
+    // Names must fit their column and enum values must be distinct
+    for body in [
+        json!({ "key": "shelf", "name": "  ", "field_type": "text" }),
+        json!({ "key": "shelf", "name": "n".repeat(101), "field_type": "text" }),
+        json!({ "key": "grade", "name": "Grade", "field_type": "enum", "enum_values": ["good", " good ", ""] }),
+    ] {
+        let req = test::TestRequest::post().uri("/api/custom-fields").set_json(body).to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 422);
+    }
+
+    // Values of the wrong type, outside the enum or for unknown fields are rejected
+    for custom_fields in [json!({ "signed": "yes" }), json!({ "condition": "mint" }), json!({ "edition": 2 })] {
+        let req = test::TestRequest::post()
+            .uri("/api/books")
+            .set_json(json!({ "title": "Dune", "author": "Frank Herbert", "custom_fields": custom_fields }))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 422);
+    }
+
+    let req = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(json!({ "title": "Dune", "author": "Frank Herbert", "custom_fields": { "signed": true, "condition": "good" } }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+    let book: Value = test::read_body_json(resp).await;
+    assert_eq!(book["custom_fields"], json!({ "signed": true, "condition": "good" }));
+    let book_id = book["id"].as_i64().unwrap();
+
+    // Patching merges values; null removes a single field
+    let req = test::TestRequest::patch()
+        .uri(&format!("/api/books/{}", book_id))
+        .insert_header((header::CONTENT_TYPE, "application/merge-patch+json"))
+        .set_payload(json!({ "custom_fields": { "signed": null, "condition": "worn" } }).to_string())
+        .to_request();
+    let patched: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(patched["custom_fields"], json!({ "condition": "worn" }));
+
+    // An enum value in use cannot be removed from the definition
+    let req = test::TestRequest::get().uri("/api/custom-fields").to_request();
+    let fields: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    let condition_id = fields
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|f| f["key"] == "condition")
+        .unwrap()["id"]
+        .as_i64()
+        .unwrap();
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/custom-fields/{}", condition_id))
+        .set_json(json!({ "enum_values": ["new", "good"] }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 409);
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/custom-fields/{}", condition_id))
+        .set_json(json!({ "name": "" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 422);
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/custom-fields/{}", condition_id))
+        .set_json(json!({ "name": " Book condition " }))
+        .to_request();
+    let updated: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(updated["name"], "Book condition");
+    assert_eq!(updated["enum_values"], json!(["new", "good", "worn"]));
+
+    // Deleting the definition strips its values from books
+    let req = test::TestRequest::delete()
+        .uri(&format!("/api/custom-fields/{}", condition_id))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 204);
+    let req = test::TestRequest::get().uri(&format!("/api/books/{}", book_id)).to_request();
+    let book: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(book["custom_fields"], json!({}));
+}
+
+/// Test filtering and sorting the book list by custom fields
+#[actix_web::test]
+async fn test_filter_and_sort_by_custom_field() {
+    let test_db = common::setup_test_db();
+    let app = test::init_service(create_app(test_db.pool.clone())).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/custom-fields")
+        .set_json(json!({ "key": "edition", "name": "Edition", "field_type": "number" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 201);
+
+    for (title, edition) in [("Second", json!(2)), ("Tenth", json!(10)), ("First", json!(1)), ("Unknown", Value::Null)] {
+        let req = test::TestRequest::post()
+            .uri("/api/books")
+            .set_json(json!({ "title": title, "author": "Author", "custom_fields": { "edition": edition } }))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 201);
+    }
+
+    // Numbers sort numerically, books without a value come last
+    let req = test::TestRequest::get()
+        .uri("/api/books?sort_custom_field=edition&sort_order=desc")
+        .to_request();
+    let list: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    let titles: Vec<&str> = list["books"].as_array().unwrap().iter().map(|b| b["title"].as_str().unwrap()).collect();
+    assert_eq!(titles, vec!["Tenth", "Second", "First", "Unknown"]);
+
+    let req = test::TestRequest::get().uri("/api/books?custom_field=edition:10").to_request();
+    let list: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(list["total"], 1);
+    assert_eq!(list["books"][0]["title"], "Tenth");
+
+    let req = test::TestRequest::get().uri("/api/books?custom_field=edition:tenth").to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 422);
+}
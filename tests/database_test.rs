@@ -36,6 +36,7 @@ async fn test_book_crud_operations() {
         page_count: Some(300),
         cover_image: None,
         description: Some("A test book".to_string()),
+        ..Default::default()
     };
     
     use reading_notes_backend::db::schema::books;
@@ -111,6 +112,7 @@ async fn test_reading_status_operations() {
         page_count: Some(200),
         cover_image: None,
         description: None,
+        ..Default::default()
     };
     
     use reading_notes_backend::db::schema::{books, reading_status};
@@ -172,6 +174,7 @@ async fn test_notes_operations() {
         page_count: Some(150),
         cover_image: None,
         description: None,
+        ..Default::default()
     };
     
     use reading_notes_backend::db::schema::{books, reading_notes};
@@ -190,6 +193,7 @@ async fn test_notes_operations() {
         note_type: Some("summary".to_string()),
         page_reference: Some("1-20".to_string()),
         is_favorite: Some(true),
+        ..Default::default()
     };
     
     let note: ReadingNote = diesel::insert_into(reading_notes::table)
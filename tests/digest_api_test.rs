@@ -0,0 +1,114 @@
+//! Integration tests for the daily highlights digest
+
+mod common;
+
+use actix_web::test;
+use chrono::Datelike;
+use reading_notes_backend::create_app;
+use serde_json::{json, Value};
+
+/// Test that the digest is stable for a day and drops deleted notes
+#[actix_web::test]
+async fn test_digest_is_stable_for_the_day() {
+    let test_db = common::setup_test_db();
+    let app = test::init_service(create_app(test_db.pool.clone())).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(json!({ "title": "The Art of Memory", "author": "Frances Yates" }))
+        .to_request();
+    let book: Value = test::read_body_json(test::call_service(&app, req).await).await;
+
+    for i in 0..8 {
+        let req = test::TestRequest::post()
+            .uri("/api/notes")
+            .set_json(json!({ "book_id": book["id"], "content": format!("Note {}", i), "is_favorite": i % 3 == 0 }))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 201);
+    }
+
+    let ids = |digest: &Value| -> Vec<i64> {
+        digest["highlights"].as_array().unwrap().iter().map(|n| n["id"].as_i64().unwrap()).collect()
+    };
+
+    let req = test::TestRequest::get().uri("/api/digest/today").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    let first: Value = test::read_body_json(resp).await;
+    assert_eq!(first["date"], chrono::Utc::now().date_naive().to_string());
+    assert_eq!(ids(&first).len(), 5);
+    assert_eq!(first["on_this_day"], json!([]));
+
+    // New favorites do not change a digest that was already picked
+    let req = test::TestRequest::post()
+        .uri("/api/notes")
+        .set_json(json!({ "book_id": book["id"], "content": "Late favorite", "is_favorite": true }))
+        .to_request();
+    test::call_service(&app, req).await;
+
+    let req = test::TestRequest::get().uri("/api/digest/today").to_request();
+    let second: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(ids(&second), ids(&first));
+
+    let req = test::TestRequest::delete()
+        .uri(&format!("/api/notes/{}", ids(&first)[0]))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 204);
+
+    let req = test::TestRequest::get().uri("/api/digest/today").to_request();
+    let third: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(ids(&third), ids(&first)[1..].to_vec());
+}
+
+/// Test that notes from this calendar date in earlier years are included
+#[actix_web::test]
+async fn test_digest_on_this_day() {
+    let test_db = common::setup_test_db();
+    let app = test::init_service(create_app(test_db.pool.clone())).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(json!({ "title": "Remembrance of Things Past", "author": "Marcel Proust" }))
+        .to_request();
+    let book: Value = test::read_body_json(test::call_service(&app, req).await).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/notes")
+        .set_json(json!({ "book_id": book["id"], "content": "The madeleine" }))
+        .to_request();
+    let note: Value = test::read_body_json(test::call_service(&app, req).await).await;
+
+    // Nothing to pick before the note was written
+    let today = chrono::Utc::now().date_naive();
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/digest/today?date={}", today.pred_opt().unwrap()))
+        .to_request();
+    let digest: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(digest["highlights"], json!([]));
+
+    let next_year = today.with_year(today.year() + 1).unwrap_or(today + chrono::Days::new(365));
+    if next_year.day() != today.day() {
+        // February 29th has no counterpart next year
+        return;
+    }
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/digest/today?date={}", next_year))
+        .to_request();
+    let digest: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(digest["on_this_day"].as_array().unwrap().len(), 1);
+    assert_eq!(digest["on_this_day"][0]["years_ago"], 1);
+    assert_eq!(digest["on_this_day"][0]["note"]["id"], note["id"]);
+    assert_eq!(digest["highlights"][0]["id"], note["id"]);
+
+    // Only today's digest is stored
+    let req = test::TestRequest::get().uri("/api/digest/today").to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+
+    let stored: Vec<chrono::NaiveDate> = {
+        use diesel::prelude::*;
+        use reading_notes_backend::db::schema::digests;
+        let mut conn = test_db.pool.get().unwrap();
+        digests::table.select(digests::digest_date).load(&mut conn).unwrap()
+    };
+    assert_eq!(stored, vec![today]);
+}
@@ -0,0 +1,127 @@
+//! Integration tests for the book lending tracker
+
+mod common;
+
+use actix_web::test;
+use chrono::{Duration, Utc};
+use reading_notes_backend::create_app;
+use serde_json::{json, Value};
+
+/// Test lending a book, rejecting a second loan and returning it
+#[actix_web::test]
+async fn test_lend_and_return_book() {
+    let test_db = common::setup_test_db();
+    let app = test::init_service(create_app(test_db.pool.clone())).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(json!({ "title": "Designing Data-Intensive Applications", "author": "Martin Kleppmann" }))
+        .to_request();
+    let book: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    let book_id = book["id"].as_i64().unwrap();
+
+    for borrower in ["  ".to_string(), "b".repeat(101)] {
+        let req = test::TestRequest::post()
+            .uri(&format!("/api/books/{}/loans", book_id))
+            .set_json(json!({ "borrower": borrower }))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 422);
+    }
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/books/{}/loans", book_id))
+        .set_json(json!({ "borrower": "Alice", "due_date": "2099-01-01" }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+    let loan: Value = test::read_body_json(resp).await;
+    assert_eq!(loan["borrower"], "Alice");
+    assert_eq!(loan["book_title"], "Designing Data-Intensive Applications");
+    assert_eq!(loan["is_overdue"], false);
+    let loan_id = loan["id"].as_i64().unwrap();
+
+    // The book is already out
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/books/{}/loans", book_id))
+        .set_json(json!({ "borrower": "Bob" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 409);
+
+    let req = test::TestRequest::get().uri("/api/loans?state=active").to_request();
+    let active: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(active["total"], 1);
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/loans/{}/return", loan_id))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+    let returned: Value = test::read_body_json(resp).await;
+    assert!(returned["returned_date"].is_string());
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/loans/{}/return", loan_id))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 409);
+
+    // Once returned it can be lent again, and the book detail shows the history
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/books/{}/loans", book_id))
+        .set_json(json!({ "borrower": "Bob" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 201);
+
+    let req = test::TestRequest::get().uri(&format!("/api/books/{}", book_id)).to_request();
+    let detail: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    let loans = detail["loans"].as_array().unwrap();
+    assert_eq!(loans.len(), 2);
+    assert_eq!(loans[0]["borrower"], "Bob");
+    assert_eq!(loans[1]["borrower"], "Alice");
+}
+
+/// Test overdue loans are listed and digital books cannot be lent
+#[actix_web::test]
+async fn test_overdue_loans_and_digital_books() {
+    let test_db = common::setup_test_db();
+    let app = test::init_service(create_app(test_db.pool.clone())).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(json!({ "title": "Sapiens", "author": "Yuval Noah Harari", "format": "hardcover" }))
+        .to_request();
+    let book: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    let book_id = book["id"].as_i64().unwrap();
+
+    let today = Utc::now().date_naive();
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/books/{}/loans", book_id))
+        .set_json(json!({
+            "borrower": "Carol",
+            "lent_date": (today - Duration::days(30)).to_string(),
+            "due_date": (today - Duration::days(2)).to_string()
+        }))
+        .to_request();
+    let loan: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(loan["is_overdue"], true);
+
+    let req = test::TestRequest::get().uri("/api/loans?state=overdue").to_request();
+    let overdue: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(overdue["total"], 1);
+    assert_eq!(overdue["loans"][0]["borrower"], "Carol");
+
+    let req = test::TestRequest::get().uri("/api/loans?state=returned").to_request();
+    let returned: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(returned["total"], 0);
+
+    let req = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(json!({ "title": "Sapiens (Kindle)", "author": "Yuval Noah Harari", "format": "ebook" }))
+        .to_request();
+    let ebook: Value = test::read_body_json(test::call_service(&app, req).await).await;
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/books/{}/loans", ebook["id"]))
+        .set_json(json!({ "borrower": "Dave" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 422);
+}
@@ -0,0 +1,133 @@
+//! Integration tests for physical shelf locations
+
+mod common;
+
+use actix_web::test;
+use reading_notes_backend::create_app;
+use serde_json::{json, Value};
+
+/// Test building a room > bookcase > shelf hierarchy and finding books in it
+#[actix_web::test]
+async fn test_location_hierarchy_and_books() {
+    let test_db = common::setup_test_db();
+    let app = test::init_service(create_app(test_db.pool.clone())).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/locations")
+        .set_json(json!({ "name": "Office", "kind": "room", "parent_id": null }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+    let room: Value = test::read_body_json(resp).await;
+    let room = room["id"].as_i64().unwrap();
+
+    let req = test::TestRequest::post()
+        .uri("/api/locations")
+        .set_json(json!({ "name": "Bookcase A", "kind": "bookcase", "parent_id": room }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+    let bookcase: Value = test::read_body_json(resp).await;
+    let bookcase = bookcase["id"].as_i64().unwrap();
+
+    let req = test::TestRequest::post()
+        .uri("/api/locations")
+        .set_json(json!({ "name": "Shelf 2", "kind": "shelf", "parent_id": bookcase }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+    let shelf: Value = test::read_body_json(resp).await;
+    let shelf = shelf["id"].as_i64().unwrap();
+
+    // Shelves belong in bookcases, not directly in rooms
+    let req = test::TestRequest::post()
+        .uri("/api/locations")
+        .set_json(json!({ "name": "Loose shelf", "kind": "shelf", "parent_id": room }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 422);
+
+    let req = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(json!({ "title": "Clean Code", "author": "Robert C. Martin", "location_id": shelf }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+    let book: Value = test::read_body_json(resp).await;
+    assert_eq!(book["location_id"], shelf);
+    assert_eq!(book["location_path"], "Office / Bookcase A / Shelf 2");
+    let book_id = book["id"].as_i64().unwrap();
+
+    let req = test::TestRequest::get().uri(&format!("/api/books/{}", book_id)).to_request();
+    let detail: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(detail["location_path"], "Office / Bookcase A / Shelf 2");
+
+    // A restored book keeps its location
+    let req = test::TestRequest::delete().uri(&format!("/api/books/{}", book_id)).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 204);
+    let req = test::TestRequest::post().uri(&format!("/api/books/{}/restore", book_id)).to_request();
+    let restored: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(restored["location_path"], "Office / Bookcase A / Shelf 2");
+
+    // Books on nested shelves are listed for the whole room
+    let req = test::TestRequest::get().uri(&format!("/api/locations/{}/books", room)).to_request();
+    let books: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(books["total"], 1);
+    assert_eq!(books["books"][0]["id"], book_id);
+
+    // Names are trimmed on update as on create
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/locations/{}", shelf))
+        .set_json(json!({ "name": "  Top shelf  " }))
+        .to_request();
+    let updated: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(updated["name"], "Top shelf");
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/locations/{}", shelf))
+        .set_json(json!({ "name": "s".repeat(101) }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 422);
+
+    // A location holding books cannot be deleted
+    let req = test::TestRequest::delete().uri(&format!("/api/locations/{}", shelf)).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 409);
+}
+
+/// Test that only owned books can be placed on a shelf
+#[actix_web::test]
+async fn test_only_owned_books_have_locations() {
+    let test_db = common::setup_test_db();
+    let app = test::init_service(create_app(test_db.pool.clone())).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/locations")
+        .set_json(json!({ "name": "Living room", "kind": "room", "parent_id": null }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+    let room: Value = test::read_body_json(resp).await;
+    let room = room["id"].as_i64().unwrap();
+
+    let req = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(json!({ "title": "Dune", "author": "Frank Herbert", "acquisition_status": "wishlist", "location_id": room }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 422);
+
+    let req = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(json!({ "title": "Dune", "author": "Frank Herbert", "location_id": room }))
+        .to_request();
+    let book: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    let book_id = book["id"].as_i64().unwrap();
+
+    // Returning a book to the library takes it off the shelf
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/books/{}", book_id))
+        .set_json(json!({ "acquisition_status": "library" }))
+        .to_request();
+    let updated: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(updated["acquisition_status"], "library");
+    assert!(updated["location_id"].is_null());
+    assert!(updated["location_path"].is_null());
+}
@@ -0,0 +1,85 @@
+//! Integration tests for server-side Markdown rendering of notes
+
+mod common;
+
+use actix_web::test;
+use reading_notes_backend::create_app;
+use serde_json::{json, Value};
+
+/// Test that `?render=html` returns sanitized HTML alongside the raw content
+#[actix_web::test]
+async fn test_get_note_rendered_html() {
+    let test_db = common::setup_test_db();
+    let app = test::init_service(create_app(test_db.pool.clone())).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(json!({ "title": "The Rust Programming Language", "author": "Steve Klabnik" }))
+        .to_request();
+    let book: Value = test::read_body_json(test::call_service(&app, req).await).await;
+
+    let content = "## Ownership\n\n- [x] Read chapter 4\n\n```rust\nlet s = String::new();\n```\n\n<script>alert(1)</script>";
+    let req = test::TestRequest::post()
+        .uri("/api/notes")
+        .set_json(json!({ "book_id": book["id"], "content": content }))
+        .to_request();
+    let note: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    let note_id = note["id"].as_i64().unwrap();
+
+    // Without render the raw content and excerpt are returned, but no HTML
+    assert!(note.get("content_html").is_none());
+    assert_eq!(note["excerpt"], "Ownership Read chapter 4 let s = String::new();");
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/notes/{}?render=html", note_id))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    let rendered: Value = test::read_body_json(resp).await;
+    let html = rendered["content_html"].as_str().unwrap();
+    assert!(html.contains("<h2>Ownership</h2>"));
+    assert!(html.contains("type=\"checkbox\""));
+    assert!(html.contains("language-rust"));
+    assert!(!html.contains("<script"));
+    assert_eq!(rendered["content"], content);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/notes/{}?render=pdf", note_id))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 422);
+}
+
+/// Test rendering on note list endpoints
+#[actix_web::test]
+async fn test_list_notes_rendered_html() {
+    let test_db = common::setup_test_db();
+    let app = test::init_service(create_app(test_db.pool.clone())).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(json!({ "title": "Walden", "author": "Henry David Thoreau" }))
+        .to_request();
+    let book: Value = test::read_body_json(test::call_service(&app, req).await).await;
+
+    for content in ["I went to the woods because I wished to live **deliberately**", "| Season | Pages |\n|---|---|\n| Spring | 40 |"] {
+        let req = test::TestRequest::post()
+            .uri("/api/notes")
+            .set_json(json!({ "book_id": book["id"], "content": content }))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 201);
+    }
+
+    for uri in ["/api/notes?render=html".to_string(), format!("/api/books/{}/notes?render=html", book["id"])] {
+        let req = test::TestRequest::get().uri(&uri).to_request();
+        let list: Value = test::read_body_json(test::call_service(&app, req).await).await;
+        let html: Vec<&str> = list["notes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|n| n["content_html"].as_str().unwrap())
+            .collect();
+        assert_eq!(html.len(), 2);
+        assert!(html.iter().any(|h| h.contains("<strong>deliberately</strong>")));
+        assert!(html.iter().any(|h| h.contains("<table>")));
+    }
+}
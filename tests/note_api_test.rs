@@ -25,6 +25,7 @@ async fn test_create_note() {
         page_count: Some(520),
         cover_image: None,
         description: Some("Rust编程语言官方指南".to_string()),
+        ..Default::default()
     };
 
     let req = test::TestRequest::post()
@@ -140,6 +141,7 @@ async fn test_get_note() {
         page_count: None,
         cover_image: None,
         description: None,
+        ..Default::default()
     };
 
     let req = test::TestRequest::post()
@@ -215,6 +217,7 @@ async fn test_list_notes() {
         page_count: None,
         cover_image: None,
         description: None,
+        ..Default::default()
     };
 
     let req = test::TestRequest::post()
@@ -278,6 +281,7 @@ async fn test_list_notes_pagination() {
         page_count: None,
         cover_image: None,
         description: None,
+        ..Default::default()
     };
 
     let req = test::TestRequest::post()
@@ -342,6 +346,7 @@ async fn test_update_note() {
         page_count: None,
         cover_image: None,
         description: None,
+        ..Default::default()
     };
 
     let req = test::TestRequest::post()
@@ -428,6 +433,7 @@ async fn test_update_note_empty_content() {
         page_count: None,
         cover_image: None,
         description: None,
+        ..Default::default()
     };
 
     let req = test::TestRequest::post()
@@ -489,6 +495,7 @@ async fn test_delete_note() {
         page_count: None,
         cover_image: None,
         description: None,
+        ..Default::default()
     };
 
     let req = test::TestRequest::post()
@@ -565,6 +572,7 @@ async fn test_get_book_notes() {
         page_count: None,
         cover_image: None,
         description: None,
+        ..Default::default()
     };
 
     let book2_data = CreateBookRequest {
@@ -576,6 +584,7 @@ async fn test_get_book_notes() {
         page_count: None,
         cover_image: None,
         description: None,
+        ..Default::default()
     };
 
     let req = test::TestRequest::post()
@@ -675,6 +684,7 @@ async fn test_update_note_tags() {
         page_count: None,
         cover_image: None,
         description: None,
+        ..Default::default()
     };
 
     let req = test::TestRequest::post()
@@ -0,0 +1,155 @@
+//! Integration tests for bulk note operations
+
+mod common;
+
+use actix_web::test;
+use reading_notes_backend::create_app;
+use serde_json::{json, Value};
+
+/// Test creating, tagging, retyping and deleting notes in bulk
+#[actix_web::test]
+async fn test_bulk_note_operations() {
+    let test_db = common::setup_test_db();
+    let app = test::init_service(create_app(test_db.pool.clone())).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(json!({ "title": "Walden", "author": "Henry David Thoreau" }))
+        .to_request();
+    let book: Value = test::read_body_json(test::call_service(&app, req).await).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/notes/bulk")
+        .set_json(json!({
+            "action": "create",
+            "notes": [
+                { "book_id": book["id"], "content": "Simplify, simplify", "tags": ["imported"] },
+                { "book_id": book["id"], "content": "   " },
+                { "book_id": book["id"], "content": "I went to the woods", "tags": ["imported"] }
+            ]
+        }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    let created: Value = test::read_body_json(resp).await;
+    assert_eq!(created["succeeded"], 2);
+    assert_eq!(created["failed"], 1);
+    assert_eq!(created["results"][1]["error"]["error"], "VALIDATION_ERROR");
+    let ids = vec![created["results"][0]["note_id"].clone(), created["results"][2]["note_id"].clone()];
+
+    let req = test::TestRequest::post()
+        .uri("/api/notes/bulk")
+        .set_json(json!({ "action": "add_tags", "note_ids": ids, "tags": ["walden", "Imported"] }))
+        .to_request();
+    let tagged: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(tagged["succeeded"], 2);
+
+    let req = test::TestRequest::post()
+        .uri("/api/notes/bulk")
+        .set_json(json!({ "action": "remove_tags", "note_ids": ids, "tags": ["imported"] }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+
+    let req = test::TestRequest::post()
+        .uri("/api/notes/bulk")
+        .set_json(json!({ "action": "change_type", "note_ids": [ids[0], 999999], "note_type": "quote" }))
+        .to_request();
+    let retyped: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(retyped["succeeded"], 1);
+    assert_eq!(retyped["results"][1]["error"]["error"], "NOT_FOUND");
+
+    let req = test::TestRequest::post()
+        .uri("/api/notes/bulk")
+        .set_json(json!({ "action": "set_favorite", "note_ids": ids, "is_favorite": true }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/notes/{}", ids[0]))
+        .to_request();
+    let note: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(note["tags"], json!(["walden"]));
+    assert_eq!(note["note_type"], "quote");
+    assert_eq!(note["is_favorite"], true);
+
+    let req = test::TestRequest::post()
+        .uri("/api/notes/bulk")
+        .set_json(json!({ "action": "delete", "note_ids": ids }))
+        .to_request();
+    let deleted: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(deleted["succeeded"], 2);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/books/{}/notes", book["id"]))
+        .to_request();
+    let list: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(list["total"], 0);
+
+    let req = test::TestRequest::post()
+        .uri("/api/notes/bulk")
+        .set_json(json!({ "action": "delete", "note_ids": [] }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 422);
+}
+
+/// Test moving notes to another book keeps threads that move together
+#[actix_web::test]
+async fn test_bulk_move_to_book() {
+    let test_db = common::setup_test_db();
+    let app = test::init_service(create_app(test_db.pool.clone())).await;
+
+    let mut books = Vec::new();
+    for (title, page_count) in [("Imported", 500), ("Essays", 100)] {
+        let req = test::TestRequest::post()
+            .uri("/api/books")
+            .set_json(json!({ "title": title, "author": "Ralph Waldo Emerson", "page_count": page_count }))
+            .to_request();
+        let book: Value = test::read_body_json(test::call_service(&app, req).await).await;
+        books.push(book);
+    }
+
+    let create = |content: &str, parent: Option<&Value>, page: Option<&str>| {
+        test::TestRequest::post()
+            .uri("/api/notes")
+            .set_json(json!({
+                "book_id": books[0]["id"],
+                "content": content,
+                "parent_id": parent.map(|p| p["id"].clone()),
+                "page_reference": page
+            }))
+            .to_request()
+    };
+    let root: Value = test::read_body_json(test::call_service(&app, create("Self-reliance", None, None)).await).await;
+    let reply: Value = test::read_body_json(test::call_service(&app, create("Trust thyself", Some(&root), None)).await).await;
+    let stays: Value = test::read_body_json(test::call_service(&app, create("Left behind", Some(&root), None)).await).await;
+    let late: Value = test::read_body_json(test::call_service(&app, create("Late page", None, Some("p. 400"))).await).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/notes/bulk")
+        .set_json(json!({ "action": "move_to_book", "note_ids": [reply["id"], root["id"], late["id"]], "book_id": books[1]["id"] }))
+        .to_request();
+    let moved: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(moved["succeeded"], 2);
+    assert_eq!(moved["results"][2]["error"]["error"], "VALIDATION_ERROR");
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/notes/{}/thread", root["id"]))
+        .to_request();
+    let thread: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(thread["note"]["book_id"], books[1]["id"]);
+    assert_eq!(thread["replies"].as_array().unwrap().len(), 1);
+    assert_eq!(thread["replies"][0]["note"]["id"], reply["id"]);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/notes/{}", stays["id"]))
+        .to_request();
+    let stays: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(stays["book_id"], books[0]["id"]);
+    assert_eq!(stays["parent_id"], Value::Null);
+
+    let req = test::TestRequest::post()
+        .uri("/api/notes/bulk")
+        .set_json(json!({ "action": "move_to_book", "note_ids": [late["id"]], "book_id": 999999 }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+}
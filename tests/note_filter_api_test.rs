@@ -0,0 +1,138 @@
+//! Integration tests for note list filters and sort orders
+
+mod common;
+
+use actix_web::test;
+use reading_notes_backend::create_app;
+use serde_json::{json, Value};
+
+/// Test that filters by book, tags and favorite flag compose
+#[actix_web::test]
+async fn test_filter_notes_by_book_tags_and_favorite() {
+    let test_db = common::setup_test_db();
+    let app = test::init_service(create_app(test_db.pool.clone())).await;
+
+    let mut book_ids = Vec::new();
+    for title in ["Walden", "Silent Spring"] {
+        let req = test::TestRequest::post()
+            .uri("/api/books")
+            .set_json(json!({ "title": title, "author": "Various" }))
+            .to_request();
+        let book: Value = test::read_body_json(test::call_service(&app, req).await).await;
+        book_ids.push(book["id"].clone());
+    }
+
+    for (book_id, content, tags, is_favorite) in [
+        (&book_ids[0], "Simplify, simplify", vec!["Nature", "Philosophy"], true),
+        (&book_ids[0], "The pond in winter", vec!["Nature"], false),
+        (&book_ids[1], "Pesticides in the food chain", vec!["Nature", "Science"], true),
+    ] {
+        let req = test::TestRequest::post()
+            .uri("/api/notes")
+            .set_json(json!({ "book_id": book_id, "content": content, "tags": tags, "is_favorite": is_favorite }))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 201);
+    }
+
+    let contents = |list: &Value| -> Vec<String> {
+        list["notes"].as_array().unwrap().iter().map(|n| n["content"].as_str().unwrap().to_string()).collect()
+    };
+
+    // Tags match by name or slug, and every tag must be present
+    let req = test::TestRequest::get().uri("/api/notes?tags=nature,PHILOSOPHY").to_request();
+    let list: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(contents(&list), vec!["Simplify, simplify"]);
+
+    let req = test::TestRequest::get().uri("/api/notes?tags=Nature&is_favorite=true&sort=title").to_request();
+    let list: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(list["total"], 2);
+    assert_eq!(contents(&list), vec!["Pesticides in the food chain", "Simplify, simplify"]);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/notes?book_id={}&is_favorite=false", book_ids[0]))
+        .to_request();
+    let list: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(contents(&list), vec!["The pond in winter"]);
+
+    // Alphabetical by book title: Silent Spring before Walden
+    let req = test::TestRequest::get().uri("/api/notes?sort=book").to_request();
+    let list: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(list["notes"][0]["book_id"], book_ids[1]);
+    assert_eq!(list["notes"][2]["book_id"], book_ids[0]);
+}
+
+/// Test date range filters and sorting by last update
+#[actix_web::test]
+async fn test_filter_notes_by_date_and_sort_by_updated() {
+    let test_db = common::setup_test_db();
+    let app = test::init_service(create_app(test_db.pool.clone())).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(json!({ "title": "The Old Man and the Sea", "author": "Ernest Hemingway" }))
+        .to_request();
+    let book: Value = test::read_body_json(test::call_service(&app, req).await).await;
+
+    let mut notes = Vec::new();
+    for content in ["First", "Second"] {
+        let req = test::TestRequest::post()
+            .uri("/api/notes")
+            .set_json(json!({ "book_id": book["id"], "content": content }))
+            .to_request();
+        let note: Value = test::read_body_json(test::call_service(&app, req).await).await;
+        notes.push(note);
+    }
+
+    let req = test::TestRequest::patch()
+        .uri(&format!("/api/notes/{}", notes[0]["id"]))
+        .set_json(json!({ "is_favorite": true }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+
+    // Imported notes may lack a favorite flag and an update time
+    let imported_id = {
+        use diesel::prelude::*;
+        use reading_notes_backend::db::schema::reading_notes;
+        let mut conn = test_db.pool.get().unwrap();
+        diesel::insert_into(reading_notes::table)
+            .values((
+                reading_notes::book_id.eq(book["id"].as_i64().unwrap()),
+                reading_notes::content.eq("Imported"),
+                reading_notes::is_favorite.eq(None::<bool>),
+                reading_notes::updated_at.eq(None::<chrono::DateTime<chrono::Utc>>),
+            ))
+            .returning(reading_notes::id)
+            .get_result::<i64>(&mut conn)
+            .unwrap()
+    };
+
+    let req = test::TestRequest::get().uri("/api/notes?sort=updated").to_request();
+    let list: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(list["notes"][0]["id"], notes[0]["id"]);
+    assert_eq!(list["notes"][2]["id"], imported_id);
+
+    let req = test::TestRequest::get().uri("/api/notes?is_favorite=false").to_request();
+    let list: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(list["total"], 2);
+
+    let today = chrono::Utc::now().date_naive();
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/notes?created_from={}&created_to={}", today, today))
+        .to_request();
+    let list: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(list["total"], 3);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/notes?updated_from={}", today.succ_opt().unwrap()))
+        .to_request();
+    let list: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(list["total"], 0);
+
+    let req = test::TestRequest::get()
+        .uri("/api/notes?created_from=2024-06-01&created_to=2024-01-01")
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 422);
+
+    let req = test::TestRequest::get().uri("/api/notes?sort=random").to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 422);
+}
@@ -0,0 +1,150 @@
+//! Integration tests for wiki-style links between notes and backlinks
+
+mod common;
+
+use actix_web::test;
+use reading_notes_backend::create_app;
+use serde_json::{json, Value};
+
+/// Test that links resolve to notes and books and show up as backlinks
+#[actix_web::test]
+async fn test_note_links_and_backlinks() {
+    let test_db = common::setup_test_db();
+    let app = test::init_service(create_app(test_db.pool.clone())).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(json!({ "title": "Thinking, Fast and Slow", "author": "Daniel Kahneman" }))
+        .to_request();
+    let book: Value = test::read_body_json(test::call_service(&app, req).await).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/notes")
+        .set_json(json!({ "book_id": book["id"], "title": "System 1", "content": "Fast, intuitive thinking" }))
+        .to_request();
+    let target: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    let target_id = target["id"].as_i64().unwrap();
+
+    let req = test::TestRequest::post()
+        .uri("/api/notes")
+        .set_json(json!({
+            "book_id": book["id"],
+            "content": format!("Contrast with [[note:{}]], see [[thinking, fast and slow]] and [[Nudge]]", target_id)
+        }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+    let source: Value = test::read_body_json(resp).await;
+
+    let links = source["links"].as_array().unwrap();
+    assert_eq!(links.len(), 3);
+    assert_eq!(links[0]["target_type"], "note");
+    assert_eq!(links[0]["target_id"], target_id);
+    assert_eq!(links[0]["target_title"], "System 1");
+    assert_eq!(links[1]["target_type"], "book");
+    assert_eq!(links[1]["target_id"], book["id"]);
+    assert_eq!(links[1]["broken"], false);
+    // No book with this title
+    assert!(links[2]["target_id"].is_null());
+    assert_eq!(links[2]["broken"], true);
+
+    // Links resolve once their target is created
+    let req = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(json!({ "title": "Nudge", "author": "Thaler and Sunstein" }))
+        .to_request();
+    let nudge: Value = test::read_body_json(test::call_service(&app, req).await).await;
+
+    let next_id = source["id"].as_i64().unwrap() + 2;
+    let req = test::TestRequest::post()
+        .uri("/api/notes")
+        .set_json(json!({ "book_id": book["id"], "content": format!("Continued in [[note:{}]]", next_id) }))
+        .to_request();
+    let pending: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(pending["links"][0]["broken"], true);
+
+    let req = test::TestRequest::post()
+        .uri("/api/notes")
+        .set_json(json!({ "book_id": book["id"], "content": "System 2" }))
+        .to_request();
+    let next: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(next["id"], next_id);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/notes/{}/backlinks", next_id))
+        .to_request();
+    let backlinks: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(backlinks[0]["id"], pending["id"]);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/notes/{}", source["id"]))
+        .to_request();
+    let source: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(source["links"][2]["target_id"], nudge["id"]);
+    assert_eq!(source["links"][2]["broken"], false);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/notes/{}/backlinks", target_id))
+        .to_request();
+    let backlinks: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(backlinks.as_array().unwrap().len(), 1);
+    assert_eq!(backlinks[0]["id"], source["id"]);
+
+    // Removing the link from the content removes the backlink
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/notes/{}", source["id"]))
+        .set_json(json!({ "content": "No more links" }))
+        .to_request();
+    let updated: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(updated["links"], json!([]));
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/notes/{}/backlinks", target_id))
+        .to_request();
+    let backlinks: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(backlinks, json!([]));
+}
+
+/// Test that links to soft-deleted targets are flagged as broken
+#[actix_web::test]
+async fn test_broken_links_to_deleted_targets() {
+    let test_db = common::setup_test_db();
+    let app = test::init_service(create_app(test_db.pool.clone())).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(json!({ "title": "Sapiens", "author": "Yuval Noah Harari" }))
+        .to_request();
+    let book: Value = test::read_body_json(test::call_service(&app, req).await).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/notes")
+        .set_json(json!({ "book_id": book["id"], "content": "The cognitive revolution" }))
+        .to_request();
+    let target: Value = test::read_body_json(test::call_service(&app, req).await).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/notes")
+        .set_json(json!({ "book_id": book["id"], "content": format!("Builds on [[note:{}]]", target["id"]) }))
+        .to_request();
+    let source: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(source["links"][0]["broken"], false);
+
+    let req = test::TestRequest::delete()
+        .uri(&format!("/api/notes/{}", target["id"]))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 204);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/notes/{}", source["id"]))
+        .to_request();
+    let source: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(source["links"][0]["target_id"], target["id"]);
+    assert_eq!(source["links"][0]["broken"], true);
+
+    // Backlinks of a deleted note are not available
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/notes/{}/backlinks", target["id"]))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+}
@@ -0,0 +1,96 @@
+//! Integration tests for structured note locations
+
+mod common;
+
+use actix_web::test;
+use reading_notes_backend::create_app;
+use serde_json::{json, Value};
+
+/// Test that page references are parsed and notes can be listed in reading order
+#[actix_web::test]
+async fn test_sort_book_notes_by_position() {
+    let test_db = common::setup_test_db();
+    let app = test::init_service(create_app(test_db.pool.clone())).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(json!({ "title": "Middlemarch", "author": "George Eliot", "page_count": 880 }))
+        .to_request();
+    let book: Value = test::read_body_json(test::call_service(&app, req).await).await;
+
+    for (content, page_reference) in [
+        ("Late", Some("pp. 700-702")),
+        ("Unplaced", None),
+        ("Early", Some("p.5")),
+        ("Middle", Some("Chapter 20, Pages 210-215")),
+    ] {
+        let req = test::TestRequest::post()
+            .uri("/api/notes")
+            .set_json(json!({ "book_id": book["id"], "content": content, "page_reference": page_reference }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 201);
+        let note: Value = test::read_body_json(resp).await;
+        if content == "Middle" {
+            assert_eq!(note["page_reference"], "Chapter 20, Pages 210-215");
+            assert_eq!(
+                note["location"],
+                json!({ "page_start": 210, "page_end": 215, "chapter": 20, "kindle_location": null, "percentage": null })
+            );
+        }
+    }
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/books/{}/notes?sort=position", book["id"]))
+        .to_request();
+    let list: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    let contents: Vec<&str> = list["notes"].as_array().unwrap().iter().map(|n| n["content"].as_str().unwrap()).collect();
+    assert_eq!(contents, vec!["Early", "Middle", "Late", "Unplaced"]);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/books/{}/notes?sort=alphabetical", book["id"]))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 422);
+}
+
+/// Test that page numbers beyond the book's page count are rejected
+#[actix_web::test]
+async fn test_reject_pages_beyond_page_count() {
+    let test_db = common::setup_test_db();
+    let app = test::init_service(create_app(test_db.pool.clone())).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(json!({ "title": "Animal Farm", "author": "George Orwell", "page_count": 112 }))
+        .to_request();
+    let book: Value = test::read_body_json(test::call_service(&app, req).await).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/notes")
+        .set_json(json!({ "book_id": book["id"], "content": "All animals are equal", "page_reference": "Pages 110-130" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 422);
+
+    // Free text that is not a page number is kept as-is
+    let req = test::TestRequest::post()
+        .uri("/api/notes")
+        .set_json(json!({ "book_id": book["id"], "content": "All animals are equal", "page_reference": "Epilogue" }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+    let note: Value = test::read_body_json(resp).await;
+    assert!(note["location"].is_null());
+
+    let req = test::TestRequest::patch()
+        .uri(&format!("/api/notes/{}", note["id"]))
+        .set_json(json!({ "page_reference": "p. 200" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 422);
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/notes/{}", note["id"]))
+        .set_json(json!({ "page_reference": "p. 97" }))
+        .to_request();
+    let updated: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(updated["location"]["page_start"], 97);
+}
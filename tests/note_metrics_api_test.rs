@@ -0,0 +1,119 @@
+//! Integration tests for note length metrics and writing statistics
+
+mod common;
+
+use actix_web::test;
+use reading_notes_backend::create_app;
+use serde_json::{json, Value};
+
+/// Test that word counts and reading time are stored on create and update
+#[actix_web::test]
+async fn test_note_metrics() {
+    let test_db = common::setup_test_db();
+    let app = test::init_service(create_app(test_db.pool.clone())).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(json!({ "title": "The Analects", "author": "Confucius" }))
+        .to_request();
+    let book: Value = test::read_body_json(test::call_service(&app, req).await).await;
+
+    // Markdown markup is not counted
+    let req = test::TestRequest::post()
+        .uri("/api/notes")
+        .set_json(json!({ "book_id": book["id"], "content": "**Learning** without thought is labor lost" }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+    let note: Value = test::read_body_json(resp).await;
+    assert_eq!(note["word_count"], 6);
+    assert_eq!(note["char_count"], 33);
+    assert_eq!(note["reading_time_seconds"], 2);
+
+    // Chinese text counts each character as a word
+    let req = test::TestRequest::patch()
+        .uri(&format!("/api/notes/{}", note["id"]))
+        .set_json(json!({ "content": "学而不思则罔，思而不学则殆。" }))
+        .to_request();
+    let note: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(note["word_count"], 12);
+    assert_eq!(note["char_count"], 14);
+    assert_eq!(note["reading_time_seconds"], 2);
+
+    // Changes that leave the content alone keep the metrics
+    let req = test::TestRequest::patch()
+        .uri(&format!("/api/notes/{}", note["id"]))
+        .set_json(json!({ "is_favorite": true }))
+        .to_request();
+    let unchanged: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(unchanged["word_count"], 12);
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/notes/{}", note["id"]))
+        .set_json(json!({ "content": "Is it not pleasant to learn?" }))
+        .to_request();
+    let note: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(note["word_count"], 6);
+}
+
+/// Test that writing statistics are aggregated per book and per month
+#[actix_web::test]
+async fn test_writing_statistics() {
+    let test_db = common::setup_test_db();
+    let app = test::init_service(create_app(test_db.pool.clone())).await;
+
+    let mut books = Vec::new();
+    for title in ["Walden", "Nature"] {
+        let req = test::TestRequest::post()
+            .uri("/api/books")
+            .set_json(json!({ "title": title, "author": "Various" }))
+            .to_request();
+        let book: Value = test::read_body_json(test::call_service(&app, req).await).await;
+        books.push(book);
+    }
+
+    for (book, content) in [
+        (&books[0], "I went to the woods"),
+        (&books[0], "Simplify, simplify"),
+        (&books[1], "Nature always wears the colors of the spirit"),
+    ] {
+        let req = test::TestRequest::post()
+            .uri("/api/notes")
+            .set_json(json!({ "book_id": book["id"], "content": content }))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 201);
+    }
+
+    // Trashed notes are left out
+    let req = test::TestRequest::post()
+        .uri("/api/notes")
+        .set_json(json!({ "book_id": books[1]["id"], "content": "Discarded draft" }))
+        .to_request();
+    let draft: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    let req = test::TestRequest::delete()
+        .uri(&format!("/api/notes/{}", draft["id"]))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 204);
+
+    let req = test::TestRequest::get().uri("/api/statistics/writing").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    let stats: Value = test::read_body_json(resp).await;
+    assert_eq!(stats["totals"]["note_count"], 3);
+    assert_eq!(stats["totals"]["word_count"], 15);
+
+    assert_eq!(stats["by_book"][0]["book_id"], books[1]["id"]);
+    assert_eq!(stats["by_book"][0]["word_count"], 8);
+    assert_eq!(stats["by_book"][1]["title"], "Walden");
+    assert_eq!(stats["by_book"][1]["note_count"], 2);
+    assert_eq!(stats["by_book"][1]["word_count"], 7);
+
+    assert_eq!(stats["by_month"].as_array().unwrap().len(), 1);
+    assert_eq!(stats["by_month"][0]["note_count"], 3);
+    assert_eq!(stats["by_month"][0]["word_count"], 15);
+
+    let req = test::TestRequest::get().uri("/api/statistics/writing?year=1999").to_request();
+    let stats: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(stats["totals"]["note_count"], 0);
+    assert_eq!(stats["by_book"], json!([]));
+}
@@ -0,0 +1,122 @@
+//! Integration tests for manual ordering and pinning of a book's notes
+
+mod common;
+
+use actix_web::test;
+use reading_notes_backend::create_app;
+use serde_json::{json, Value};
+
+/// Test that a book's notes follow the manual order with pinned notes first
+#[actix_web::test]
+async fn test_reorder_and_pin_book_notes() {
+    let test_db = common::setup_test_db();
+    let app = test::init_service(create_app(test_db.pool.clone())).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(json!({ "title": "The Art of Learning", "author": "Josh Waitzkin" }))
+        .to_request();
+    let book: Value = test::read_body_json(test::call_service(&app, req).await).await;
+
+    let mut ids = Vec::new();
+    for content in ["Introduction", "Beginner's mind", "Loss", "Investment in loss"] {
+        let req = test::TestRequest::post()
+            .uri("/api/notes")
+            .set_json(json!({ "book_id": book["id"], "content": content }))
+            .to_request();
+        let note: Value = test::read_body_json(test::call_service(&app, req).await).await;
+        ids.push(note["id"].as_i64().unwrap());
+    }
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/books/{}/notes/order", book["id"]))
+        .set_json(json!({ "note_ids": [ids[2], ids[0], ids[1]] }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    let notes: Value = test::read_body_json(resp).await;
+    let order: Vec<i64> = notes.as_array().unwrap().iter().map(|n| n["id"].as_i64().unwrap()).collect();
+    assert_eq!(order, vec![ids[2], ids[0], ids[1], ids[3]]);
+    assert_eq!(notes[0]["sort_order"], 0);
+    assert_eq!(notes[3]["sort_order"], Value::Null);
+
+    let req = test::TestRequest::patch()
+        .uri(&format!("/api/notes/{}", ids[1]))
+        .set_json(json!({ "is_pinned": true }))
+        .to_request();
+    let pinned: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(pinned["is_pinned"], true);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/books/{}/notes", book["id"]))
+        .to_request();
+    let list: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    let order: Vec<i64> = list["notes"].as_array().unwrap().iter().map(|n| n["id"].as_i64().unwrap()).collect();
+    assert_eq!(order, vec![ids[1], ids[2], ids[0], ids[3]]);
+
+    // Pinned notes also come first in other sort orders
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/books/{}/notes?sort=created", book["id"]))
+        .to_request();
+    let list: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    let order: Vec<i64> = list["notes"].as_array().unwrap().iter().map(|n| n["id"].as_i64().unwrap()).collect();
+    assert_eq!(order, vec![ids[1], ids[3], ids[2], ids[0]]);
+
+    let req = test::TestRequest::patch()
+        .uri(&format!("/api/notes/{}", ids[1]))
+        .set_json(json!({ "is_pinned": null }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 422);
+}
+
+/// Test that invalid orders are rejected without changing the notes
+#[actix_web::test]
+async fn test_reorder_rejects_invalid_notes() {
+    let test_db = common::setup_test_db();
+    let app = test::init_service(create_app(test_db.pool.clone())).await;
+
+    let mut books = Vec::new();
+    for title in ["Mastery", "Range"] {
+        let req = test::TestRequest::post()
+            .uri("/api/books")
+            .set_json(json!({ "title": title, "author": "Various" }))
+            .to_request();
+        let book: Value = test::read_body_json(test::call_service(&app, req).await).await;
+        books.push(book);
+    }
+
+    let mut ids = Vec::new();
+    for book in &books {
+        let req = test::TestRequest::post()
+            .uri("/api/notes")
+            .set_json(json!({ "book_id": book["id"], "content": "A note" }))
+            .to_request();
+        let note: Value = test::read_body_json(test::call_service(&app, req).await).await;
+        ids.push(note["id"].as_i64().unwrap());
+    }
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/books/{}/notes/order", books[0]["id"]))
+        .set_json(json!({ "note_ids": [ids[0], ids[1]] }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 422);
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/books/{}/notes/order", books[0]["id"]))
+        .set_json(json!({ "note_ids": [ids[0], ids[0]] }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 422);
+
+    let req = test::TestRequest::put()
+        .uri("/api/books/999999/notes/order")
+        .set_json(json!({ "note_ids": [] }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/notes/{}", ids[0]))
+        .to_request();
+    let note: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(note["sort_order"], Value::Null);
+    assert_eq!(note["is_pinned"], false);
+}
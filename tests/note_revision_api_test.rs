@@ -0,0 +1,119 @@
+//! Integration tests for note revision history
+
+mod common;
+
+use actix_web::test;
+use reading_notes_backend::create_app;
+use serde_json::{json, Value};
+
+/// Test that updates keep prior versions and that revisions can be diffed
+#[actix_web::test]
+async fn test_note_revisions_and_diff() {
+    let test_db = common::setup_test_db();
+    let app = test::init_service(create_app(test_db.pool.clone())).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(json!({ "title": "Meditations", "author": "Marcus Aurelius" }))
+        .to_request();
+    let book: Value = test::read_body_json(test::call_service(&app, req).await).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/notes")
+        .set_json(json!({ "book_id": book["id"], "content": "You have power over your mind" }))
+        .to_request();
+    let note: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    let note_id = note["id"].as_i64().unwrap();
+
+    for content in ["You have power over your mind, not outside events", "Realize this and you will find strength"] {
+        let req = test::TestRequest::put()
+            .uri(&format!("/api/notes/{}", note_id))
+            .set_json(json!({ "content": content }))
+            .to_request();
+        assert!(test::call_service(&app, req).await.status().is_success());
+    }
+
+    // Changing only the favorite flag does not create a revision
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/notes/{}", note_id))
+        .set_json(json!({ "is_favorite": true }))
+        .to_request();
+    assert!(test::call_service(&app, req).await.status().is_success());
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/notes/{}/revisions", note_id))
+        .to_request();
+    let revisions: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    let revisions = revisions.as_array().unwrap();
+    assert_eq!(revisions.len(), 2);
+    assert_eq!(revisions[0]["revision"], 2);
+    assert_eq!(revisions[1]["revision"], 1);
+    assert_eq!(revisions[1]["content"], "You have power over your mind");
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/notes/{}/revisions/diff?from=1&to=2", note_id))
+        .to_request();
+    let diff: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(
+        diff["segments"],
+        json!([
+            { "op": "equal", "text": "You have power over your " },
+            { "op": "delete", "text": "mind" },
+            { "op": "insert", "text": "mind, not outside events" }
+        ])
+    );
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/notes/{}/revisions/diff?from=9", note_id))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+}
+
+/// Test restoring a prior revision
+#[actix_web::test]
+async fn test_restore_note_revision() {
+    let test_db = common::setup_test_db();
+    let app = test::init_service(create_app(test_db.pool.clone())).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(json!({ "title": "Letters from a Stoic", "author": "Seneca" }))
+        .to_request();
+    let book: Value = test::read_body_json(test::call_service(&app, req).await).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/notes")
+        .set_json(json!({ "book_id": book["id"], "title": "Letter 1", "content": "Hold every hour in your grasp" }))
+        .to_request();
+    let note: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    let note_id = note["id"].as_i64().unwrap();
+
+    // An accidental edit overwrites the quote
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/notes/{}", note_id))
+        .set_json(json!({ "title": "Oops", "content": "oops" }))
+        .to_request();
+    assert!(test::call_service(&app, req).await.status().is_success());
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/notes/{}/revisions/1/restore", note_id))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    let restored: Value = test::read_body_json(resp).await;
+    assert_eq!(restored["title"], "Letter 1");
+    assert_eq!(restored["content"], "Hold every hour in your grasp");
+
+    // The overwritten version is kept, so the restore can be undone
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/notes/{}/revisions", note_id))
+        .to_request();
+    let revisions: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(revisions[0]["revision"], 2);
+    assert_eq!(revisions[0]["content"], "oops");
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/notes/{}/revisions/7/restore", note_id))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+}
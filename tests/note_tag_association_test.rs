@@ -25,6 +25,7 @@ async fn test_note_tag_basic_association() {
         page_count: None,
         cover_image: None,
         description: None,
+        ..Default::default()
     };
 
     let req = test::TestRequest::post()
@@ -96,6 +97,7 @@ async fn test_tag_usage_count_multiple_notes() {
         page_count: None,
         cover_image: None,
         description: None,
+        ..Default::default()
     };
 
     let req = test::TestRequest::post()
@@ -194,6 +196,7 @@ async fn test_update_note_tags_usage_count() {
         page_count: None,
         cover_image: None,
         description: None,
+        ..Default::default()
     };
 
     let req = test::TestRequest::post()
@@ -289,6 +292,7 @@ async fn test_partial_tag_update() {
         page_count: None,
         cover_image: None,
         description: None,
+        ..Default::default()
     };
 
     let req = test::TestRequest::post()
@@ -379,6 +383,7 @@ async fn test_tag_usage_with_note_deletion() {
         page_count: None,
         cover_image: None,
         description: None,
+        ..Default::default()
     };
 
     let req = test::TestRequest::post()
@@ -474,6 +479,7 @@ async fn test_complex_tag_associations() {
         page_count: None,
         cover_image: None,
         description: None,
+        ..Default::default()
     };
 
     let req = test::TestRequest::post()
@@ -565,6 +571,7 @@ async fn test_empty_tags_array() {
         page_count: None,
         cover_image: None,
         description: None,
+        ..Default::default()
     };
 
     let req = test::TestRequest::post()
@@ -635,6 +642,7 @@ async fn test_update_to_empty_tags() {
         page_count: None,
         cover_image: None,
         description: None,
+        ..Default::default()
     };
 
     let req = test::TestRequest::post()
@@ -0,0 +1,165 @@
+//! Integration tests for note templates
+
+mod common;
+
+use actix_web::test;
+use chrono::Utc;
+use reading_notes_backend::create_app;
+use serde_json::{json, Value};
+
+/// Test that a note created from a template gets the rendered content, type and tags
+#[actix_web::test]
+async fn test_create_note_from_template() {
+    let test_db = common::setup_test_db();
+    let app = test::init_service(create_app(test_db.pool.clone())).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(json!({ "title": "Deep Work", "author": "Cal Newport" }))
+        .to_request();
+    let book: Value = test::read_body_json(test::call_service(&app, req).await).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/note-templates")
+        .set_json(json!({
+            "name": "Book summary",
+            "title": "Summary of {{ book.title }}",
+            "content": "# {{book.title}} by {{book.author}}\n\nRead on {{today}} ({{book.isbn}})\n\n## Key ideas\n\n## Quotes\n\n## Actions\n",
+            "note_type": "summary",
+            "tags": ["summary", "to review"]
+        }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+    let template: Value = test::read_body_json(resp).await;
+    assert_eq!(template["tags"], json!(["summary", "to review"]));
+
+    let req = test::TestRequest::post()
+        .uri("/api/notes/from-template")
+        .set_json(json!({ "template_id": template["id"], "book_id": book["id"], "page_reference": "p. 12" }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+    let note: Value = test::read_body_json(resp).await;
+
+    let today = Utc::now().date_naive();
+    assert_eq!(note["title"], "Summary of Deep Work");
+    assert_eq!(
+        note["content"],
+        format!("# Deep Work by Cal Newport\n\nRead on {} ()\n\n## Key ideas\n\n## Quotes\n\n## Actions\n", today)
+    );
+    assert_eq!(note["note_type"], "summary");
+    assert_eq!(note["book_id"], book["id"]);
+    let mut tags: Vec<&str> = note["tags"].as_array().unwrap().iter().map(|t| t.as_str().unwrap()).collect();
+    tags.sort();
+    assert_eq!(tags, vec!["summary", "to review"]);
+
+    let req = test::TestRequest::post()
+        .uri("/api/notes/from-template")
+        .set_json(json!({ "template_id": template["id"], "book_id": 999999 }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+
+    // A long book title can render a title that does not fit a note
+    let req = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(json!({ "title": "Long ".repeat(30), "author": "Anonymous" }))
+        .to_request();
+    let long_book: Value = test::read_body_json(test::call_service(&app, req).await).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/notes/from-template")
+        .set_json(json!({ "template_id": template["id"], "book_id": long_book["id"] }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 201);
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/note-templates/{}", template["id"]))
+        .set_json(json!({ "title": "{{book.title}} and {{book.title}}" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+
+    let req = test::TestRequest::post()
+        .uri("/api/notes/from-template")
+        .set_json(json!({ "template_id": template["id"], "book_id": long_book["id"] }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 422);
+}
+
+/// Test template validation, updates and deletion
+#[actix_web::test]
+async fn test_note_template_management() {
+    let test_db = common::setup_test_db();
+    let app = test::init_service(create_app(test_db.pool.clone())).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/note-templates")
+        .set_json(json!({ "name": "Broken", "content": "{{book.name}}" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 422);
+
+    let req = test::TestRequest::post()
+        .uri("/api/note-templates")
+        .set_json(json!({ "name": "Broken", "content": "Notes", "note_type": "unknown" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 422);
+
+    for body in [
+        json!({ "name": "n".repeat(101), "content": "Notes" }),
+        json!({ "name": "Broken", "title": "t".repeat(201), "content": "Notes" }),
+        json!({ "name": "Broken", "content": "Notes", "tags": ["t".repeat(31)] }),
+    ] {
+        let req = test::TestRequest::post().uri("/api/note-templates").set_json(body).to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 422);
+    }
+
+    let req = test::TestRequest::post()
+        .uri("/api/note-types")
+        .set_json(json!({ "key": "reflection", "name": "Reflection" }))
+        .to_request();
+    let note_type: Value = test::read_body_json(test::call_service(&app, req).await).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/note-templates")
+        .set_json(json!({ "name": "Reflection", "content": "What did {{book.author}} change my mind about?", "note_type": "reflection" }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+    let template: Value = test::read_body_json(resp).await;
+    assert_eq!(template["tags"], json!([]));
+
+    let req = test::TestRequest::post()
+        .uri("/api/note-templates")
+        .set_json(json!({ "name": "Reflection", "content": "Duplicate" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 409);
+
+    // Note types used by a template cannot be deleted
+    let req = test::TestRequest::delete()
+        .uri(&format!("/api/note-types/{}", note_type["id"]))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 409);
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/note-templates/{}", template["id"]))
+        .set_json(json!({ "content": "Lessons from {{ book.title }}", "tags": ["reflection"] }))
+        .to_request();
+    let updated: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(updated["content"], "Lessons from {{ book.title }}");
+    assert_eq!(updated["tags"], json!(["reflection"]));
+    assert_eq!(updated["name"], "Reflection");
+
+    let req = test::TestRequest::get().uri("/api/note-templates").to_request();
+    let templates: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(templates.as_array().unwrap().len(), 1);
+
+    let req = test::TestRequest::delete()
+        .uri(&format!("/api/note-templates/{}", template["id"]))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 204);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/note-templates/{}", template["id"]))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+}
@@ -0,0 +1,159 @@
+//! Integration tests for threaded notes
+
+mod common;
+
+use actix_web::test;
+use reading_notes_backend::create_app;
+use serde_json::{json, Value};
+
+/// Test that replies form a thread and can be collapsed in listings
+#[actix_web::test]
+async fn test_note_thread() {
+    let test_db = common::setup_test_db();
+    let app = test::init_service(create_app(test_db.pool.clone())).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(json!({ "title": "Meditations", "author": "Marcus Aurelius" }))
+        .to_request();
+    let book: Value = test::read_body_json(test::call_service(&app, req).await).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/notes")
+        .set_json(json!({ "book_id": book["id"], "content": "You have power over your mind", "note_type": "quote" }))
+        .to_request();
+    let quote: Value = test::read_body_json(test::call_service(&app, req).await).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/notes")
+        .set_json(json!({ "book_id": book["id"], "content": "Not over events", "note_type": "thought", "parent_id": quote["id"] }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+    let thought: Value = test::read_body_json(resp).await;
+    assert_eq!(thought["parent_id"], quote["id"]);
+
+    let req = test::TestRequest::post()
+        .uri("/api/notes")
+        .set_json(json!({ "book_id": book["id"], "content": "Compare with Epictetus", "parent_id": thought["id"] }))
+        .to_request();
+    let follow_up: Value = test::read_body_json(test::call_service(&app, req).await).await;
+
+    // The whole thread is returned from any of its notes
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/notes/{}/thread", follow_up["id"]))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    let thread: Value = test::read_body_json(resp).await;
+    assert_eq!(thread["note"]["id"], quote["id"]);
+    assert_eq!(thread["note"]["reply_count"], 1);
+    assert_eq!(thread["replies"][0]["note"]["id"], thought["id"]);
+    assert_eq!(thread["replies"][0]["replies"][0]["note"]["id"], follow_up["id"]);
+    assert_eq!(thread["replies"][0]["replies"][0]["replies"], json!([]));
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/books/{}/notes?collapse_replies=true", book["id"]))
+        .to_request();
+    let list: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(list["total"], 1);
+    assert_eq!(list["notes"][0]["id"], quote["id"]);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/notes?book_id={}", book["id"]))
+        .to_request();
+    let list: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(list["total"], 3);
+
+    // Replies to a trashed note stay reachable as top-level notes
+    let req = test::TestRequest::delete()
+        .uri(&format!("/api/notes/{}", quote["id"]))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 204);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/notes?book_id={}&collapse_replies=true", book["id"]))
+        .to_request();
+    let list: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(list["total"], 1);
+    assert_eq!(list["notes"][0]["id"], thought["id"]);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/notes/{}/thread", follow_up["id"]))
+        .to_request();
+    let thread: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(thread["note"]["id"], thought["id"]);
+    assert_eq!(thread["replies"][0]["note"]["id"], follow_up["id"]);
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/notes/{}/restore", quote["id"]))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/notes/{}/thread", thought["id"]))
+        .to_request();
+    let thread: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(thread["note"]["id"], quote["id"]);
+}
+
+/// Test that replies must stay within a book and cannot form cycles
+#[actix_web::test]
+async fn test_invalid_replies_are_rejected() {
+    let test_db = common::setup_test_db();
+    let app = test::init_service(create_app(test_db.pool.clone())).await;
+
+    let mut books = Vec::new();
+    for title in ["Letters from a Stoic", "Discourses"] {
+        let req = test::TestRequest::post()
+            .uri("/api/books")
+            .set_json(json!({ "title": title, "author": "Various" }))
+            .to_request();
+        let book: Value = test::read_body_json(test::call_service(&app, req).await).await;
+        books.push(book);
+    }
+
+    let req = test::TestRequest::post()
+        .uri("/api/notes")
+        .set_json(json!({ "book_id": books[0]["id"], "content": "On the shortness of life" }))
+        .to_request();
+    let root: Value = test::read_body_json(test::call_service(&app, req).await).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/notes")
+        .set_json(json!({ "book_id": books[1]["id"], "content": "Elsewhere", "parent_id": root["id"] }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 422);
+
+    let req = test::TestRequest::post()
+        .uri("/api/notes")
+        .set_json(json!({ "book_id": books[0]["id"], "content": "Orphan", "parent_id": 999999 }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+
+    let req = test::TestRequest::post()
+        .uri("/api/notes")
+        .set_json(json!({ "book_id": books[0]["id"], "content": "Reply", "parent_id": root["id"] }))
+        .to_request();
+    let reply: Value = test::read_body_json(test::call_service(&app, req).await).await;
+
+    let req = test::TestRequest::patch()
+        .uri(&format!("/api/notes/{}", root["id"]))
+        .set_json(json!({ "parent_id": reply["id"] }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 422);
+
+    let req = test::TestRequest::patch()
+        .uri(&format!("/api/notes/{}", root["id"]))
+        .set_json(json!({ "parent_id": root["id"] }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 422);
+
+    // Detaching a reply makes it a top-level note
+    let req = test::TestRequest::patch()
+        .uri(&format!("/api/notes/{}", reply["id"]))
+        .set_json(json!({ "parent_id": null }))
+        .to_request();
+    let detached: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(detached["parent_id"], Value::Null);
+}
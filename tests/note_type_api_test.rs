@@ -0,0 +1,138 @@
+//! Integration tests for user-definable note types
+
+mod common;
+
+use actix_web::test;
+use reading_notes_backend::create_app;
+use serde_json::{json, Value};
+
+/// Test that user-defined types can be created, used on notes and managed
+#[actix_web::test]
+async fn test_custom_note_type_lifecycle() {
+    let test_db = common::setup_test_db();
+    let app = test::init_service(create_app(test_db.pool.clone())).await;
+
+    let req = test::TestRequest::get().uri("/api/note-types").to_request();
+    let types: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    let keys: Vec<&str> = types.as_array().unwrap().iter().map(|t| t["key"].as_str().unwrap()).collect();
+    assert_eq!(keys, vec!["quote", "summary", "thought", "general"]);
+
+    let req = test::TestRequest::post()
+        .uri("/api/note-types")
+        .set_json(json!({ "key": "vocabulary", "name": "Vocabulary", "icon": "book", "color": "#0ea5e9" }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+    let vocabulary: Value = test::read_body_json(resp).await;
+    assert_eq!(vocabulary["is_builtin"], false);
+
+    let req = test::TestRequest::post()
+        .uri("/api/note-types")
+        .set_json(json!({ "key": "vocabulary", "name": "Words" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 409);
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/note-types/{}", vocabulary["id"]))
+        .set_json(json!({ "name": "New words", "color": "#22c55e" }))
+        .to_request();
+    let updated: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(updated["name"], "New words");
+    assert_eq!(updated["key"], "vocabulary");
+    assert_eq!(updated["icon"], "book");
+
+    let req = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(json!({ "title": "A Clockwork Orange", "author": "Anthony Burgess" }))
+        .to_request();
+    let book: Value = test::read_body_json(test::call_service(&app, req).await).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/notes")
+        .set_json(json!({ "book_id": book["id"], "content": "droog: friend", "note_type": "vocabulary" }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+    let note: Value = test::read_body_json(resp).await;
+    assert_eq!(note["note_type"], "vocabulary");
+
+    // Types in use cannot be deleted
+    let req = test::TestRequest::delete()
+        .uri(&format!("/api/note-types/{}", vocabulary["id"]))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 409);
+
+    let req = test::TestRequest::patch()
+        .uri(&format!("/api/notes/{}", note["id"]))
+        .set_json(json!({ "note_type": "quote" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+
+    let req = test::TestRequest::delete()
+        .uri(&format!("/api/note-types/{}", vocabulary["id"]))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 204);
+
+    // Built-in types cannot be deleted
+    let req = test::TestRequest::delete()
+        .uri(&format!("/api/note-types/{}", types[0]["id"]))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 409);
+}
+
+/// Test that unknown note types are rejected instead of becoming general notes
+#[actix_web::test]
+async fn test_reject_unknown_note_type() {
+    let test_db = common::setup_test_db();
+    let app = test::init_service(create_app(test_db.pool.clone())).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(json!({ "title": "Meditations", "author": "Marcus Aurelius" }))
+        .to_request();
+    let book: Value = test::read_body_json(test::call_service(&app, req).await).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/notes")
+        .set_json(json!({ "book_id": book["id"], "content": "Memento mori", "note_type": "question" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 422);
+
+    let req = test::TestRequest::post()
+        .uri("/api/notes")
+        .set_json(json!({ "book_id": book["id"], "content": "Memento mori", "note_type": "thought" }))
+        .to_request();
+    let note: Value = test::read_body_json(test::call_service(&app, req).await).await;
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/notes/{}", note["id"]))
+        .set_json(json!({ "note_type": "question" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 422);
+
+    let req = test::TestRequest::post()
+        .uri("/api/note-types")
+        .set_json(json!({ "key": "Action Item", "name": "Action item" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 422);
+
+    let req = test::TestRequest::post()
+        .uri("/api/note-types")
+        .set_json(json!({ "key": "action_item", "name": "Action item", "color": "red" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 422);
+
+    for body in [
+        json!({ "key": "action_item", "name": "a".repeat(51) }),
+        json!({ "key": "action_item", "name": "Action item", "icon": "  " }),
+    ] {
+        let req = test::TestRequest::post().uri("/api/note-types").set_json(body).to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 422);
+    }
+
+    let req = test::TestRequest::put()
+        .uri("/api/note-types/999999")
+        .set_json(json!({ "name": "Action item" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+}
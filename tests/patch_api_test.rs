@@ -0,0 +1,120 @@
+//! Integration tests for PATCH endpoints with JSON Merge Patch semantics
+
+mod common;
+
+use actix_web::{http::header, test};
+use reading_notes_backend::create_app;
+use serde_json::{json, Value};
+
+/// Test that PATCH clears fields sent as null and leaves absent fields unchanged
+#[actix_web::test]
+async fn test_patch_book_clears_null_fields() {
+    let test_db = common::setup_test_db();
+    let app = test::init_service(create_app(test_db.pool.clone())).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(json!({
+            "title": "Effective Java",
+            "author": "Joshua Bloch",
+            "isbn": "978-0134685991",
+            "publisher": "Addison-Wesley",
+            "cover_image": "https://example.com/cover.jpg"
+        }))
+        .to_request();
+    let book: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    let book_id = book["id"].as_i64().unwrap();
+
+    let req = test::TestRequest::patch()
+        .uri(&format!("/api/books/{}", book_id))
+        .insert_header((header::CONTENT_TYPE, "application/merge-patch+json"))
+        .set_payload(json!({ "isbn": null, "cover_image": null, "title": "Effective Java, 3rd Edition" }).to_string())
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+    let patched: Value = test::read_body_json(resp).await;
+    assert!(patched["isbn"].is_null());
+    assert!(patched["cover_image"].is_null());
+    assert_eq!(patched["title"], "Effective Java, 3rd Edition");
+    // Absent fields are untouched
+    assert_eq!(patched["publisher"], "Addison-Wesley");
+    assert_eq!(patched["author"], "Joshua Bloch");
+
+    // Required fields cannot be cleared
+    let req = test::TestRequest::patch()
+        .uri(&format!("/api/books/{}", book_id))
+        .set_json(json!({ "title": null }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 422);
+
+    // Only JSON objects are accepted as patch documents
+    let req = test::TestRequest::patch()
+        .uri(&format!("/api/books/{}", book_id))
+        .set_json(json!(["isbn"]))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 422);
+}
+
+/// Test patching notes and tags
+#[actix_web::test]
+async fn test_patch_note_and_tag() {
+    let test_db = common::setup_test_db();
+    let app = test::init_service(create_app(test_db.pool.clone())).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(json!({ "title": "Refactoring", "author": "Martin Fowler" }))
+        .to_request();
+    let book: Value = test::read_body_json(test::call_service(&app, req).await).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/notes")
+        .set_json(json!({
+            "book_id": book["id"],
+            "title": "Chapter 1",
+            "content": "Refactoring is a disciplined technique",
+            "page_reference": "p. 12"
+        }))
+        .to_request();
+    let note: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    let note_id = note["id"].as_i64().unwrap();
+
+    let req = test::TestRequest::patch()
+        .uri(&format!("/api/notes/{}", note_id))
+        .insert_header((header::CONTENT_TYPE, "application/merge-patch+json"))
+        .set_payload(json!({ "title": null, "page_reference": null }).to_string())
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+    let patched: Value = test::read_body_json(resp).await;
+    assert!(patched["title"].is_null());
+    assert!(patched["page_reference"].is_null());
+    assert_eq!(patched["content"], "Refactoring is a disciplined technique");
+
+    let req = test::TestRequest::patch()
+        .uri(&format!("/api/notes/{}", note_id))
+        .set_json(json!({ "is_favorite": null }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 422);
+
+    let req = test::TestRequest::post()
+        .uri("/api/tags")
+        .set_json(json!({ "name": "Classics" }))
+        .to_request();
+    let tag: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    let tag_id = tag["id"].as_i64().unwrap();
+
+    let req = test::TestRequest::patch()
+        .uri(&format!("/api/tags/{}", tag_id))
+        .set_json(json!({ "name": "Modern Classics" }))
+        .to_request();
+    let patched: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(patched["slug"], "modern-classics");
+
+    let req = test::TestRequest::patch()
+        .uri(&format!("/api/tags/{}", tag_id))
+        .insert_header((header::CONTENT_TYPE, "text/plain"))
+        .set_payload(r#"{"name": "Other"}"#)
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 400);
+}
@@ -0,0 +1,126 @@
+//! Integration tests for reading status and format-aware progress
+
+mod common;
+
+use actix_web::test;
+use reading_notes_backend::create_app;
+use serde_json::{json, Value};
+
+/// Test audiobook progress is computed from minutes listened
+#[actix_web::test]
+async fn test_audiobook_progress_by_duration() {
+    let test_db = common::setup_test_db();
+    let app = test::init_service(create_app(test_db.pool.clone())).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(json!({
+            "title": "Project Hail Mary",
+            "author": "Andy Weir",
+            "format": "audiobook",
+            "duration_minutes": 960
+        }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+    let book: Value = test::read_body_json(resp).await;
+    assert_eq!(book["format"], "audiobook");
+    let book_id = book["id"].as_i64().unwrap();
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/books/{}/status", book_id))
+        .set_json(json!({ "status": "reading", "listened_minutes": 240 }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+    let status: Value = test::read_body_json(resp).await;
+    assert_eq!(status["format"], "audiobook");
+    assert_eq!(status["listened_minutes"], 240);
+    assert_eq!(status["reading_progress"], 25.0);
+
+    // Listening beyond the total duration is rejected
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/books/{}/status", book_id))
+        .set_json(json!({ "listened_minutes": 1000 }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 422);
+}
+
+/// Test ebook progress by location and by explicit percentage
+#[actix_web::test]
+async fn test_ebook_progress_by_location_or_percentage() {
+    let test_db = common::setup_test_db();
+    let app = test::init_service(create_app(test_db.pool.clone())).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(json!({ "title": "Dune", "author": "Frank Herbert", "format": "ebook" }))
+        .to_request();
+    let book: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    let book_id = book["id"].as_i64().unwrap();
+
+    // Without total locations, an explicit percentage is used
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/books/{}/status", book_id))
+        .set_json(json!({ "status": "reading", "reading_progress": 12.5 }))
+        .to_request();
+    let status: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(status["reading_progress"], 12.5);
+
+    // Once total locations are known, progress follows the location
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/books/{}", book_id))
+        .set_json(json!({ "total_locations": 8000 }))
+        .to_request();
+    assert!(test::call_service(&app, req).await.status().is_success());
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/books/{}/status", book_id))
+        .set_json(json!({ "current_location": 2000 }))
+        .to_request();
+    let status: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(status["reading_progress"], 25.0);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/books/{}/status", book_id))
+        .to_request();
+    let status: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(status["current_location"], 2000);
+    assert_eq!(status["total_locations"], 8000);
+}
+
+/// Test paper books keep page-based progress and reject invalid formats
+#[actix_web::test]
+async fn test_paper_progress_and_format_validation() {
+    let test_db = common::setup_test_db();
+    let app = test::init_service(create_app(test_db.pool.clone())).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(json!({ "title": "Effective Java", "author": "Joshua Bloch", "page_count": 400 }))
+        .to_request();
+    let book: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(book["format"], "paperback");
+    let book_id = book["id"].as_i64().unwrap();
+
+    // No status recorded yet
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/books/{}/status", book_id))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/books/{}/status", book_id))
+        .set_json(json!({ "status": "reading", "current_page": 100 }))
+        .to_request();
+    let status: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(status["reading_progress"], 25.0);
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/books/{}", book_id))
+        .set_json(json!({ "format": "cassette" }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 422);
+}
@@ -0,0 +1,101 @@
+//! Integration tests for similar books and next-read recommendations
+
+mod common;
+
+use actix_web::test;
+use reading_notes_backend::create_app;
+use serde_json::{json, Value};
+
+/// Test that similar books are ranked by tag overlap and shared authors
+#[actix_web::test]
+async fn test_similar_books() {
+    let test_db = common::setup_test_db();
+    let app = test::init_service(create_app(test_db.pool.clone())).await;
+
+    let mut ids = Vec::new();
+    for (title, author, tags) in [
+        ("Effective Java", "Joshua Bloch", vec!["java", "best-practices"]),
+        ("Java Concurrency in Practice", "Brian Goetz", vec!["java", "best-practices"]),
+        ("Java Puzzlers", "Joshua Bloch", vec!["puzzles"]),
+        ("Dune", "Frank Herbert", vec!["scifi"]),
+    ] {
+        let req = test::TestRequest::post()
+            .uri("/api/books")
+            .set_json(json!({ "title": title, "author": author }))
+            .to_request();
+        let book: Value = test::read_body_json(test::call_service(&app, req).await).await;
+        ids.push(book["id"].as_i64().unwrap());
+
+        // Tags on notes count towards the book
+        let req = test::TestRequest::post()
+            .uri("/api/notes")
+            .set_json(json!({ "book_id": book["id"], "content": format!("Notes on {}", title) }))
+            .to_request();
+        let note: Value = test::read_body_json(test::call_service(&app, req).await).await;
+        let req = test::TestRequest::put()
+            .uri(&format!("/api/notes/{}/tags", note["id"]))
+            .set_json(json!(tags))
+            .to_request();
+        assert!(test::call_service(&app, req).await.status().is_success());
+    }
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/books/{}/similar", ids[0]))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    let similar: Value = test::read_body_json(resp).await;
+    let similar = similar.as_array().unwrap();
+
+    // Unrelated books are left out
+    assert_eq!(similar.len(), 2);
+    assert_eq!(similar[0]["book_id"], ids[1]);
+    assert_eq!(similar[0]["shared_tags"], json!(["best-practices", "java"]));
+    assert_eq!(similar[1]["book_id"], ids[2]);
+    assert_eq!(similar[1]["same_author"], true);
+
+    let req = test::TestRequest::get().uri("/api/books/999999/similar").to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+}
+
+/// Test that the to-read pile is ranked by similarity to highly rated finished books
+#[actix_web::test]
+async fn test_next_read_recommendations() {
+    let test_db = common::setup_test_db();
+    let app = test::init_service(create_app(test_db.pool.clone())).await;
+
+    let mut ids = Vec::new();
+    for (title, author, status, rating) in [
+        ("Foundation", "Isaac Asimov", "completed", Some(5)),
+        ("The Road", "Cormac McCarthy", "completed", Some(2)),
+        ("Blood Meridian", "Cormac McCarthy", "to_read", None),
+        ("I, Robot", "Isaac Asimov", "to_read", None),
+        ("Neuromancer", "William Gibson", "reading", None),
+    ] {
+        let req = test::TestRequest::post()
+            .uri("/api/books")
+            .set_json(json!({ "title": title, "author": author }))
+            .to_request();
+        let book: Value = test::read_body_json(test::call_service(&app, req).await).await;
+        ids.push(book["id"].as_i64().unwrap());
+
+        let req = test::TestRequest::put()
+            .uri(&format!("/api/books/{}/status", book["id"]))
+            .set_json(json!({ "status": status, "rating": rating }))
+            .to_request();
+        assert!(test::call_service(&app, req).await.status().is_success());
+    }
+
+    let req = test::TestRequest::get().uri("/api/recommendations/next").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    let recommendations: Value = test::read_body_json(resp).await;
+    let recommendations = recommendations.as_array().unwrap();
+
+    // Only to-read books are suggested; a poorly rated book does not seed anything
+    assert_eq!(recommendations.len(), 2);
+    assert_eq!(recommendations[0]["book_id"], ids[3]);
+    assert_eq!(recommendations[0]["because_you_liked"], json!(["Foundation"]));
+    assert_eq!(recommendations[1]["book_id"], ids[2]);
+    assert_eq!(recommendations[1]["score"], 0.0);
+}
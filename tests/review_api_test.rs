@@ -0,0 +1,118 @@
+//! Integration tests for spaced-repetition review of notes
+
+mod common;
+
+use actix_web::test;
+use reading_notes_backend::create_app;
+use serde_json::{json, Value};
+
+/// Test that grading a note schedules it and removes it from today's reviews
+#[actix_web::test]
+async fn test_grade_note_schedules_next_review() {
+    let test_db = common::setup_test_db();
+    let app = test::init_service(create_app(test_db.pool.clone())).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(json!({ "title": "Make It Stick", "author": "Peter C. Brown" }))
+        .to_request();
+    let book: Value = test::read_body_json(test::call_service(&app, req).await).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/notes")
+        .set_json(json!({ "book_id": book["id"], "content": "Retrieval practice strengthens memory", "note_type": "quote" }))
+        .to_request();
+    let note: Value = test::read_body_json(test::call_service(&app, req).await).await;
+
+    // New notes are due right away
+    let req = test::TestRequest::get().uri("/api/review/due").to_request();
+    let due: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(due["total"], 1);
+    assert_eq!(due["notes"][0]["note"]["id"], note["id"]);
+    assert!(due["notes"][0]["review"].is_null());
+
+    let today = chrono::Utc::now().date_naive();
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/review/{}", note["id"]))
+        .set_json(json!({ "grade": "good" }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    let review: Value = test::read_body_json(resp).await;
+    assert_eq!(review["repetitions"], 1);
+    assert_eq!(review["interval_days"], 1);
+    assert_eq!(review["ease_factor"], 2.5);
+    assert_eq!(review["due_date"], today.succ_opt().unwrap().to_string());
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/review/{}", note["id"]))
+        .set_json(json!({ "grade": "easy" }))
+        .to_request();
+    let review: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(review["repetitions"], 2);
+    assert_eq!(review["interval_days"], 6);
+    assert_eq!(review["ease_factor"], 2.6);
+    assert_eq!(review["last_grade"], "easy");
+
+    let req = test::TestRequest::get().uri("/api/review/due").to_request();
+    let due: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(due["total"], 0);
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/review/{}", note["id"]))
+        .set_json(json!({ "grade": "perfect" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 400);
+
+    let req = test::TestRequest::post()
+        .uri("/api/review/999999")
+        .set_json(json!({ "grade": "good" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+}
+
+/// Test that due reviews can be limited to quotes or favorites
+#[actix_web::test]
+async fn test_due_reviews_only_quotes_or_favorites() {
+    let test_db = common::setup_test_db();
+    let app = test::init_service(create_app(test_db.pool.clone())).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(json!({ "title": "Moonwalking with Einstein", "author": "Joshua Foer" }))
+        .to_request();
+    let book: Value = test::read_body_json(test::call_service(&app, req).await).await;
+
+    for (content, note_type, is_favorite) in [
+        ("Memory palace", "quote", false),
+        ("Chunking", "summary", true),
+        ("Deliberate practice", "quote", true),
+    ] {
+        let req = test::TestRequest::post()
+            .uri("/api/notes")
+            .set_json(json!({ "book_id": book["id"], "content": content, "note_type": note_type, "is_favorite": is_favorite }))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 201);
+    }
+
+    let contents = |due: &Value| -> Vec<String> {
+        due["notes"].as_array().unwrap().iter().map(|n| n["note"]["content"].as_str().unwrap().to_string()).collect()
+    };
+
+    let req = test::TestRequest::get().uri("/api/review/due?quotes_only=true").to_request();
+    let due: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(contents(&due), vec!["Memory palace", "Deliberate practice"]);
+
+    let req = test::TestRequest::get().uri("/api/review/due?favorites_only=true").to_request();
+    let due: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(contents(&due), vec!["Chunking", "Deliberate practice"]);
+
+    let req = test::TestRequest::get().uri("/api/review/due?quotes_only=true&favorites_only=true&limit=5").to_request();
+    let due: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(contents(&due), vec!["Deliberate practice"]);
+
+    let req = test::TestRequest::get().uri("/api/review/due?limit=1").to_request();
+    let due: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(due["total"], 3);
+    assert_eq!(due["notes"].as_array().unwrap().len(), 1);
+}
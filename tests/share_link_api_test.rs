@@ -0,0 +1,228 @@
+//! Integration tests for read-only share links
+
+mod common;
+
+use actix_web::test;
+use reading_notes_backend::create_app;
+use serde_json::{json, Value};
+
+/// Test that a shared book's notes can be read through its token until revoked
+#[actix_web::test]
+async fn test_share_book() {
+    let test_db = common::setup_test_db();
+    let app = test::init_service(create_app(test_db.pool.clone())).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(json!({ "title": "Thinking, Fast & Slow", "author": "Daniel Kahneman" }))
+        .to_request();
+    let book: Value = test::read_body_json(test::call_service(&app, req).await).await;
+
+    for content in ["System 1 is **fast**", "<script>alert(1)</script>System 2 is slow"] {
+        let req = test::TestRequest::post()
+            .uri("/api/notes")
+            .set_json(json!({ "book_id": book["id"], "content": content }))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 201);
+    }
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/books/{}/share", book["id"]))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+    let link: Value = test::read_body_json(resp).await;
+    let url = link["url"].as_str().unwrap().to_string();
+    assert_eq!(link["token"].as_str().unwrap().len(), 64);
+    assert_eq!(link["book_id"], book["id"]);
+    assert_eq!(link["is_expired"], false);
+
+    let req = test::TestRequest::get().uri(&url).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert!(resp.headers().get("content-type").unwrap().to_str().unwrap().starts_with("text/html"));
+    let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+    assert!(body.contains("Fast&#32;&amp;&#32;Slow"));
+    assert!(body.contains("<strong>fast</strong>"));
+    assert!(!body.contains("<script>"));
+
+    let req = test::TestRequest::get().uri(&format!("{}?format=json", url)).to_request();
+    let shared: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(shared["book"]["author"], "Daniel Kahneman");
+    assert_eq!(shared["notes"].as_array().unwrap().len(), 2);
+    assert!(shared["notes"][0].get("id").is_none());
+
+    let req = test::TestRequest::get().uri(&format!("{}?format=pdf", url)).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 422);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/shares?book_id={}", book["id"]))
+        .to_request();
+    let links: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(links.as_array().unwrap().len(), 1);
+
+    let req = test::TestRequest::delete()
+        .uri(&format!("/api/shares/{}", link["id"]))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 204);
+
+    let req = test::TestRequest::get().uri(&url).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+
+    let req = test::TestRequest::delete()
+        .uri(&format!("/api/shares/{}", link["id"]))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+}
+
+/// Test that note links stop working when expired or when the note is trashed
+#[actix_web::test]
+async fn test_share_note_expiry() {
+    let test_db = common::setup_test_db();
+    let app = test::init_service(create_app(test_db.pool.clone())).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(json!({ "title": "Nudge", "author": "Thaler and Sunstein" }))
+        .to_request();
+    let book: Value = test::read_body_json(test::call_service(&app, req).await).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/notes")
+        .set_json(json!({ "book_id": book["id"], "title": "Choice architecture", "content": "Defaults matter" }))
+        .to_request();
+    let note: Value = test::read_body_json(test::call_service(&app, req).await).await;
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/notes/{}/share", note["id"]))
+        .set_json(json!({ "expires_at": "2000-01-01T00:00:00Z" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 422);
+
+    let req = test::TestRequest::post()
+        .uri("/api/notes/999999/share")
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/notes/{}/share", note["id"]))
+        .set_json(json!({ "expires_at": "2999-01-01T00:00:00Z" }))
+        .to_request();
+    let link: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(link["note_id"], note["id"]);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("{}?format=json", link["url"].as_str().unwrap()))
+        .to_request();
+    let shared: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(shared["notes"][0]["title"], "Choice architecture");
+    assert_eq!(shared["book"]["title"], "Nudge");
+
+    // An expired link is treated like an unknown one
+    {
+        use diesel::prelude::*;
+        use reading_notes_backend::db::schema::share_links;
+        let mut conn = test_db.pool.get().unwrap();
+        diesel::update(share_links::table.find(link["id"].as_i64().unwrap()))
+            .set(share_links::expires_at.eq(chrono::Utc::now() - chrono::Duration::minutes(1)))
+            .execute(&mut conn)
+            .unwrap();
+    }
+    let req = test::TestRequest::get().uri(link["url"].as_str().unwrap()).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/shares?note_id={}", note["id"]))
+        .to_request();
+    let links: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(links[0]["is_expired"], true);
+
+    // A trashed note is no longer shared
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/notes/{}/share", note["id"]))
+        .to_request();
+    let link: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    let req = test::TestRequest::delete()
+        .uri(&format!("/api/notes/{}", note["id"]))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 204);
+    let req = test::TestRequest::get().uri(link["url"].as_str().unwrap()).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+}
+
+/// Test that images embedded in shared notes are served through the link only
+#[actix_web::test]
+async fn test_shared_attachments() {
+    const BOUNDARY: &str = "share-test-boundary";
+    const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR test image";
+
+    std::env::set_var("ATTACHMENTS_DIR", std::env::temp_dir().join("reading_notes_share_tests"));
+    let test_db = common::setup_test_db();
+    let app = test::init_service(create_app(test_db.pool.clone())).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(json!({ "title": "Envisioning Information", "author": "Edward Tufte" }))
+        .to_request();
+    let book: Value = test::read_body_json(test::call_service(&app, req).await).await;
+
+    let mut notes = Vec::new();
+    let mut attachments = Vec::new();
+    for content in ["Small multiples", "Layering and separation"] {
+        let req = test::TestRequest::post()
+            .uri("/api/notes")
+            .set_json(json!({ "book_id": book["id"], "content": content }))
+            .to_request();
+        let note: Value = test::read_body_json(test::call_service(&app, req).await).await;
+
+        let mut body = format!(
+            "--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"chart.png\"\r\nContent-Type: image/png\r\n\r\n"
+        )
+        .into_bytes();
+        body.extend_from_slice(PNG);
+        body.extend_from_slice(format!("\r\n--{BOUNDARY}--\r\n").as_bytes());
+        let req = test::TestRequest::post()
+            .uri(&format!("/api/notes/{}/attachments", note["id"]))
+            .insert_header(("content-type", format!("multipart/form-data; boundary={BOUNDARY}")))
+            .set_payload(body)
+            .to_request();
+        let uploaded: Value = test::read_body_json(test::call_service(&app, req).await).await;
+
+        let req = test::TestRequest::patch()
+            .uri(&format!("/api/notes/{}", note["id"]))
+            .set_json(json!({ "content": format!("{} {}", content, uploaded[0]["markdown"].as_str().unwrap()) }))
+            .to_request();
+        test::call_service(&app, req).await;
+        notes.push(note);
+        attachments.push(uploaded[0]["id"].as_i64().unwrap());
+    }
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/notes/{}/share", notes[0]["id"]))
+        .to_request();
+    let link: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    let url = link["url"].as_str().unwrap();
+
+    let req = test::TestRequest::get().uri(url).to_request();
+    let body = String::from_utf8(test::read_body(test::call_service(&app, req).await).await.to_vec()).unwrap();
+    assert!(body.contains(&format!("src=\"{}/attachments/{}\"", url, attachments[0])));
+    assert!(!body.contains("/api/attachments"));
+
+    let req = test::TestRequest::get()
+        .uri(&format!("{}/attachments/{}", url, attachments[0]))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(test::read_body(resp).await.as_ref(), PNG);
+
+    // Attachments of notes outside the link are not reachable through it
+    let req = test::TestRequest::get()
+        .uri(&format!("{}/attachments/{}", url, attachments[1]))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/s/{}/attachments/{}", "0".repeat(64), attachments[0]))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+}
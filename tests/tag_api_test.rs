@@ -489,6 +489,7 @@ async fn test_get_popular_tags() {
         page_count: None,
         cover_image: None,
         description: None,
+        ..Default::default()
     };
 
     let req = test::TestRequest::post()
@@ -607,6 +608,7 @@ async fn test_tag_usage_count_with_notes() {
         page_count: None,
         cover_image: None,
         description: None,
+        ..Default::default()
     };
 
     let req = test::TestRequest::post()
@@ -670,6 +672,7 @@ async fn test_tag_auto_creation_via_notes() {
         page_count: None,
         cover_image: None,
         description: None,
+        ..Default::default()
     };
 
     let req = test::TestRequest::post()
@@ -0,0 +1,167 @@
+//! Integration tests for merging tags and deleting tags with reassignment
+
+mod common;
+
+use actix_web::test;
+use diesel::prelude::*;
+use reading_notes_backend::create_app;
+use reading_notes_backend::db::schema::book_tags;
+use serde_json::{json, Value};
+
+/// Test that merging moves notes and books to the target without duplicates
+#[actix_web::test]
+async fn test_merge_tags() {
+    let test_db = common::setup_test_db();
+    let app = test::init_service(create_app(test_db.pool.clone())).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(json!({ "title": "The Rust Programming Language", "author": "Klabnik and Nichols" }))
+        .to_request();
+    let book: Value = test::read_body_json(test::call_service(&app, req).await).await;
+
+    let mut notes = Vec::new();
+    for tags in [json!(["rust"]), json!(["Rust编程"]), json!(["rust", "Rust编程"]), json!(["Rust编程"])] {
+        let req = test::TestRequest::post()
+            .uri("/api/notes")
+            .set_json(json!({ "book_id": book["id"], "content": "Ownership", "tags": tags }))
+            .to_request();
+        let note: Value = test::read_body_json(test::call_service(&app, req).await).await;
+        notes.push(note);
+    }
+
+    // A trashed note keeps its association and follows the merge
+    let req = test::TestRequest::delete()
+        .uri(&format!("/api/notes/{}", notes[3]["id"]))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 204);
+
+    let req = test::TestRequest::get().uri("/api/tags?per_page=100").to_request();
+    let tags: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    let tag_id = |name: &str| {
+        tags["tags"].as_array().unwrap().iter().find(|t| t["name"] == name).unwrap()["id"].as_i64().unwrap()
+    };
+    let (target_id, source_id) = (tag_id("rust"), tag_id("Rust编程"));
+
+    {
+        let mut conn = test_db.pool.get().unwrap();
+        let book_id = book["id"].as_i64().unwrap();
+        diesel::insert_into(book_tags::table)
+            .values(&vec![
+                (book_tags::book_id.eq(book_id), book_tags::tag_id.eq(target_id)),
+                (book_tags::book_id.eq(book_id), book_tags::tag_id.eq(source_id)),
+            ])
+            .execute(&mut conn)
+            .unwrap();
+    }
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/tags/{}/merge", source_id))
+        .set_json(json!({ "target_id": target_id }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    let merged: Value = test::read_body_json(resp).await;
+    assert_eq!(merged["id"], target_id);
+    assert_eq!(merged["note_count"], 3);
+    assert_eq!(merged["book_count"], 1);
+    assert_eq!(merged["usage_count"], 4);
+
+    for note in &notes[1..3] {
+        let req = test::TestRequest::get()
+            .uri(&format!("/api/notes/{}", note["id"]))
+            .to_request();
+        let note: Value = test::read_body_json(test::call_service(&app, req).await).await;
+        assert_eq!(note["tags"], json!(["rust"]));
+    }
+
+    let req = test::TestRequest::get().uri(&format!("/api/tags/{}", source_id)).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/notes/{}/restore", notes[3]["id"]))
+        .to_request();
+    let restored: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(restored["tags"], json!(["rust"]));
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/tags/{}/merge", target_id))
+        .set_json(json!({ "target_id": target_id }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 422);
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/tags/{}/merge", target_id))
+        .set_json(json!({ "target_id": 999999 }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+}
+
+/// Test that deleting a tag trashes or reassigns its associations
+#[actix_web::test]
+async fn test_delete_tag_with_reassign() {
+    let test_db = common::setup_test_db();
+    let app = test::init_service(create_app(test_db.pool.clone())).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(json!({ "title": "Deep Work", "author": "Cal Newport" }))
+        .to_request();
+    let book: Value = test::read_body_json(test::call_service(&app, req).await).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/notes")
+        .set_json(json!({ "book_id": book["id"], "content": "Shallow work", "tags": ["draft", "todo", "later"] }))
+        .to_request();
+    let note: Value = test::read_body_json(test::call_service(&app, req).await).await;
+
+    let req = test::TestRequest::get().uri("/api/tags?per_page=100").to_request();
+    let tags: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    let tag_id = |name: &str| {
+        tags["tags"].as_array().unwrap().iter().find(|t| t["name"] == name).unwrap()["id"].as_i64().unwrap()
+    };
+
+    let req = test::TestRequest::delete()
+        .uri(&format!("/api/tags/{}?reassign_to={}", tag_id("todo"), tag_id("todo")))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 422);
+
+    let req = test::TestRequest::delete()
+        .uri(&format!("/api/tags/{}?reassign_to={}", tag_id("todo"), tag_id("later")))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 204);
+
+    // Without reassignment the associations are trashed with the tag
+    let req = test::TestRequest::delete()
+        .uri(&format!("/api/tags/{}", tag_id("draft")))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 204);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/notes/{}", note["id"]))
+        .to_request();
+    let current: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(current["tags"], json!(["later"]));
+
+    let req = test::TestRequest::get().uri(&format!("/api/tags/{}", tag_id("later"))).to_request();
+    let later: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(later["note_count"], 1);
+
+    let draft_usage: Option<i32> = {
+        use diesel::prelude::*;
+        use reading_notes_backend::db::schema::tags;
+        let mut conn = test_db.pool.get().unwrap();
+        tags::table.find(tag_id("draft")).select(tags::usage_count).first(&mut conn).unwrap()
+    };
+    assert_eq!(draft_usage, Some(0));
+
+    // Restoring brings the trashed associations back, but not reassigned ones
+    for name in ["draft", "todo"] {
+        let req = test::TestRequest::post()
+            .uri(&format!("/api/tags/{}/restore", tag_id(name)))
+            .to_request();
+        let restored: Value = test::read_body_json(test::call_service(&app, req).await).await;
+        assert_eq!(restored["note_count"], if name == "draft" { 1 } else { 0 });
+        assert_eq!(restored["usage_count"], restored["note_count"]);
+    }
+}
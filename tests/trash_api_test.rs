@@ -0,0 +1,214 @@
+//! Integration tests for the trash: listing, restoring and purging
+//! soft deleted books, notes and tags
+
+mod common;
+
+use actix_web::test;
+use reading_notes_backend::create_app;
+use serde_json::{json, Value};
+
+/// Test deleted records appear in the trash and can be restored
+#[actix_web::test]
+async fn test_trash_list_and_restore() {
+    let test_db = common::setup_test_db();
+    let app = test::init_service(create_app(test_db.pool.clone())).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(json!({ "title": "Clean Code", "author": "Robert C. Martin" }))
+        .to_request();
+    let book: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    let book_id = book["id"].as_i64().unwrap();
+
+    let req = test::TestRequest::post()
+        .uri("/api/notes")
+        .set_json(json!({ "book_id": book_id, "content": "Functions should do one thing." }))
+        .to_request();
+    let note: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    let note_id = note["id"].as_i64().unwrap();
+
+    let req = test::TestRequest::delete().uri(&format!("/api/notes/{}", note_id)).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 204);
+    let req = test::TestRequest::delete().uri(&format!("/api/books/{}", book_id)).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 204);
+
+    let req = test::TestRequest::get().uri("/api/trash").to_request();
+    let trash: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(trash["books"][0]["id"], book_id);
+    assert_eq!(trash["books"][0]["label"], "Clean Code");
+    assert!(trash["books"][0]["deleted_at"].is_string());
+    assert_eq!(trash["notes"][0]["id"], note_id);
+    assert_eq!(trash["notes"][0]["label"], "Functions should do one thing.");
+
+    // The note cannot come back while its book is in the trash
+    let req = test::TestRequest::post().uri(&format!("/api/notes/{}/restore", note_id)).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 409);
+
+    let req = test::TestRequest::post().uri(&format!("/api/books/{}/restore", book_id)).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+
+    let req = test::TestRequest::post().uri(&format!("/api/notes/{}/restore", note_id)).to_request();
+    assert!(test::call_service(&app, req).await.status().is_success());
+
+    let req = test::TestRequest::get().uri(&format!("/api/notes/{}", note_id)).to_request();
+    assert!(test::call_service(&app, req).await.status().is_success());
+
+    // Restoring a record that is not in the trash is a 404
+    let req = test::TestRequest::post().uri(&format!("/api/books/{}/restore", book_id)).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+}
+
+/// Test tag restore is rejected when an active tag took its slug
+#[actix_web::test]
+async fn test_restore_tag_slug_collision() {
+    let test_db = common::setup_test_db();
+    let app = test::init_service(create_app(test_db.pool.clone())).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/tags")
+        .set_json(json!({ "name": "Rust" }))
+        .to_request();
+    let tag: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    let tag_id = tag["id"].as_i64().unwrap();
+
+    let req = test::TestRequest::delete().uri(&format!("/api/tags/{}", tag_id)).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 204);
+
+    let req = test::TestRequest::post()
+        .uri("/api/tags")
+        .set_json(json!({ "name": "rust" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 201);
+
+    let req = test::TestRequest::post().uri(&format!("/api/tags/{}/restore", tag_id)).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 409);
+    let body: Value = test::read_body_json(resp).await;
+    assert_eq!(body["error"], "CONFLICT");
+}
+
+/// Test purging removes records permanently and only from the trash
+#[actix_web::test]
+async fn test_purge_trash_items() {
+    let test_db = common::setup_test_db();
+    let app = test::init_service(create_app(test_db.pool.clone())).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(json!({ "title": "Refactoring", "author": "Martin Fowler" }))
+        .to_request();
+    let book: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    let book_id = book["id"].as_i64().unwrap();
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/books/{}/status", book_id))
+        .set_json(json!({ "status": "reading" }))
+        .to_request();
+    assert!(test::call_service(&app, req).await.status().is_success());
+
+    // Active records cannot be purged
+    let req = test::TestRequest::delete().uri(&format!("/api/trash/books/{}", book_id)).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+
+    let req = test::TestRequest::delete().uri(&format!("/api/books/{}", book_id)).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 204);
+
+    let req = test::TestRequest::delete().uri(&format!("/api/trash/books/{}", book_id)).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 204);
+
+    let req = test::TestRequest::get().uri("/api/trash").to_request();
+    let trash: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert!(trash["books"].as_array().unwrap().is_empty());
+
+    let req = test::TestRequest::post().uri(&format!("/api/books/{}/restore", book_id)).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+
+    let req = test::TestRequest::delete().uri("/api/trash/widgets/1").to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+}
+
+/// Test deleting a book cascades to its notes and status, and restoring the
+/// book brings back only what was deleted with it
+#[actix_web::test]
+async fn test_book_delete_cascades_and_restores_batch() {
+    let test_db = common::setup_test_db();
+    let app = test::init_service(create_app(test_db.pool.clone())).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(json!({ "title": "The Pragmatic Programmer", "author": "Hunt & Thomas" }))
+        .to_request();
+    let book: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    let book_id = book["id"].as_i64().unwrap();
+
+    let mut note_ids = Vec::new();
+    for content in ["Care about your craft.", "Don't live with broken windows.", "DRY."] {
+        let req = test::TestRequest::post()
+            .uri("/api/notes")
+            .set_json(json!({ "book_id": book_id, "content": content, "tags": ["craft"] }))
+            .to_request();
+        let note: Value = test::read_body_json(test::call_service(&app, req).await).await;
+        note_ids.push(note["id"].as_i64().unwrap());
+    }
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/books/{}/status", book_id))
+        .set_json(json!({ "status": "reading" }))
+        .to_request();
+    assert!(test::call_service(&app, req).await.status().is_success());
+
+    // One note is deleted on its own before the book
+    let req = test::TestRequest::delete().uri(&format!("/api/notes/{}", note_ids[2])).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 204);
+
+    let req = test::TestRequest::delete().uri(&format!("/api/books/{}", book_id)).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 204);
+
+    // Notes of the deleted book are no longer listed or counted
+    let req = test::TestRequest::get().uri("/api/notes").to_request();
+    let notes: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(notes["total"], 0);
+
+    let req = test::TestRequest::get().uri("/api/tags?search=craft").to_request();
+    let tags: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(tags["tags"][0]["note_count"], 0);
+    assert_eq!(tags["tags"][0]["usage_count"], 0);
+
+    let req = test::TestRequest::get().uri("/api/trash").to_request();
+    let trash: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    let book_batch = trash["books"][0]["deletion_batch_id"].clone();
+    assert!(book_batch.is_string());
+    let notes_in_batch = trash["notes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|n| n["deletion_batch_id"] == book_batch)
+        .count();
+    assert_eq!(notes_in_batch, 2);
+
+    let req = test::TestRequest::post().uri(&format!("/api/books/{}/restore", book_id)).to_request();
+    assert!(test::call_service(&app, req).await.status().is_success());
+
+    let req = test::TestRequest::get().uri(&format!("/api/books/{}/notes", book_id)).to_request();
+    let notes: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(notes["total"], 2);
+    assert_eq!(notes["notes"][0]["tags"], json!(["craft"]));
+
+    let req = test::TestRequest::get().uri(&format!("/api/books/{}/status", book_id)).to_request();
+    let status: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(status["status"], "reading");
+
+    // The individually deleted note stays in the trash until restored itself
+    let req = test::TestRequest::get().uri(&format!("/api/notes/{}", note_ids[2])).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+
+    let req = test::TestRequest::post().uri(&format!("/api/notes/{}/restore", note_ids[2])).to_request();
+    let note: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(note["tags"], json!(["craft"]));
+
+    let req = test::TestRequest::get().uri("/api/tags?search=craft").to_request();
+    let tags: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(tags["tags"][0]["note_count"], 3);
+    assert_eq!(tags["tags"][0]["usage_count"], 3);
+}